@@ -1,3 +1,7 @@
+// Tasks are spawned through the runtime backend selected by deebs' features
+#[doc(hidden)]
+pub use deebs::runtime;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u64)]
 pub enum Method {
@@ -9,11 +13,13 @@ pub enum Method {
 }
 
 /// Block on a future, instrumenting it with the current tracing span
+///
+/// Unlike `deebs::block_on`, this doesn't check for an enclosing task, so it can be used from inside one
 #[macro_export]
 macro_rules! block {
     ($fut:expr) => {
         let span = tracing::info_span!("block", method = $crate::Method::Block as u64);
-        async_std::task::block(($fut).instrument(span))
+        $crate::runtime::block_on_unchecked(($fut).instrument(span))
     };
 }
 
@@ -23,7 +29,7 @@ macro_rules! spawn {
     ($fut:expr) => {{
         let span = tracing::info_span!("spawn", method = $crate::Method::Spawn as u64);
         span.follows_from(tracing::span::Span::current());
-        async_std::task::spawn(($fut).instrument(span))
+        $crate::runtime::spawn(($fut).instrument(span))
     }};
 }
 
@@ -60,7 +66,7 @@ macro_rules ! parallel {
         {
             let span = tracing::info_span!("parallel", method = $crate::Method::Parallel as u64);
             futures::join!($(
-                async_std::task::spawn(($fut).instrument(span.clone())),
+                $crate::runtime::spawn(($fut).instrument(span.clone())),
             )*);
         }
     };
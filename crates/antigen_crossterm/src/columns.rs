@@ -0,0 +1,11 @@
+use borrow_derive::Borrow;
+use deebs::{macros::SubTable, Column, Singleton};
+
+/// The singletons and columns used by the [`crossterm`] systems,
+/// to be held by a table as a `#[flatten]` field.
+#[derive(Debug, Default, Borrow, SubTable)]
+pub struct CrosstermColumns {
+    events: Singleton<crate::CrosstermEvents>,
+
+    key_events: Column<crate::CrosstermKeyEvents>,
+}
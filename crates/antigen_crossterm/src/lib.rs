@@ -1,11 +1,13 @@
 //! [`crossterm`] event handling for `antigen`
 
+mod columns;
 mod events;
 mod key_events;
 mod macros;
 mod mouse_events;
 mod resize_events;
 
+pub use columns::*;
 pub use events::*;
 pub use key_events::*;
 pub use macros::*;
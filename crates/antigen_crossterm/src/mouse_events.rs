@@ -88,15 +88,16 @@ pub async fn run_mouse_events_system<T>(table: Arc<T>)
 where
     T: Table + BorrowSingleton<CrosstermEvents> + BorrowColumn<CrosstermMouseEvents> + Send + Sync,
 {
-    let events = ReadSingleton::new(table.deref()).await;
+    let events = ReadSingleton::<CrosstermEvents>::new(table.deref()).await;
 
     let mut stream = table.keys::<CrosstermMouseEvents>().await;
     while let Some(key) = stream.next().await {
         let mut mouse_events = table.get_mut::<CrosstermMouseEvents>(&key).await.unwrap();
-        for event in events.iter() {
+        for event in events.current() {
             if let crossterm::event::Event::Mouse(mouse) = event {
                 mouse_events.push(*mouse);
             }
         }
+        mouse_events.release().await;
     }
 }
@@ -42,15 +42,16 @@ pub async fn run_resize_events_system<T>(table: Arc<T>)
 where
     T: Table + BorrowSingleton<CrosstermEvents> + BorrowColumn<CrosstermResizeEvents> + Send + Sync,
 {
-    let events = ReadSingleton::new(table.deref()).await;
+    let events = ReadSingleton::<CrosstermEvents>::new(table.deref()).await;
 
     let mut stream = table.keys::<CrosstermResizeEvents>().await;
     while let Some(key) = stream.next().await {
         let mut resize_events = table.get_mut::<CrosstermResizeEvents>(&key).await.unwrap();
-        for event in events.iter() {
+        for event in events.current() {
             if let crossterm::event::Event::Resize(width, height) = event {
                 resize_events.push((*width, *height));
             }
         }
+        resize_events.release().await;
     }
 }
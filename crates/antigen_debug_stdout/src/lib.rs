@@ -13,8 +13,8 @@ use antigen_crossterm::{
 use async_std::{io::prelude::WriteExt, sync::Arc};
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, ToCell};
 use deebs::{
-    macros::CommonKeys, macros::Row, BorrowColumn, BorrowSingleton, BorrowView, CommonKeys, Map,
-    ReadView, Row, Table, ToStringMapper, WriteCell, WriteSingleton,
+    macros::CommonKeys, macros::Row, BorrowColumn, BorrowSingleton, BorrowView, CommonKeys,
+    EventReader, Map, ReadCell, ReadView, Row, Table, ToStringMapper, WriteCell, WriteSingleton,
 };
 use futures::StreamExt;
 
@@ -25,26 +25,38 @@ use antigen_crossterm::crossterm::{
 
 #[derive(Row, CommonKeys)]
 struct StdoutDebugRow<'a> {
-    key_events: WriteCell<'a, CrosstermKeyEvents>,
+    key_events: ReadCell<'a, CrosstermKeyEvents>,
+    key_event_reader: WriteCell<'a, EventReader<KeyEvent>>,
 }
 
 #[cfg_attr(feature = "tracing", tracing::instrument(skip(table)))]
 pub async fn run<D, T>(table: Arc<T>)
 where
-    T: Table + BorrowSingleton<D> + BorrowColumn<CrosstermKeyEvents> + Send + Sync,
+    T: Table
+        + BorrowSingleton<D>
+        + BorrowColumn<CrosstermKeyEvents>
+        + BorrowColumn<EventReader<KeyEvent>>
+        + Send
+        + Sync,
     D: StdoutDebug<T> + Send + Sync + 'static,
 {
     let mut debug = WriteSingleton::<D>::new(table.deref()).await;
 
     let mut stream = StdoutDebugRow::common_keys(table.deref()).await;
     while let Some(key) = stream.next().await {
-        let StdoutDebugRow { mut key_events } = StdoutDebugRow::new(table.deref(), &key).await;
+        let StdoutDebugRow {
+            key_events,
+            mut key_event_reader,
+        } = StdoutDebugRow::new(table.deref(), &key).await;
 
-        for key_event in key_events.drain(..) {
-            StdoutDebug::handle_key_input(debug.deref_mut(), key_event).await;
+        for key_event in key_event_reader.read(&key_events) {
+            StdoutDebug::handle_key_input(debug.deref_mut(), *key_event).await;
         }
         StdoutDebug::run(debug.deref(), table.clone()).await;
     }
+
+    drop(debug);
+    StdoutDebugRow::notify_observers(table.deref()).await;
 }
 
 #[async_trait::async_trait]
@@ -4,10 +4,12 @@
 
 mod egui_render_pass;
 mod egui_user_interface;
+mod reflect;
 mod widgets;
 
 pub use egui_render_pass::*;
 pub use egui_user_interface::*;
+pub use reflect::*;
 pub use widgets::*;
 
 #[cfg(feature = "clipboard")]
@@ -265,4 +267,5 @@ where
             }
         }
     }
+    HandleEventsRow::<T, F>::notify_observers(table.deref()).await;
 }
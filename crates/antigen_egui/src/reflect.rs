@@ -0,0 +1,140 @@
+//! [`egui::Widget`] vtables for [`deebs::reflect`], filled in by tables deriving with `#[reflect_egui]`.
+
+use std::any::TypeId;
+
+use deebs::{
+    reflect::{try_edit_cell, try_with_cell, ColumnInfo, ColumnRegistry, Probe},
+    BorrowColumn, CellError, Key, Table,
+};
+
+/// A type-erased widget for one cell.
+pub type CellWidgetFn<Tbl> = fn(&Tbl, &Key, &mut egui::Ui) -> Result<egui::Response, CellError>;
+
+/// The widgets available for a column, stored as a [`ColumnInfo`] extension.
+///
+/// `view` requires `&T: egui::Widget` and `edit` requires `&mut T: egui::Widget`,
+/// matching the bounds used by `#[derive(Widgets)]`.
+pub struct CellWidget<Tbl> {
+    pub view: Option<CellWidgetFn<Tbl>>,
+    pub edit: Option<CellWidgetFn<Tbl>>,
+}
+
+impl<Tbl> Clone for CellWidget<Tbl> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tbl> Copy for CellWidget<Tbl> {}
+
+impl<Tbl> CellWidget<Tbl>
+where
+    Tbl: 'static,
+{
+    /// Show the cell at `key` in `column`, preferring its editable widget,
+    /// and falling back to its `Debug` representation.
+    pub fn show(
+        table: &Tbl,
+        column: &ColumnInfo<Tbl>,
+        key: &Key,
+        ui: &mut egui::Ui,
+    ) -> Result<Option<egui::Response>, CellError> {
+        let widget = column.extension::<CellWidget<Tbl>>();
+
+        if let Some(f) = widget.and_then(|widget| widget.edit.or(widget.view)) {
+            return f(table, key, ui).map(Some);
+        }
+
+        match column.debug {
+            Some(f) => f(table, key).map(|string| Some(ui.label(string))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Implemented by the `Table` derive for tables marked `#[reflect_egui]`.
+pub trait ReflectWidgets: Sized + 'static {
+    /// Return the widgets of each column, keyed by cell type.
+    fn cell_widgets() -> Vec<(TypeId, CellWidget<Self>)>;
+
+    /// Attach each column's [`CellWidget`] to its entry in `registry`.
+    fn insert_cell_widgets(registry: &mut ColumnRegistry<Self>) {
+        for (type_id, widget) in Self::cell_widgets() {
+            if let Some(column) = registry.iter_mut().find(|column| column.type_id == type_id) {
+                column.insert_extension(widget);
+            }
+        }
+    }
+}
+
+fn view_widget<Tbl, T>(
+    table: &Tbl,
+    key: &Key,
+    ui: &mut egui::Ui,
+) -> Result<egui::Response, CellError>
+where
+    Tbl: Table + BorrowColumn<T>,
+    for<'a> &'a T: egui::Widget,
+{
+    try_with_cell(table, key, |cell: &T| ui.add(cell))
+}
+
+fn edit_widget<Tbl, T>(
+    table: &Tbl,
+    key: &Key,
+    ui: &mut egui::Ui,
+) -> Result<egui::Response, CellError>
+where
+    Tbl: Table + BorrowColumn<T>,
+    for<'a> &'a mut T: egui::Widget,
+{
+    try_edit_cell(table, key, |cell: &mut T| {
+        let response = ui.add(cell);
+        let changed = response.changed();
+        (response, changed)
+    })
+}
+
+pub trait ProbeViewWidget<Tbl> {
+    fn view_widget_fn(&self) -> Option<CellWidgetFn<Tbl>>;
+}
+
+impl<Tbl, T> ProbeViewWidget<Tbl> for Probe<Tbl, T>
+where
+    Tbl: Table + BorrowColumn<T>,
+    for<'a> &'a T: egui::Widget,
+{
+    fn view_widget_fn(&self) -> Option<CellWidgetFn<Tbl>> {
+        Some(view_widget::<Tbl, T>)
+    }
+}
+
+pub trait ProbeNoViewWidget<Tbl> {
+    fn view_widget_fn(&self) -> Option<CellWidgetFn<Tbl>> {
+        None
+    }
+}
+
+impl<Tbl, T> ProbeNoViewWidget<Tbl> for &Probe<Tbl, T> {}
+
+pub trait ProbeEditWidget<Tbl> {
+    fn edit_widget_fn(&self) -> Option<CellWidgetFn<Tbl>>;
+}
+
+impl<Tbl, T> ProbeEditWidget<Tbl> for Probe<Tbl, T>
+where
+    Tbl: Table + BorrowColumn<T>,
+    for<'a> &'a mut T: egui::Widget,
+{
+    fn edit_widget_fn(&self) -> Option<CellWidgetFn<Tbl>> {
+        Some(edit_widget::<Tbl, T>)
+    }
+}
+
+pub trait ProbeNoEditWidget<Tbl> {
+    fn edit_widget_fn(&self) -> Option<CellWidgetFn<Tbl>> {
+        None
+    }
+}
+
+impl<Tbl, T> ProbeNoEditWidget<Tbl> for &Probe<Tbl, T> {}
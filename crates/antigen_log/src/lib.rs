@@ -1,6 +1,7 @@
 use std::{
     fmt::Display,
     ops::{Deref, DerefMut},
+    sync::Mutex,
     time::SystemTime,
 };
 
@@ -12,11 +13,17 @@ use log::{Level, Log, Metadata};
 #[derive(Debug)]
 pub struct Logger<T> {
     table: Arc<T>,
+    /// Records logged while [`LogRecords`] was locked,
+    /// flushed by the next log call that finds it free.
+    pending: Mutex<Vec<LogRecord>>,
 }
 
 impl<T> Logger<T> {
     pub fn new(table: Arc<T>) -> Self {
-        Logger { table }
+        Logger {
+            table,
+            pending: Default::default(),
+        }
     }
 }
 
@@ -114,10 +121,13 @@ where
     }
 
     fn log(&self, record: &log::Record) {
-        async_std::task::block_on(async move {
-            let mut records = WriteSingleton::<LogRecords>::new(self.table.deref()).await;
-            records.push(record.into());
-        });
+        // Logging may happen inside executor tasks, so never wait on the records singleton
+        let mut pending = self.pending.lock().expect("Pending log records are poisoned.");
+        pending.push(record.into());
+
+        if let Some(mut records) = WriteSingleton::<LogRecords>::try_new(self.table.deref()) {
+            records.append(&mut pending);
+        }
     }
 
     fn flush(&self) {}
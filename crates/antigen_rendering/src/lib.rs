@@ -89,5 +89,6 @@ where
     while let Some(key) = stream.next().await {
         let RedrawRow { mut flag, .. } = RedrawRow::new(table.deref(), &key).await;
         **flag = true;
+        flag.release().await;
     }
 }
@@ -16,7 +16,7 @@ impl TraceExecution {
     where
         T: Table + BorrowSingleton<TraceRoot>,
     {
-        let trace_root = async_std::task::block_on(WriteSingleton::<TraceRoot>::new(table.deref()));
+        let trace_root = WriteSingleton::<TraceRoot>::blocking(table.deref());
         let sender = trace_root.sender();
         TraceExecution { sender }
     }
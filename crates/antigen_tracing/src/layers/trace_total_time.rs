@@ -40,7 +40,7 @@ impl TraceTotalTime {
     where
         T: Table + BorrowSingleton<TraceRoot>,
     {
-        let trace_root = async_std::task::block_on(ReadSingleton::new(table.deref()));
+        let trace_root = ReadSingleton::blocking(table.deref());
         let sender = trace_root.sender();
         TraceTotalTime { sender }
     }
@@ -0,0 +1,16 @@
+use borrow_derive::Borrow;
+use deebs::{macros::SubTable, Column, Singleton};
+
+/// The singletons and columns used by the [`wgpu`] systems,
+/// to be held by a table as a `#[flatten]` field.
+#[derive(Debug, Default, Borrow, SubTable)]
+pub struct WgpuColumns {
+    instance: Singleton<crate::WgpuInstance>,
+
+    devices: Column<crate::WgpuDevice>,
+    queues: Column<crate::WgpuQueue>,
+    swap_chain_frames: Column<crate::WgpuSwapChainFrame>,
+    texture_views: Column<crate::WgpuTextureView>,
+    renderers: Column<crate::WgpuRenderer>,
+    command_buffers: Column<crate::WgpuCommandBuffers>,
+}
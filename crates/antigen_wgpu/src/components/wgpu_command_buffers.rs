@@ -5,7 +5,7 @@ use std::{
 
 use antigen_rendering::RedrawFlag;
 use async_std::sync::Arc;
-use deebs::{macros::CommonKeys, macros::Row, BorrowColumn, CommonKeys, Row, Table, WriteCell};
+use deebs::{macros::Row, BorrowColumn, Row, Table, WriteCell};
 use futures::StreamExt;
 use wgpu::CommandBuffer;
 
@@ -48,6 +48,7 @@ where
             .await
             .unwrap();
         command_buffers.clear();
+        command_buffers.release().await;
     }
 }
 
@@ -74,18 +75,18 @@ where
         .await
         .unwrap();
 
-    #[derive(Row, CommonKeys)]
+    #[derive(Row)]
     struct FlushRow<'a> {
         command_buffers: WriteCell<'a, WgpuCommandBuffers>,
         redraw_flag: Option<WriteCell<'a, RedrawFlag>>,
     }
 
-    let mut stream = FlushRow::common_keys(table.deref()).await;
-    while let Some(key) = stream.next().await {
+    let mut rows = FlushRow::query(table.deref()).await;
+    while let Some(row) = rows.next().await {
         let FlushRow {
             mut command_buffers,
             redraw_flag,
-        } = FlushRow::new(table.deref(), &key).await;
+        } = row;
 
         if !command_buffers.is_empty() {
             queue.submit(command_buffers.drain(..));
@@ -95,4 +96,8 @@ where
             }
         }
     }
+
+    drop(rows);
+    drop(queue);
+    FlushRow::notify_observers(table.deref()).await;
 }
@@ -1,5 +1,5 @@
 use async_std::sync::Arc;
-use deebs::{macros::{Row, CommonKeys}, BorrowColumn, CommonKeys, ReadCell, Row, Table, WriteCell};
+use deebs::{macros::Row, BorrowColumn, ReadCell, Row, Table, WriteCell};
 use futures::StreamExt;
 
 use std::{
@@ -99,7 +99,7 @@ where
         + Sync,
     F: Render + Send + Sync + 'static,
 {
-    #[derive(Row, CommonKeys)]
+    #[derive(Row)]
     struct RenderRow<'a, F>
     where
         F: Render + Send + Sync + 'static,
@@ -109,16 +109,19 @@ where
         command_buffers: WriteCell<'a, WgpuCommandBuffers>,
     }
 
-    let mut stream = RenderRow::<F>::common_keys(table.deref()).await;
-    while let Some(key) = stream.next().await {
+    let mut rows = RenderRow::<F>::query(table.deref()).await;
+    while let Some(row) = rows.next().await {
         let RenderRow {
             texture_view,
             mut renderer,
             mut command_buffers,
-        } = RenderRow::<F>::new(table.deref(), &key).await;
+        } = row;
 
         if let WgpuTextureView::Ready(texture_view) = texture_view.deref() {
             command_buffers.push(renderer.render(texture_view));
         }
     }
+
+    drop(rows);
+    RenderRow::<F>::notify_observers(table.deref()).await;
 }
@@ -1,7 +1,9 @@
 //! [`wgpu`] integration for `antigen`
 
+mod columns;
 mod components;
 
+pub use columns::*;
 pub use components::*;
 
 pub use wgpu;
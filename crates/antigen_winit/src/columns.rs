@@ -0,0 +1,14 @@
+use borrow_derive::Borrow;
+use deebs::{macros::SubTable, Column, Singleton};
+
+/// The singletons and columns used by the [`winit`] systems,
+/// to be held by a table as a `#[flatten]` field.
+#[derive(Debug, Default, Borrow, SubTable)]
+pub struct WinitColumns {
+    window_pool: Singleton<crate::WinitWindows>,
+    main_events: Singleton<crate::WinitMainEvents>,
+    redraw_events: Singleton<crate::WinitRedrawEvents>,
+
+    window_events: Column<crate::WinitWindowEvents>,
+    windows: Column<crate::WinitWindow>,
+}
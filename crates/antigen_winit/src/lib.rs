@@ -1,7 +1,9 @@
 //! [`winit`] integration for `antigen`
 
+mod columns;
 mod systems;
 mod window;
 
+pub use columns::*;
 pub use systems::*;
 pub use window::*;
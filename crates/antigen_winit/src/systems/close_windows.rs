@@ -1,24 +1,32 @@
 use std::{ops::Deref, sync::Arc};
 
-use deebs::{BorrowColumn, BorrowSingleton, ReadSingleton, Table};
+use deebs::{BorrowColumn, BorrowSingleton, Commands, ReadSingleton, Table};
 use futures::StreamExt;
 
 use crate::{WinitWindow, WinitWindows};
 
+/// Despawns entities whose [`WinitWindow`] has been closed, so must run after systems that release their per-window resources such as swap chains
 #[cfg_attr(feature = "tracing", tracing::instrument(skip(table)))]
 pub async fn run_close_window_system<'a, T>(table: Arc<T>)
 where
     T: Table + BorrowSingleton<WinitWindows> + BorrowColumn<WinitWindow> + Send + Sync + 'static,
 {
-    let windows = ReadSingleton::new(table.deref()).await;
+    let commands = Commands::default();
 
-    let mut keys = table.keys::<WinitWindow>().await;
-    while let Some(key) = keys.next().await {
-        let mut winit_window = table.get_mut::<WinitWindow>(&key).await.unwrap();
-        if let WinitWindow::Ready { window_id, .. } = winit_window.deref() {
-            if !windows.contains_key(window_id) {
-                *winit_window = WinitWindow::Closed;
+    {
+        let windows = ReadSingleton::new(table.deref()).await;
+
+        let mut keys = table.keys::<WinitWindow>().await;
+        while let Some(key) = keys.next().await {
+            let winit_window = table.get::<WinitWindow>(&key).await.unwrap();
+            if let WinitWindow::Ready { window_id, .. } = winit_window.deref() {
+                if !windows.contains_key(window_id) {
+                    commands.despawn(key);
+                }
             }
         }
     }
+
+    // Despawn closed windows along with the rest of their entity
+    commands.flush(table.deref()).await;
 }
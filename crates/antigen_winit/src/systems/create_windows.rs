@@ -36,6 +36,8 @@ impl CreateWindowSystem {
                 out_keys.push(window_id);
             }
         }
+        drop(windows);
+        table.notify_observers::<WinitWindow>().await;
         out_keys
     }
 }
@@ -85,29 +85,20 @@ impl WinitEventLoopSystem {
                             event: WinitWindowEvent::Opened,
                         });
 
-                        // Write new events to singleton
+                        // Start a new frame of events and write this frame's to the singleton
                         {
                             let mut events =
                                 async_std::task::block_on(WriteSingleton::<WinitMainEvents>::new(
                                     table.deref(),
                                 ));
 
+                            events.update();
                             events.extend(created_windows.chain(winit.main_events.drain(..)));
                         }
 
                         // If present, invoke callback
                         async_std::task::block_on((main_event_callback)(table.clone()));
 
-                        // Write new events to singleton
-                        {
-                            let mut events =
-                                async_std::task::block_on(WriteSingleton::<WinitMainEvents>::new(
-                                    table.deref(),
-                                ));
-
-                            events.clear();
-                        }
-
                         // Proceed to the next state
                         winit.state = State::RedrawEvents;
                     } else if let Event::WindowEvent { window_id, event } = event {
@@ -126,27 +117,19 @@ impl WinitEventLoopSystem {
                 }
                 State::RedrawEvents => match event {
                     Event::RedrawEventsCleared => {
-                        // Write new events to singleton
+                        // Start a new frame of events and write this frame's to the singleton
                         {
                             let mut events =
                                 async_std::task::block_on(
                                     WriteSingleton::<WinitRedrawEvents>::new(table.deref()),
                                 );
+                            events.update();
                             events.extend(winit.redraw_events.drain(..));
                         }
 
                         // Invoke callback
                         async_std::task::block_on((redraw_event_callback)(table.clone()));
 
-                        // Clear events
-                        {
-                            let mut events =
-                                async_std::task::block_on(
-                                    WriteSingleton::<WinitRedrawEvents>::new(table.deref()),
-                                );
-                            events.clear();
-                        }
-
                         // Proceed to the next state
                         winit.state = State::Waiting;
                     }
@@ -1,8 +1,4 @@
-use std::{
-    fmt::Display,
-    ops::{Deref, DerefMut},
-};
-
+use deebs::Events;
 use winit::{event::Event, window::WindowId};
 
 use crate::WinitWindowEvent;
@@ -45,25 +41,5 @@ impl<'a> From<Event<'a, ()>> for WinitMainEvent {
     }
 }
 
-#[derive(Debug, Default)]
-pub struct WinitMainEvents(Vec<WinitMainEvent>);
-
-impl Deref for WinitMainEvents {
-    type Target = Vec<WinitMainEvent>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl DerefMut for WinitMainEvents {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
-    }
-}
-
-impl Display for WinitMainEvents {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("Winit Events")
-    }
-}
+/// Main events of the current winit frame, rotated by [`WinitEventLoopSystem`](crate::WinitEventLoopSystem)
+pub type WinitMainEvents = Events<WinitMainEvent>;
@@ -3,7 +3,7 @@ use std::ops::Deref;
 use async_std::sync::Arc;
 use deebs::{
     macros::CommonKeys, macros::Row, BorrowColumn, BorrowSingleton, CommonKeys, ReadCell,
-    ReadSingleton, Row, Table, WriteCell, WriteSingleton,
+    ReadSingleton, Row, Table, WriteCell,
 };
 use futures::StreamExt;
 
@@ -45,7 +45,7 @@ where
     }
 }
 
-/// Sets [`RedrawFlag`] to true on entities that have a [`WinitWindow`] with a pending [`WinitRedrawEvent`]
+/// Sets [`RedrawFlag`] to true on entities that have a [`WinitWindow`] with a [`WinitRedrawEvent`] this frame
 #[cfg_attr(feature = "tracing", tracing::instrument(skip(table)))]
 pub async fn run_redraw_flag_system<T>(table: Arc<T>)
 where
@@ -56,7 +56,7 @@ where
         + Send
         + Sync,
 {
-    let mut events = WriteSingleton::<WinitRedrawEvents>::new(table.deref()).await;
+    let events = ReadSingleton::<WinitRedrawEvents>::new(table.deref()).await;
 
     #[derive(Row, CommonKeys)]
     struct RedrawRow<'a> {
@@ -72,11 +72,10 @@ where
         } = RedrawRow::new(table.deref(), &key).await;
 
         if let WinitWindow::Ready { window_id, .. } = window.deref() {
-            if events.iter().any(|event| event.0 == *window_id) {
+            if events.current().iter().any(|event| event.0 == *window_id) {
                 **redraw_flag = true;
             }
         }
     }
-
-    events.clear();
+    RedrawRow::notify_observers(table.deref()).await;
 }
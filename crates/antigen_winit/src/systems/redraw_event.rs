@@ -1,12 +1,11 @@
-use std::ops::{Deref, DerefMut};
-
+use deebs::Events;
 use winit::window::WindowId;
 
 #[derive(Debug, Copy, Clone)]
 pub struct WinitRedrawEvent(pub WindowId);
 
-#[derive(Debug, Default, Clone)]
-pub struct WinitRedrawEvents(Vec<WinitRedrawEvent>);
+/// Redraw events of the current winit frame, rotated by [`WinitEventLoopSystem`](crate::WinitEventLoopSystem)
+pub type WinitRedrawEvents = Events<WinitRedrawEvent>;
 
 impl From<WindowId> for WinitRedrawEvent {
     fn from(window_id: WindowId) -> Self {
@@ -19,17 +18,3 @@ impl From<WinitRedrawEvent> for WindowId {
         event.0
     }
 }
-
-impl Deref for WinitRedrawEvents {
-    type Target = Vec<WinitRedrawEvent>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl DerefMut for WinitRedrawEvents {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
-    }
-}
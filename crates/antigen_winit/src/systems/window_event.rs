@@ -180,7 +180,7 @@ where
             WindowEventSinkRow::new(table.deref(), &key).await;
 
         if let WinitWindow::Ready { window_id, .. } = window.deref() {
-            for event in main_events.iter() {
+            for event in main_events.current() {
                 if let WinitMainEvent::WindowEvent {
                     window_id: event_window_id,
                     event,
@@ -193,4 +193,5 @@ where
             }
         }
     }
+    WindowEventSinkRow::notify_observers(table.deref()).await;
 }
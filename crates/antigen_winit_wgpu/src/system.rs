@@ -5,8 +5,8 @@ use std::ops::{Deref, DerefMut};
 use async_std::sync::Arc;
 use deebs::{
     macros::{CommonKeys, Row},
-    BorrowColumn, BorrowSingleton, CommonKeys, ReadCell, ReadSingleton, Row, Table, WriteCell,
-    WriteSingleton,
+    BorrowColumn, BorrowSingleton, CommonKeys, Parent, ReadCell, ReadSingleton, Row, Table,
+    WriteCell, WriteSingleton,
 };
 
 use antigen_winit::{WinitWindow, WinitWindows};
@@ -25,6 +25,7 @@ where
         + BorrowColumn<WgpuDevice>
         + BorrowColumn<WinitWindow>
         + BorrowColumn<WinitSwapChain>
+        + BorrowColumn<Parent>
         + Send
         + Sync,
 {
@@ -37,17 +38,13 @@ where
         return;
     };
 
-    let device = table
-        .get::<WgpuDevice>(
-            &table
-                .keys::<WgpuDevice>()
-                .await
-                .next()
-                .await
-                .expect("No Wgpu cell in table."),
-        )
+    // Swap chains without a device ancestor fall back to the first device in the table
+    let default_device_key = table
+        .keys::<WgpuDevice>()
         .await
-        .unwrap();
+        .next()
+        .await
+        .expect("No Wgpu cell in table.");
 
     #[derive(Row, CommonKeys)]
     struct MaintainSwapChainsRow<'a> {
@@ -55,8 +52,24 @@ where
         swap_chain: WriteCell<'a, WinitSwapChain>,
     }
 
-    let mut stream = MaintainSwapChainsRow::common_keys(table.deref()).await;
-    while let Some(key) = stream.next().await {
+    // Resolve each row's device before taking any row guards,
+    // so that hierarchy lookups never nest inside the row's column locks
+    let keys = MaintainSwapChainsRow::common_keys(table.deref())
+        .await
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut device_keys = Vec::with_capacity(keys.len());
+    for key in keys {
+        let device_key = table
+            .ancestor_with::<WgpuDevice>(key)
+            .await
+            .unwrap_or(default_device_key);
+        device_keys.push((key, device_key));
+    }
+
+    for (key, device_key) in device_keys {
+        let device = table.get::<WgpuDevice>(&device_key).await.unwrap();
         let MaintainSwapChainsRow {
             window,
             mut swap_chain,
@@ -112,6 +125,9 @@ where
             _ => (),
         }
     }
+
+    drop(swap_chains);
+    MaintainSwapChainsRow::notify_observers(table.deref()).await;
 }
 
 /// Create, resize or drop [`WinitSwapChain`] instances based on an associated [`WinitWindow`]
@@ -143,4 +159,7 @@ where
             }
         }
     }
+
+    drop(swap_chains);
+    table.notify_observers::<WinitSwapChain>().await;
 }
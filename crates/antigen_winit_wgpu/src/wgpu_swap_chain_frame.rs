@@ -52,6 +52,7 @@ where
             }
         }
     }
+    SwapChainFrameRow::notify_observers(table.deref()).await;
 }
 
 #[cfg_attr(feature = "tracing", tracing::instrument(skip(table)))]
@@ -65,5 +66,6 @@ where
         if let WgpuSwapChainFrame::Ready(_) = swap_chain_frame.deref() {
             *swap_chain_frame = WgpuSwapChainFrame::Dropped;
         }
+        swap_chain_frame.release().await;
     }
 }
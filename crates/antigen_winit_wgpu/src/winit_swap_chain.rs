@@ -95,4 +95,5 @@ where
             command_buffers.push(renderer.render(&frame.output.view));
         }
     }
+    RenderRow::<F>::notify_observers(table.deref()).await;
 }
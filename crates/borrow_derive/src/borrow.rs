@@ -1,5 +1,5 @@
 use quote::quote;
-use syn::{DeriveInput, Field, Fields, Index};
+use syn::{DeriveInput, Field, Fields, GenericArgument, Index, PathArguments, Type};
 
 pub fn impl_borrow(input: DeriveInput) -> proc_macro::TokenStream {
     let data = input.data;
@@ -17,14 +17,17 @@ pub fn impl_borrow(input: DeriveInput) -> proc_macro::TokenStream {
         _ => panic!("Borrow may only be derived for structs with named fields"),
     };
 
-    let should_derive_field = |field: &Field| {
+    let has_attr = |field: &Field, name: &str| {
         field
             .attrs
             .iter()
-            .any(|attr| attr.path.segments.last().unwrap().ident == "borrow")
+            .any(|attr| attr.path.segments.last().unwrap().ident == name)
     };
+    let should_derive_field =
+        |field: &Field| has_attr(field, "borrow") || has_attr(field, "flatten");
+    let (impl_generics, ty_generics, where_clause) = struct_generics.split_for_impl();
 
-    let fields = if fields.iter().any(should_derive_field) {
+    let fields = if fields.iter().any(|field| has_attr(field, "borrow")) {
         fields.into_iter().filter(should_derive_field).collect()
     } else {
         fields
@@ -35,23 +38,33 @@ pub fn impl_borrow(input: DeriveInput) -> proc_macro::TokenStream {
         .enumerate()
         .map(|(i, field)| {
             let field_tokens = if is_named {
-                let field_ident = field.ident.unwrap();
+                let field_ident = field.ident.as_ref().unwrap();
                 quote! {
-                    self.#field_ident
+                    #field_ident
                 }
             } else {
                 let index = Index::from(i);
                 quote! {
-                    self.#index
+                    #index
                 }
             };
 
+            if has_attr(&field, "flatten") {
+                return flatten_field(
+                    &field.ty,
+                    quote!(#impl_generics),
+                    quote!(#struct_ident #ty_generics),
+                    quote!(#where_clause),
+                    field_tokens,
+                );
+            }
+
             let field_type = field.ty;
 
             quote! {
                 impl #struct_generics std::borrow::Borrow<#field_type> for #struct_ident #struct_generics {
                     fn borrow(&self) -> &#field_type {
-                        &#field_tokens
+                        &self.#field_tokens
                     }
                 }
             }
@@ -60,3 +73,44 @@ pub fn impl_borrow(input: DeriveInput) -> proc_macro::TokenStream {
 
     tokens.into()
 }
+
+/// Invoke the companion macro of the bundle type `ty`, which implements [`Borrow`] on the struct
+/// for each of the bundle's fields by forwarding to `field`.
+fn flatten_field(
+    ty: &Type,
+    impl_generics: proc_macro2::TokenStream,
+    self_ty: proc_macro2::TokenStream,
+    where_clause: proc_macro2::TokenStream,
+    field: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let mut path = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.clone(),
+        _ => {
+            return syn::Error::new_spanned(ty, "#[flatten] fields must be paths.")
+                .to_compile_error()
+        }
+    };
+
+    let last = path.segments.last_mut().unwrap();
+    let lifetimes = match std::mem::replace(&mut last.arguments, PathArguments::None) {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .into_iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Lifetime(lifetime) => Some(lifetime),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    quote! {
+        #path! {
+            [#impl_generics]
+            [#self_ty]
+            [#where_clause]
+            [#field]
+            [#(#lifetimes),*]
+        }
+    }
+}
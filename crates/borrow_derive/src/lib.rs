@@ -1,9 +1,12 @@
 //! Automatically derive [`Borrow`] for the fields of a given struct
+//!
+//! A field marked `#[flatten]` is a sub-table bundle whose own fields are borrowed instead,
+//! by invoking the companion macro that `deebs`' `SubTable` derive defines under the bundle's name.
 
 mod borrow;
 use borrow::impl_borrow;
 
-#[proc_macro_derive(Borrow, attributes(borrow))]
+#[proc_macro_derive(Borrow, attributes(borrow, flatten))]
 pub fn derive_borrow(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(tokens);
     impl_borrow(input)
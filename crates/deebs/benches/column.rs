@@ -0,0 +1,148 @@
+//! Compares map-backed and dense column storage.
+
+use borrow_derive::Borrow;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use deebs::{
+    macros::Table, runtime::run, BorrowColumn, Column, Key, KeyAllocator, ReadColumn, Table,
+    WriteColumn,
+};
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+#[derive(Debug, Default, Borrow, Table)]
+struct MapTable {
+    keys: KeyAllocator,
+    floats: Column<f32>,
+}
+
+#[derive(Debug, Default, Borrow, Table)]
+struct DenseTable {
+    keys: KeyAllocator,
+    #[dense]
+    floats: Column<f32>,
+}
+
+fn populate<T>(table: &T, size: usize) -> Vec<Key>
+where
+    T: Table + BorrowColumn<f32> + Send + Sync,
+{
+    run(table.insert_auto_multi((0..size).map(|i| i as f32)))
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+    for size in SIZES.iter().copied() {
+        group.bench_with_input(BenchmarkId::new("map", size), &size, |b, &size| {
+            b.iter(|| populate(&MapTable::default(), size))
+        });
+        group.bench_with_input(BenchmarkId::new("dense", size), &size, |b, &size| {
+            b.iter(|| populate(&DenseTable::default(), size))
+        });
+    }
+    group.finish();
+}
+
+fn bench_get_mut(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_mut");
+    for size in SIZES.iter().copied() {
+        let map = MapTable::default();
+        let map_keys = populate(&map, size);
+        group.bench_with_input(BenchmarkId::new("map", size), &size, |b, _| {
+            b.iter(|| {
+                run(async {
+                    for key in map_keys.iter() {
+                        *map.get_mut::<f32>(key).await.unwrap() += 1.0;
+                    }
+                })
+            })
+        });
+
+        let dense = DenseTable::default();
+        let dense_keys = populate(&dense, size);
+        group.bench_with_input(BenchmarkId::new("dense", size), &size, |b, _| {
+            b.iter(|| {
+                run(async {
+                    for key in dense_keys.iter() {
+                        *dense.get_mut::<f32>(key).await.unwrap() += 1.0;
+                    }
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_iterate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iterate");
+    for size in SIZES.iter().copied() {
+        let map = MapTable::default();
+        populate(&map, size);
+        group.bench_with_input(BenchmarkId::new("map", size), &size, |b, _| {
+            b.iter(|| {
+                run(async {
+                    let mut column = WriteColumn::<f32>::new(&map).await;
+                    let keys = column.keys().copied().collect::<Vec<_>>();
+                    for key in keys.iter() {
+                        *column.get_mut(key).unwrap() += 1.0;
+                    }
+                })
+            })
+        });
+
+        let dense = DenseTable::default();
+        populate(&dense, size);
+        group.bench_with_input(BenchmarkId::new("dense", size), &size, |b, _| {
+            b.iter(|| {
+                run(async {
+                    let mut column = WriteColumn::<f32>::new(&dense).await;
+                    let (_, values) = column.slices_mut().unwrap();
+                    for value in values {
+                        *value += 1.0;
+                    }
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_sum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sum");
+    for size in SIZES.iter().copied() {
+        let map = MapTable::default();
+        let map_keys = populate(&map, size);
+        group.bench_with_input(BenchmarkId::new("map", size), &size, |b, _| {
+            b.iter(|| {
+                run(async {
+                    let mut sum = 0.0;
+                    for key in map_keys.iter() {
+                        sum += *map.get::<f32>(key).await.unwrap();
+                    }
+                    sum
+                })
+            })
+        });
+
+        let dense = DenseTable::default();
+        populate(&dense, size);
+        group.bench_with_input(BenchmarkId::new("dense", size), &size, |b, _| {
+            b.iter(|| {
+                run(async {
+                    let column = ReadColumn::<f32>::new(&dense).await;
+                    let slices = column.slices().await.unwrap();
+                    slices.values().iter().sum::<f32>()
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_insert,
+    bench_get_mut,
+    bench_iterate,
+    bench_sum
+);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+//! Select the runtime backend from deebs' features.
+//!
+//! Backend features are additive, so that crates enabling different backends can share a build;
+//! the enabled backend with the highest priority is exposed as `cfg(deebs_backend = "...")`.
+
+use std::env;
+
+/// Backends in priority order, by feature name.
+const BACKENDS: [&str; 4] = ["tokio", "smol", "async-std", "parking_lot"];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!(
+        "cargo:rustc-check-cfg=cfg(deebs_backend, values(\"tokio\", \"smol\", \"async-std\", \"parking_lot\"))"
+    );
+
+    let backend = BACKENDS.iter().find(|backend| {
+        let feature = backend.to_uppercase().replace('-', "_");
+        env::var_os(format!("CARGO_FEATURE_{}", feature)).is_some()
+    });
+
+    if let Some(backend) = backend {
+        println!("cargo:rustc-cfg=deebs_backend=\"{}\"", backend);
+    }
+}
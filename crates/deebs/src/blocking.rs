@@ -0,0 +1,27 @@
+//! Synchronous access to tables from code that can't `.await`.
+//!
+//! Blocking calls park the current thread until their locks are free.
+//! Doing so from inside an executor task stalls an executor thread, and deadlocks outright
+//! if the lock holder is waiting to be polled on it, so debug builds panic when a blocking
+//! call is made from within a task. Code that may run inside a task should use the
+//! `try_` variants instead, which never wait.
+
+use std::future::Future;
+
+/// Run `future` to completion on the current thread.
+///
+/// # Panics
+///
+/// In debug builds, if called from within a task of the [`runtime`](crate::runtime) backend.
+#[track_caller]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    #[cfg(debug_assertions)]
+    if crate::runtime::in_task() {
+        panic!(
+            "Blocking table access from within an executor task. \
+            Await the async API, or use a try_ method that never waits."
+        );
+    }
+
+    futures::executor::block_on(future)
+}
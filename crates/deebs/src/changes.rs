@@ -0,0 +1,104 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{Key, Tick};
+
+type ChangeMap = HashMap<Key, Tick, fnv::FnvBuildHasher>;
+
+/// Per-column record of the ticks at which keys were added, changed or removed.
+///
+/// Added and changed records are dropped along with their cells,
+/// but removals accumulate until they are pruned with [`Table::prune_changes`](crate::Table::prune_changes).
+#[derive(Debug, Default)]
+pub struct ColumnChanges {
+    added: ChangeMap,
+    changed: ChangeMap,
+    removed: ChangeMap,
+    unviewed: BTreeSet<Key>,
+    latest: Tick,
+}
+
+impl ColumnChanges {
+    pub(crate) fn record_added(&mut self, key: Key) {
+        let tick = self.touch();
+        self.added.insert(key, tick);
+    }
+
+    pub(crate) fn record_changed(&mut self, key: Key) {
+        let tick = self.touch();
+        self.changed.insert(key, tick);
+    }
+
+    /// Record a write made in place, which views have yet to be updated for.
+    pub(crate) fn record_written(&mut self, key: Key) {
+        self.record_changed(key);
+        self.unviewed.insert(key);
+    }
+
+    /// Return the keys written in place since the last call, for updating views.
+    pub(crate) fn take_unviewed(&mut self) -> Vec<Key> {
+        std::mem::take(&mut self.unviewed).into_iter().collect()
+    }
+
+    pub(crate) fn record_removed(&mut self, key: Key) {
+        self.added.remove(&key);
+        self.changed.remove(&key);
+        self.unviewed.remove(&key);
+        let tick = self.touch();
+        self.removed.insert(key, tick);
+    }
+
+    /// Claim a tick for a write to the column that may not be attributable to a single key.
+    pub(crate) fn touch(&mut self) -> Tick {
+        self.latest = Tick::next();
+        self.latest
+    }
+
+    /// Return the tick of the most recent write to the column.
+    ///
+    /// Unlike the per-key records, this is never pruned.
+    pub fn latest(&self) -> Tick {
+        self.latest
+    }
+
+    /// Return the set of keys inserted after `tick`.
+    pub fn added_since(&self, tick: Tick) -> BTreeSet<Key> {
+        Self::since(&self.added, tick)
+    }
+
+    /// Return the set of keys whose cells were written after `tick`.
+    pub fn changed_since(&self, tick: Tick) -> BTreeSet<Key> {
+        Self::since(&self.changed, tick)
+    }
+
+    /// Return the set of keys removed after `tick`.
+    ///
+    /// Removals are kept until pruned with [`ColumnChanges::prune`],
+    /// so this record grows with every key removed from the column until then.
+    pub fn removed_since(&self, tick: Tick) -> BTreeSet<Key> {
+        Self::since(&self.removed, tick)
+    }
+
+    /// Return true if `key` was inserted after `tick`.
+    pub fn is_added(&self, key: &Key, tick: Tick) -> bool {
+        matches!(self.added.get(key), Some(added) if *added > tick)
+    }
+
+    /// Return true if `key`'s cell was written after `tick`.
+    pub fn is_changed(&self, key: &Key, tick: Tick) -> bool {
+        matches!(self.changed.get(key), Some(changed) if *changed > tick)
+    }
+
+    /// Forget all changes recorded at or before `tick`.
+    pub fn prune(&mut self, tick: Tick) {
+        self.added.retain(|_, added| *added > tick);
+        self.changed.retain(|_, changed| *changed > tick);
+        self.removed.retain(|_, removed| *removed > tick);
+    }
+
+    fn since(map: &ChangeMap, tick: Tick) -> BTreeSet<Key> {
+        map.iter()
+            .filter(|(_, changed)| **changed > tick)
+            .map(|(key, _)| *key)
+            .collect()
+    }
+}
@@ -1,19 +1,103 @@
-use async_std::sync::RwLock;
+use crate::runtime::RwLock;
 
-use crate::ColumnCollection;
+use crate::{
+    ColumnChanges, ColumnCollection, ColumnIndex, ColumnStorage, Key, Observers, SnapshotCache,
+};
 
 use std::{
     borrow::{Borrow, BorrowMut},
     ops::{Deref, DerefMut},
+    sync::{Mutex, MutexGuard},
 };
 
 /// A collection of row structs
 #[derive(Debug)]
-pub struct Column<T>(RwLock<ColumnCollection<T>>);
+pub struct Column<T> {
+    cells: RwLock<ColumnCollection<T>>,
+    changes: Mutex<ColumnChanges>,
+    observers: Observers,
+    snapshot: SnapshotCache<T>,
+    index: ColumnIndex<T>,
+    #[cfg(feature = "journal")]
+    journal: crate::journal::ColumnJournal<T>,
+}
+
+/// A write to a [`Column`], passed to its index and journal once it has been made.
+pub(crate) enum ColumnWrite<'a, T> {
+    Insert(Key, &'a T),
+    Remove(Key),
+    Replace(&'a ColumnCollection<T>),
+}
 
 impl<T> Default for Column<T> {
     fn default() -> Self {
-        Column(RwLock::new(ColumnCollection::default()))
+        Column {
+            cells: RwLock::new(ColumnCollection::default()),
+            changes: Default::default(),
+            observers: Default::default(),
+            snapshot: Default::default(),
+            index: Default::default(),
+            #[cfg(feature = "journal")]
+            journal: Default::default(),
+        }
+    }
+}
+
+impl<T> Column<T> {
+    /// Create an empty column that lays out its cells according to `storage`.
+    pub fn with_storage(storage: ColumnStorage) -> Self {
+        Column {
+            cells: RwLock::new(ColumnCollection::with_storage(storage)),
+            changes: Default::default(),
+            observers: Default::default(),
+            snapshot: Default::default(),
+            index: Default::default(),
+            #[cfg(feature = "journal")]
+            journal: Default::default(),
+        }
+    }
+
+    /// Lock and return this column's change record.
+    pub fn changes(&self) -> MutexGuard<'_, ColumnChanges> {
+        self.changes
+            .lock()
+            .expect("Column change record is poisoned.")
+    }
+
+    /// Return the observers registered on this column.
+    pub fn observers(&self) -> &Observers {
+        &self.observers
+    }
+
+    pub(crate) fn snapshot_cache(&self) -> &SnapshotCache<T> {
+        &self.snapshot
+    }
+
+    pub(crate) fn index(&self) -> &ColumnIndex<T> {
+        &self.index
+    }
+
+    #[cfg(feature = "journal")]
+    pub(crate) fn journal(&self) -> &crate::journal::ColumnJournal<T> {
+        &self.journal
+    }
+
+    /// Return true if writes to this column need to be passed to [`Column::record_write`].
+    pub(crate) fn is_recording(&self) -> bool {
+        #[cfg(feature = "journal")]
+        if self.journal.is_attached() {
+            return true;
+        }
+
+        self.index.is_installed()
+    }
+
+    /// Pass a write made through the deebs guards to this column's index and journal.
+    pub(crate) fn record_write(&self, write: ColumnWrite<'_, T>) {
+        self.index.record(&write);
+
+        #[cfg(feature = "journal")]
+        self.journal.record(write);
     }
 }
 
@@ -21,13 +105,16 @@ impl<T> Deref for Column<T> {
     type Target = RwLock<ColumnCollection<T>>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.cells
     }
 }
 
 impl<T> DerefMut for Column<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        // Writes through the bare lock bypass the change record and index
+        self.snapshot.clear();
+        self.index.reset();
+        &mut self.cells
     }
 }
 
@@ -0,0 +1,297 @@
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeMap, BTreeSet},
+    sync::{Mutex, MutexGuard},
+};
+
+use futures::future::BoxFuture;
+
+use crate::{
+    lock_order, snapshot::notify_column, BorrowColumn, Key, NotifyColumnFn, Table, WriteColumn,
+};
+
+type BoxedCell = Box<dyn Any + Send>;
+type InsertCellFn<Tbl> = for<'a> fn(&'a Tbl, Key, BoxedCell) -> BoxFuture<'a, ()>;
+type RemoveCellFn<Tbl> = for<'a> fn(&'a Tbl, Key) -> BoxFuture<'a, ()>;
+
+/// A structural change queued in [`Commands`].
+enum Command<Tbl> {
+    Insert {
+        key: Key,
+        cell: BoxedCell,
+        type_id: TypeId,
+        insert: InsertCellFn<Tbl>,
+        notify: NotifyColumnFn<Tbl>,
+    },
+    Remove {
+        key: Key,
+        type_id: TypeId,
+        remove: RemoveCellFn<Tbl>,
+        notify: NotifyColumnFn<Tbl>,
+    },
+    Despawn(Key),
+}
+
+impl<Tbl> Command<Tbl> {
+    fn key(&self) -> &Key {
+        match self {
+            Command::Insert { key, .. } | Command::Remove { key, .. } | Command::Despawn(key) => {
+                key
+            }
+        }
+    }
+}
+
+fn insert_cell<Tbl, T>(table: &Tbl, key: Key, cell: BoxedCell) -> BoxFuture<'_, ()>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: Send + Sync + 'static,
+{
+    Box::pin(async move {
+        let value = *cell.downcast::<T>().expect("Cell has the wrong type.");
+        WriteColumn::<T>::new(table).await.insert(key, value);
+    })
+}
+
+fn remove_cell<Tbl, T>(table: &Tbl, key: Key) -> BoxFuture<'_, ()>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: Send + Sync + 'static,
+{
+    Box::pin(async move {
+        WriteColumn::<T>::new(table).await.remove(&key);
+    })
+}
+
+/// The result of [`Table::despawn_cells`].
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct DespawnedCells {
+    /// The number of cells dropped.
+    pub dropped: usize,
+    /// Every key that was despawned, including descendants in the hierarchy.
+    pub keys: Vec<Key>,
+    /// The type of each column a cell was dropped from.
+    pub type_ids: Vec<TypeId>,
+}
+
+/// A [`WriteColumn`] whose cells can be despawned without knowing its type.
+#[doc(hidden)]
+pub trait DespawnColumn: Send {
+    /// Remove the cells of `keys`, returning the number of cells dropped.
+    fn despawn(&mut self, keys: &[Key]) -> usize;
+}
+
+impl<'a, T> DespawnColumn for WriteColumn<'a, T>
+where
+    T: Send + Sync,
+{
+    fn despawn(&mut self, keys: &[Key]) -> usize {
+        keys.iter().filter(|key| self.remove(key).is_some()).count()
+    }
+}
+
+/// A type-erased write lock of one column for [`despawn_columns`].
+#[doc(hidden)]
+pub type LockDespawnFn<Tbl> = for<'a> fn(&'a Tbl) -> BoxFuture<'a, Box<dyn DespawnColumn + 'a>>;
+
+/// Write-lock the `T` column of `table` for [`despawn_columns`].
+#[doc(hidden)]
+pub fn lock_despawn<Tbl, T>(table: &Tbl) -> BoxFuture<'_, Box<dyn DespawnColumn + '_>>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: Send + Sync + 'static,
+{
+    Box::pin(async move {
+        let column: Box<dyn DespawnColumn> = Box::new(WriteColumn::<T>::new(table).await);
+        column
+    })
+}
+
+/// Write-lock every one of `columns` in canonical order, then remove the cells of `keys`
+/// from each before releasing any, so that no row sees a key partially despawned.
+///
+/// Returns the number of cells dropped and the type of each column they were dropped from.
+#[doc(hidden)]
+pub async fn despawn_columns<Tbl>(
+    table: &Tbl,
+    columns: Vec<(TypeId, LockDespawnFn<Tbl>)>,
+    keys: &[Key],
+) -> (usize, Vec<TypeId>) {
+    let type_ids = columns
+        .iter()
+        .map(|(type_id, _)| *type_id)
+        .collect::<Vec<_>>();
+
+    let mut guards = Vec::with_capacity(columns.len());
+    for i in lock_order(&type_ids) {
+        let (type_id, lock) = columns[i];
+        guards.push((type_id, lock(table).await));
+    }
+
+    let mut dropped = 0;
+    let mut type_ids = vec![];
+    for (type_id, guard) in guards.iter_mut() {
+        let removed = guard.despawn(keys);
+        if removed > 0 {
+            dropped += removed;
+            type_ids.push(*type_id);
+        }
+    }
+
+    (dropped, type_ids)
+}
+
+/// A queue of inserts, removes and despawns to be applied to a `Tbl` later.
+///
+/// Rows hold their columns' locks, so cells can't be added to or removed from those columns
+/// while iterating them. Queue the changes here instead, and [`flush`](Commands::flush)
+/// once the rows have been dropped.
+pub struct Commands<Tbl> {
+    commands: Mutex<Vec<Command<Tbl>>>,
+}
+
+impl<Tbl> Default for Commands<Tbl> {
+    fn default() -> Self {
+        Commands {
+            commands: Default::default(),
+        }
+    }
+}
+
+impl<Tbl> std::fmt::Debug for Commands<Tbl> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Commands")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<Tbl> Commands<Tbl> {
+    fn commands(&self) -> MutexGuard<'_, Vec<Command<Tbl>>> {
+        self.commands.lock().expect("Command queue is poisoned.")
+    }
+
+    /// Queue the insertion of `value` into the `T` column at `key`.
+    pub fn insert<T>(&self, key: Key, value: T)
+    where
+        Tbl: Table + BorrowColumn<T> + Sync,
+        T: Send + Sync + 'static,
+    {
+        self.commands().push(Command::Insert {
+            key,
+            cell: Box::new(value),
+            type_id: TypeId::of::<T>(),
+            insert: insert_cell::<Tbl, T>,
+            notify: notify_column::<Tbl, T>,
+        });
+    }
+
+    /// Queue the removal of the `T` cell at `key`.
+    pub fn remove<T>(&self, key: Key)
+    where
+        Tbl: Table + BorrowColumn<T> + Sync,
+        T: Send + Sync + 'static,
+    {
+        self.commands().push(Command::Remove {
+            key,
+            type_id: TypeId::of::<T>(),
+            remove: remove_cell::<Tbl, T>,
+            notify: notify_column::<Tbl, T>,
+        });
+    }
+
+    /// Queue the despawning of `key`, as by [`Table::despawn`].
+    pub fn despawn(&self, key: Key) {
+        self.commands().push(Command::Despawn(key));
+    }
+
+    /// Return the number of queued commands.
+    pub fn len(&self) -> usize {
+        self.commands().len()
+    }
+
+    /// Return true if no commands are queued.
+    pub fn is_empty(&self) -> bool {
+        self.commands().is_empty()
+    }
+
+    /// Apply every queued command to `table` in the order it was queued,
+    /// returning the number of commands applied.
+    ///
+    /// Commands whose key has been freed by the time they're reached,
+    /// such as inserts queued for a key despawned earlier in the queue, are skipped.
+    ///
+    /// Affected [`View`](crate::View)s are updated once after every command has been applied,
+    /// after which observers are notified.
+    /// Columns are locked one command at a time, so this must not be called while a row or
+    /// guard over `table` is held by the calling task.
+    pub async fn flush(&self, table: &Tbl) -> usize
+    where
+        Tbl: Table + Sync,
+    {
+        let commands = std::mem::take(&mut *self.commands());
+        let mut count = 0;
+
+        let mut type_ids = BTreeSet::new();
+        let mut keys = BTreeSet::new();
+        let mut notify = BTreeMap::new();
+        let mut despawned = false;
+
+        for command in commands {
+            if table.is_stale(command.key()) {
+                continue;
+            }
+            count += 1;
+
+            match command {
+                Command::Insert {
+                    key,
+                    cell,
+                    type_id,
+                    insert,
+                    notify: notify_fn,
+                } => {
+                    insert(table, key, cell).await;
+                    type_ids.insert(type_id);
+                    keys.insert(key);
+                    notify.insert(type_id, notify_fn);
+                }
+                Command::Remove {
+                    key,
+                    type_id,
+                    remove,
+                    notify: notify_fn,
+                } => {
+                    remove(table, key).await;
+                    type_ids.insert(type_id);
+                    keys.insert(key);
+                    notify.insert(type_id, notify_fn);
+                }
+                Command::Despawn(key) => {
+                    let cells = table.despawn_cells(vec![key]).await;
+                    type_ids.extend(cells.type_ids);
+                    keys.extend(cells.keys);
+                    despawned = true;
+                }
+            }
+        }
+
+        if !type_ids.is_empty() {
+            let type_ids = type_ids.into_iter().collect::<Vec<_>>();
+            let keys = keys.into_iter().collect::<Vec<_>>();
+            table.update_views(&type_ids, &keys).await;
+        }
+
+        // Despawns may touch any column, so every column's observers are notified after one
+        if despawned {
+            table.notify_all_observers().await;
+        } else {
+            for notify in notify.values() {
+                notify(table).await;
+            }
+        }
+
+        count
+    }
+}
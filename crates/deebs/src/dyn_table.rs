@@ -0,0 +1,397 @@
+use std::{
+    any::{Any, TypeId},
+    borrow::Borrow,
+    collections::HashMap,
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use futures::future::BoxFuture;
+
+use crate::{
+    despawn_columns, despawn_hierarchy, lock_despawn, reflect::ColumnRegistry,
+    snapshot::notify_column, CellIndex, Children, Column, ColumnStorage, CommonKeys,
+    DespawnedCells, Key, KeyAllocator, LockDespawnFn, NotifyColumnFn, Parent, Row, Singleton,
+    Table, View,
+};
+
+type BoxedEntry = Box<dyn Any + Send + Sync>;
+type UpdateViewFn = for<'a> fn(&'a DynTable) -> BoxFuture<'a, ()>;
+type UpdateViewKeysFn = for<'a> fn(&'a DynTable, &'a [Key]) -> BoxFuture<'a, ()>;
+
+/// Extend a borrow of a boxed entry to the lifetime of the [`DynTable`] holding it.
+///
+/// # Safety
+///
+/// `value` must live in a box owned by `table` that is never removed or replaced.
+/// Moving the box between map buckets doesn't move its contents.
+unsafe fn extend_entry<'a, T>(_table: &'a DynTable, value: &T) -> &'a T {
+    &*(value as *const T)
+}
+
+/// A type-erased [`Column`] held by a [`DynTable`].
+struct DynColumn {
+    column: BoxedEntry,
+    storage: ColumnStorage,
+    /// A `fn() -> Box<dyn CellIndex<T>>` creating the column's index, if it has one.
+    index: Option<BoxedEntry>,
+    despawn: LockDespawnFn<DynTable>,
+    notify: NotifyColumnFn<DynTable>,
+}
+
+impl DynColumn {
+    fn new<T>(storage: ColumnStorage) -> Self
+    where
+        T: Send + Sync + 'static,
+    {
+        DynColumn {
+            column: Box::new(Column::<T>::with_storage(storage)),
+            storage,
+            index: None,
+            despawn: lock_despawn::<DynTable, T>,
+            notify: notify_column::<DynTable, T>,
+        }
+    }
+}
+
+/// A type-erased [`View`] held by a [`DynTable`].
+struct DynView {
+    view: BoxedEntry,
+    inner_types: Vec<TypeId>,
+    update: UpdateViewFn,
+    update_keys: UpdateViewKeysFn,
+}
+
+fn update_view<R>(table: &DynTable) -> BoxFuture<'_, ()>
+where
+    R: CommonKeys<DynTable> + 'static,
+{
+    Box::pin(async move {
+        let view: &View<R> = table.borrow();
+        view.update(table).await;
+    })
+}
+
+fn update_view_keys<'a, R>(table: &'a DynTable, keys: &'a [Key]) -> BoxFuture<'a, ()>
+where
+    R: CommonKeys<DynTable> + 'static,
+{
+    Box::pin(async move {
+        let view: &View<R> = table.borrow();
+        view.update_keys(table, keys).await;
+    })
+}
+
+/// A [`Table`] whose columns, singletons and views are added at runtime rather than declared as fields.
+///
+/// Columns and singletons are created empty or defaulted the first time they're borrowed,
+/// so any `Send + Sync + 'static` cell type can be inserted, and the [`Row`],
+/// [`Insert`](crate::Insert) and [`Remove`](crate::Remove) derives work as they do over a derived table.
+/// Views must be added with [`register_view`](DynTable::register_view) before they're borrowed.
+///
+/// Runtime columns don't appear in [`Table::column_registry`], so they aren't captured by
+/// snapshots, journals or serialization.
+#[derive(Default)]
+pub struct DynTable {
+    key_allocator: KeyAllocator,
+    columns: RwLock<HashMap<TypeId, DynColumn>>,
+    singletons: RwLock<HashMap<TypeId, BoxedEntry>>,
+    views: RwLock<HashMap<TypeId, DynView>>,
+}
+
+impl std::fmt::Debug for DynTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynTable")
+            .field("key_allocator", &self.key_allocator)
+            .field("columns", &self.columns().len())
+            .field("singletons", &read(&self.singletons).len())
+            .field("views", &self.views().len())
+            .finish()
+    }
+}
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().expect("DynTable is poisoned.")
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().expect("DynTable is poisoned.")
+}
+
+impl DynTable {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn columns(&self) -> RwLockReadGuard<'_, HashMap<TypeId, DynColumn>> {
+        read(&self.columns)
+    }
+
+    fn views(&self) -> RwLockReadGuard<'_, HashMap<TypeId, DynView>> {
+        read(&self.views)
+    }
+
+    /// Return the `T` column, creating it if it doesn't exist yet.
+    pub fn column<T>(&self) -> &Column<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        if let Some(column) = self.columns().get(&type_id) {
+            let column = column
+                .column
+                .downcast_ref()
+                .expect("Column has the wrong type.");
+            // Safety: Columns are never removed from the table
+            return unsafe { extend_entry(self, column) };
+        }
+
+        let mut columns = write(&self.columns);
+        let column = columns
+            .entry(type_id)
+            .or_insert_with(|| DynColumn::new::<T>(ColumnStorage::Map))
+            .column
+            .downcast_ref()
+            .expect("Column has the wrong type.");
+        // Safety: Columns are never removed from the table
+        unsafe { extend_entry(self, column) }
+    }
+
+    /// Return true if the `T` column has been created.
+    pub fn has_column<T>(&self) -> bool
+    where
+        T: 'static,
+    {
+        self.columns().contains_key(&TypeId::of::<T>())
+    }
+
+    /// Create the `T` column if it doesn't exist yet, and lay its cells out according to `storage`.
+    ///
+    /// A new column is created with `storage`; an existing column is moved into it
+    /// the next time it is write-locked, as with `#[dense]`.
+    pub fn register_column<T>(&self, storage: ColumnStorage)
+    where
+        T: Send + Sync + 'static,
+    {
+        let mut columns = write(&self.columns);
+        columns
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| DynColumn::new::<T>(storage))
+            .storage = storage;
+    }
+
+    /// Index the `T` column with an `I`, as with `#[index]`.
+    ///
+    /// Indexes are installed the first time a column is write-locked,
+    /// so this has no effect on a column that has already been written.
+    pub fn register_index<T, I>(&self)
+    where
+        T: Send + Sync + 'static,
+        I: CellIndex<T> + Default + 'static,
+    {
+        fn new_index<T, I>() -> Box<dyn CellIndex<T>>
+        where
+            I: CellIndex<T> + Default + 'static,
+        {
+            Box::new(I::default())
+        }
+
+        let index: fn() -> Box<dyn CellIndex<T>> = new_index::<T, I>;
+        let mut columns = write(&self.columns);
+        columns
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| DynColumn::new::<T>(ColumnStorage::Map))
+            .index = Some(Box::new(index));
+    }
+
+    /// Return the `T` singleton, creating it with `T`'s default value if it doesn't exist yet.
+    pub fn singleton<T>(&self) -> &Singleton<T>
+    where
+        T: Default + Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        if let Some(singleton) = read(&self.singletons).get(&type_id) {
+            let singleton = singleton
+                .downcast_ref()
+                .expect("Singleton has the wrong type.");
+            // Safety: Singletons are never removed from the table
+            return unsafe { extend_entry(self, singleton) };
+        }
+
+        let mut singletons = write(&self.singletons);
+        let singleton = singletons
+            .entry(type_id)
+            .or_insert_with(|| Box::new(Singleton::<T>::default()))
+            .downcast_ref()
+            .expect("Singleton has the wrong type.");
+        // Safety: Singletons are never removed from the table
+        unsafe { extend_entry(self, singleton) }
+    }
+
+    /// Add a [`View`] over `R` and fill it from the table's current cells.
+    ///
+    /// Rows are registered with their lifetime set to `'static`,
+    /// and borrowed from the table as `View<R<'static>>`.
+    /// Does nothing if the view has already been registered.
+    pub async fn register_view<R>(&self)
+    where
+        R: Row<'static, DynTable> + CommonKeys<DynTable> + 'static,
+    {
+        {
+            let mut views = write(&self.views);
+            if views.contains_key(&TypeId::of::<R>()) {
+                return;
+            }
+
+            views.insert(
+                TypeId::of::<R>(),
+                DynView {
+                    view: Box::new(View::<R>::default()),
+                    inner_types: R::inner_types(),
+                    update: update_view::<R>,
+                    update_keys: update_view_keys::<R>,
+                },
+            );
+        }
+
+        update_view::<R>(self).await;
+    }
+
+    /// Return true if a [`View`] over `R` has been registered.
+    pub fn has_view<R>(&self) -> bool
+    where
+        R: 'static,
+    {
+        self.views().contains_key(&TypeId::of::<R>())
+    }
+}
+
+impl<T> Borrow<Column<T>> for DynTable
+where
+    T: Send + Sync + 'static,
+{
+    fn borrow(&self) -> &Column<T> {
+        self.column()
+    }
+}
+
+impl<T> Borrow<Singleton<T>> for DynTable
+where
+    T: Default + Send + Sync + 'static,
+{
+    fn borrow(&self) -> &Singleton<T> {
+        self.singleton()
+    }
+}
+
+impl<R> Borrow<View<R>> for DynTable
+where
+    R: 'static,
+{
+    fn borrow(&self) -> &View<R> {
+        let views = self.views();
+        let view = views
+            .get(&TypeId::of::<R>())
+            .unwrap_or_else(|| panic!("View of {} is not registered.", std::any::type_name::<R>()))
+            .view
+            .downcast_ref()
+            .expect("View has the wrong type.");
+        // Safety: Views are never removed from the table
+        unsafe { extend_entry(self, view) }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for DynTable {
+    type Key = Key;
+
+    fn key_allocator(&self) -> Option<&KeyAllocator> {
+        Some(&self.key_allocator)
+    }
+
+    async fn despawn_cells(&self, keys: Vec<Key>) -> DespawnedCells {
+        // Only despawn descendants if the hierarchy columns are in use
+        let keys = if self.has_column::<Parent>() && self.has_column::<Children>() {
+            despawn_hierarchy(self, keys).await
+        } else {
+            keys
+        };
+
+        let columns = self
+            .columns()
+            .iter()
+            .map(|(type_id, column)| (*type_id, column.despawn))
+            .collect::<Vec<_>>();
+
+        let (dropped, type_ids) = despawn_columns(self, columns, &keys).await;
+
+        for key in keys.iter() {
+            self.key_allocator.free(key);
+        }
+
+        DespawnedCells {
+            dropped,
+            keys,
+            type_ids,
+        }
+    }
+
+    async fn notify_all_observers(&self) {
+        let notify = self
+            .columns()
+            .values()
+            .map(|column| column.notify)
+            .collect::<Vec<_>>();
+
+        for notify in notify {
+            notify(self).await;
+        }
+    }
+
+    fn column_storage(&self, type_id: TypeId) -> ColumnStorage {
+        self.columns()
+            .get(&type_id)
+            .map(|column| column.storage)
+            .unwrap_or(ColumnStorage::Map)
+    }
+
+    fn column_index<T>(&self) -> Option<Box<dyn CellIndex<T>>>
+    where
+        T: 'static,
+    {
+        let columns = self.columns();
+        let index = columns.get(&TypeId::of::<T>())?.index.as_ref()?;
+        let index = index
+            .downcast_ref::<fn() -> Box<dyn CellIndex<T>>>()
+            .expect("Index has the wrong type.");
+        Some(index())
+    }
+
+    async fn update_views(&self, type_ids: &[TypeId], keys: &[Key]) {
+        let views = self
+            .views()
+            .values()
+            .filter(|view| view.inner_types.iter().any(|ty| type_ids.contains(ty)))
+            .map(|view| view.update_keys)
+            .collect::<Vec<_>>();
+
+        for update_keys in views {
+            update_keys(self, keys).await;
+        }
+    }
+
+    async fn rebuild_views(&self) {
+        let views = self
+            .views()
+            .values()
+            .map(|view| view.update)
+            .collect::<Vec<_>>();
+
+        for update in views {
+            update(self).await;
+        }
+    }
+
+    fn column_registry() -> ColumnRegistry<Self> {
+        ColumnRegistry::new(vec![])
+    }
+}
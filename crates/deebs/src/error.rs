@@ -0,0 +1,26 @@
+use std::fmt::Display;
+
+use crate::Key;
+
+/// The reason a [`ReadCell`] or [`WriteCell`] could not be created.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CellError {
+    /// The key is alive, but has no cell in the requested column.
+    Missing(Key),
+    /// The key's entity has been freed, and its slot may since have been reused.
+    Stale(Key),
+    /// The cell or its column is locked, and the caller asked not to wait for it.
+    Locked(Key),
+}
+
+impl Display for CellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellError::Missing(key) => write!(f, "Key {} has no cell in this column.", key),
+            CellError::Stale(key) => write!(f, "Key {} is stale.", key),
+            CellError::Locked(key) => write!(f, "Cell for key {} is locked.", key),
+        }
+    }
+}
+
+impl std::error::Error for CellError {}
@@ -0,0 +1,162 @@
+use std::{fmt::Display, marker::PhantomData};
+
+/// A double-buffered queue of `E` events, stored in a [`Singleton`](crate::Singleton) or
+/// [`Column`](crate::Column).
+///
+/// The producer calls [`update`](Events::update) once per frame before sending that frame's events.
+/// Events stay readable for the frame they were sent in and the one after,
+/// then are dropped by the next update, so nothing has to clear them by hand.
+///
+/// Consumers that run in step with the producer can read the frame's events with
+/// [`current`](Events::current). Consumers that run on their own schedule keep an
+/// [`EventReader`], which sees each buffered event exactly once.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Events<E> {
+    previous: Vec<E>,
+    current: Vec<E>,
+    start: usize,
+}
+
+impl<E> Default for Events<E> {
+    fn default() -> Self {
+        Events {
+            previous: Default::default(),
+            current: Default::default(),
+            start: 0,
+        }
+    }
+}
+
+impl<E> Events<E> {
+    /// Send an event for the current frame.
+    pub fn send(&mut self, event: E) {
+        self.current.push(event);
+    }
+
+    /// Start a new frame, dropping the events of the frame before the current one.
+    pub fn update(&mut self) {
+        self.start += self.previous.len();
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// Return the events sent since the last [`update`](Events::update).
+    pub fn current(&self) -> &[E] {
+        &self.current
+    }
+
+    /// Iterate over every buffered event, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &E> {
+        self.previous.iter().chain(self.current.iter())
+    }
+
+    /// Return the number of buffered events.
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    /// Return true if no events are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_empty() && self.current.is_empty()
+    }
+
+    /// Drop every buffered event.
+    ///
+    /// Readers skip the dropped events, as if they had been rotated out.
+    pub fn clear(&mut self) {
+        self.start += self.len();
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    /// Return the total number of events sent, used to position [`EventReader`]s.
+    pub fn event_count(&self) -> usize {
+        self.start + self.len()
+    }
+
+    /// Return a reader that only sees events sent after this call.
+    pub fn reader(&self) -> EventReader<E> {
+        EventReader {
+            next: self.event_count(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E> Extend<E> for Events<E> {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        self.current.extend(iter)
+    }
+}
+
+impl<E> Display for Events<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} events", self.len())
+    }
+}
+
+/// A cursor into an [`Events`] queue, so that several consumers can read the same events independently.
+///
+/// A default reader starts with every event still buffered.
+/// Events rotated out before a reader gets to them are skipped.
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct EventReader<E> {
+    next: usize,
+    _phantom: PhantomData<fn() -> E>,
+}
+
+impl<E> Default for EventReader<E> {
+    fn default() -> Self {
+        EventReader {
+            next: 0,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E> Clone for EventReader<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for EventReader<E> {}
+
+impl<E> EventReader<E> {
+    fn skip(&self, events: &Events<E>) -> usize {
+        self.next.saturating_sub(events.start)
+    }
+
+    /// Iterate over the events this reader hasn't seen yet, marking them as read.
+    pub fn read<'a>(&mut self, events: &'a Events<E>) -> impl Iterator<Item = &'a E> {
+        let skip = self.skip(events);
+        self.next = events.event_count();
+        events.iter().skip(skip)
+    }
+
+    /// Return the number of events this reader hasn't seen yet.
+    pub fn len(&self, events: &Events<E>) -> usize {
+        events.len().saturating_sub(self.skip(events))
+    }
+
+    /// Return true if this reader has seen every buffered event.
+    pub fn is_empty(&self, events: &Events<E>) -> bool {
+        self.len(events) == 0
+    }
+
+    /// Mark every buffered event as read without reading it.
+    pub fn clear(&mut self, events: &Events<E>) {
+        self.next = events.event_count();
+    }
+}
+
+impl<E> Display for EventReader<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Next event {}", self.next)
+    }
+}
@@ -0,0 +1,233 @@
+//! Canonical lock ordering and a debug-mode lock-order checker.
+//!
+//! Every column guard registers itself with the checker while it is held.
+//! Whenever a task locks column `B` while holding column `A`, the edge `A -> B` is recorded
+//! by [`TypeId`], so that distinct types sharing a printed name are told apart,
+//! alongside both call sites. If a later acquisition would record the inverse edge `B -> A`,
+//! the two code paths can deadlock one another, and the checker panics with all four sites.
+//!
+//! Held locks are tracked per task, so the checker is disabled under the `smol` backend,
+//! which has no way to identify its tasks.
+
+use std::{any::TypeId, panic::Location};
+
+/// Return the indices of `type_ids` sorted into the canonical order that multi-column
+/// acquisitions (such as derived [`Row::new`] implementations) lock in.
+pub fn lock_order(type_ids: &[TypeId]) -> Vec<usize> {
+    let mut order = (0..type_ids.len()).collect::<Vec<_>>();
+    order.sort_by_key(|i| type_ids[*i]);
+    order
+}
+
+/// Registration of a held column lock with the lock-order checker.
+/// Unregisters itself when dropped.
+#[derive(Debug)]
+pub struct LockOrderToken {
+    #[cfg(all(debug_assertions, not(deebs_backend = "smol")))]
+    id: usize,
+}
+
+impl LockOrderToken {
+    /// Register a lock on the `T` column acquired at `site`,
+    /// panicking if doing so inverts a previously observed lock order.
+    #[allow(unused_variables, clippy::extra_unused_type_parameters)]
+    pub(crate) fn acquire<T>(site: &'static Location<'static>) -> Self
+    where
+        T: 'static,
+    {
+        #[cfg(all(debug_assertions, not(deebs_backend = "smol")))]
+        {
+            LockOrderToken {
+                id: checker::acquire(checker::Column::of::<T>(), site),
+            }
+        }
+
+        #[cfg(not(all(debug_assertions, not(deebs_backend = "smol"))))]
+        {
+            LockOrderToken {}
+        }
+    }
+
+    /// Register a lock on the `T` column that was taken at `site` without waiting.
+    ///
+    /// A lock that never waits can't complete a deadlock cycle,
+    /// so no ordering edges are recorded for it, but later acquisitions still see it as held.
+    #[allow(unused_variables, clippy::extra_unused_type_parameters)]
+    pub(crate) fn hold<T>(site: &'static Location<'static>) -> Self
+    where
+        T: 'static,
+    {
+        #[cfg(all(debug_assertions, not(deebs_backend = "smol")))]
+        {
+            LockOrderToken {
+                id: checker::hold(checker::Column::of::<T>(), site),
+            }
+        }
+
+        #[cfg(not(all(debug_assertions, not(deebs_backend = "smol"))))]
+        {
+            LockOrderToken {}
+        }
+    }
+}
+
+#[cfg(all(debug_assertions, not(deebs_backend = "smol")))]
+impl Drop for LockOrderToken {
+    fn drop(&mut self) {
+        checker::release(self.id);
+    }
+}
+
+#[cfg(all(debug_assertions, not(deebs_backend = "smol")))]
+mod checker {
+    use std::{
+        any::TypeId,
+        cell::RefCell,
+        collections::HashMap,
+        panic::Location,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+    };
+
+    type Site = &'static Location<'static>;
+    type Edges = HashMap<(TypeId, TypeId), (Site, Site)>;
+
+    /// A column type, identified by its [`TypeId`] and named by its type name for diagnostics.
+    #[derive(Debug, Copy, Clone)]
+    pub struct Column {
+        type_id: TypeId,
+        name: &'static str,
+    }
+
+    impl Column {
+        pub fn of<T>() -> Self
+        where
+            T: 'static,
+        {
+            Column {
+                type_id: TypeId::of::<T>(),
+                name: std::any::type_name::<T>(),
+            }
+        }
+    }
+
+    #[derive(Debug, Copy, Clone)]
+    struct Held {
+        id: usize,
+        column: Column,
+        site: Site,
+    }
+
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    lazy_static::lazy_static! {
+        /// Map of (held, acquired) column pairs to the sites they were first locked at.
+        static ref EDGES: Mutex<Edges> = Default::default();
+    }
+
+    thread_local! {
+        static THREAD_HELD: RefCell<Vec<Held>> = const { RefCell::new(vec![]) };
+    }
+
+    #[cfg(deebs_backend = "async-std")]
+    async_std::task_local! {
+        static TASK_HELD: RefCell<Vec<Held>> = RefCell::new(vec![]);
+    }
+
+    /// Run `f` over the held locks of the current task,
+    /// or of the current thread if called outside of an async_std task.
+    #[cfg(deebs_backend = "async-std")]
+    fn with_held<R>(f: impl FnOnce(&mut Vec<Held>) -> R) -> R {
+        let mut f = Some(f);
+        match TASK_HELD.try_with(|held| (f.take().unwrap())(&mut held.borrow_mut())) {
+            Ok(result) => result,
+            Err(_) => THREAD_HELD.with(|held| (f.take().unwrap())(&mut held.borrow_mut())),
+        }
+    }
+
+    #[cfg(deebs_backend = "tokio")]
+    lazy_static::lazy_static! {
+        /// Held locks of each tokio task that currently holds any.
+        static ref TASK_HELD: Mutex<HashMap<tokio::task::Id, Vec<Held>>> = Default::default();
+    }
+
+    /// Run `f` over the held locks of the current task,
+    /// or of the current thread if called outside of a tokio task.
+    #[cfg(deebs_backend = "tokio")]
+    fn with_held<R>(f: impl FnOnce(&mut Vec<Held>) -> R) -> R {
+        let id = match tokio::task::try_id() {
+            Some(id) => id,
+            None => return THREAD_HELD.with(|held| f(&mut held.borrow_mut())),
+        };
+
+        // `f` panics on an inversion, which must not poison the registry for other tasks
+        let mut tasks = TASK_HELD.lock().unwrap_or_else(|e| e.into_inner());
+        let held = tasks.entry(id).or_default();
+        let result = f(held);
+        if held.is_empty() {
+            tasks.remove(&id);
+        }
+        result
+    }
+
+    /// Run `f` over the held locks of the current thread.
+    #[cfg(deebs_backend = "parking_lot")]
+    fn with_held<R>(f: impl FnOnce(&mut Vec<Held>) -> R) -> R {
+        THREAD_HELD.with(|held| f(&mut held.borrow_mut()))
+    }
+
+    pub fn acquire(column: Column, site: Site) -> usize {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        with_held(|held| {
+            let mut edges = EDGES.lock().expect("Lock order graph is poisoned.");
+
+            for prev in held
+                .iter()
+                .filter(|prev| prev.column.type_id != column.type_id)
+            {
+                if let Some((inverse_held_site, inverse_site)) =
+                    edges.get(&(column.type_id, prev.column.type_id))
+                {
+                    let (inverse_held_site, inverse_site) = (*inverse_held_site, *inverse_site);
+                    drop(edges);
+                    panic!(
+                        "Lock order inversion between columns {} and {}:\n\
+                        \t{} locked at {} while holding {} locked at {}\n\
+                        \t{} locked at {} while holding {} locked at {}",
+                        prev.column.name,
+                        column.name,
+                        column.name,
+                        site,
+                        prev.column.name,
+                        prev.site,
+                        prev.column.name,
+                        inverse_site,
+                        column.name,
+                        inverse_held_site,
+                    );
+                }
+
+                edges
+                    .entry((prev.column.type_id, column.type_id))
+                    .or_insert((prev.site, site));
+            }
+
+            held.push(Held { id, column, site });
+        });
+
+        id
+    }
+
+    pub fn hold(column: Column, site: Site) -> usize {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        with_held(|held| held.push(Held { id, column, site }));
+        id
+    }
+
+    pub fn release(id: usize) {
+        with_held(|held| held.retain(|held| held.id != id));
+    }
+}
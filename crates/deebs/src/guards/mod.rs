@@ -1,15 +1,19 @@
+mod lock_order;
 mod read_cell;
 mod read_column;
 mod read_singleton;
 mod read_view;
+mod transaction_column;
 mod write_cell;
 mod write_column;
 mod write_singleton;
 
+pub use lock_order::*;
 pub use read_cell::*;
 pub use read_column::*;
 pub use read_singleton::*;
 pub use read_view::*;
+pub use transaction_column::*;
 pub use write_cell::*;
 pub use write_column::*;
 pub use write_singleton::*;
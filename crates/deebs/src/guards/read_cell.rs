@@ -1,20 +1,70 @@
 use std::ops::Deref;
-use std::{marker::PhantomPinned, pin::Pin, ptr::NonNull};
+use std::{
+    future::Future, marker::PhantomPinned, panic::Location, pin::Pin, ptr::NonNull, sync::Arc,
+};
 
-use crate::{BorrowColumn, ColumnCollection, ReadColumn, Key};
+use crate::{BorrowColumn, CellError, CellLock, ColumnCollection, Key, ReadColumn, Table};
 
 /// A view into one of the [`Cell`]s of a [`Column`]
 #[derive(Debug)]
 pub struct ReadCell<'a, T>(Pin<Box<ReadCellInner<'a, T>>>);
 
 impl<'a, T> ReadCell<'a, T> {
+    #[track_caller]
+    pub fn new<'b, DB>(
+        table: &'a DB,
+        key: &'b Key,
+    ) -> impl Future<Output = Result<ReadCell<'a, T>, CellError>> + 'b
+    where
+        'a: 'b,
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
+    {
+        Self::new_at(table, key, Location::caller())
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(table)))]
-    pub async fn new<DB>(table: &'a DB, key: &Key) -> Option<ReadCell<'a, T>>
+    pub(crate) async fn new_at<DB>(
+        table: &'a DB,
+        key: &Key,
+        site: &'static Location<'static>,
+    ) -> Result<ReadCell<'a, T>, CellError>
     where
-        T: 'a,
-        DB: BorrowColumn<T>,
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
     {
-        let inner = ReadCellInner::new(table, key).await;
+        let inner = ReadCellInner::new(table, key, site).await;
+        inner.map(ReadCell)
+    }
+
+    /// Blocking counterpart of [`ReadCell::new`]. See [`block_on`](crate::block_on).
+    #[track_caller]
+    pub fn blocking<DB>(table: &'a DB, key: &Key) -> Result<ReadCell<'a, T>, CellError>
+    where
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
+    {
+        crate::block_on(Self::new_at(table, key, Location::caller()))
+    }
+
+    /// Create a cell if it and its column are free,
+    /// returning [`CellError::Locked`] instead of waiting otherwise.
+    #[track_caller]
+    pub fn try_new<DB>(table: &'a DB, key: &Key) -> Result<ReadCell<'a, T>, CellError>
+    where
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
+    {
+        ReadCellInner::try_new(table, key, Location::caller()).map(ReadCell)
+    }
+
+    /// Create a cell that shares an already-held column guard,
+    /// so that many cells can be taken from one lock acquisition.
+    pub async fn from_column(
+        column_guard: Arc<ReadColumn<'a, T>>,
+        key: &Key,
+    ) -> Result<ReadCell<'a, T>, CellError> {
+        let inner = ReadCellInner::from_column(column_guard, key).await;
         inner.map(ReadCell)
     }
 
@@ -24,7 +74,7 @@ impl<'a, T> ReadCell<'a, T> {
 
     #[allow(dead_code)]
     pub fn column(&'a self) -> &'a ColumnCollection<T> {
-        self.0.deref().column_guard.deref()
+        self.0.column_guard.deref().deref()
     }
 }
 
@@ -40,7 +90,9 @@ impl<'a, T> Deref for ReadCell<'a, T> {
 /// Self-referential struct that holds both the column and cell read guards
 #[derive(Debug)]
 struct ReadCellInner<'a, T> {
-    column_guard: ReadColumn<'a, T>,
+    // Declared before the column guard so that it is released first
+    cell_lock: Option<CellLock<'a, T>>,
+    column_guard: Arc<ReadColumn<'a, T>>,
     item_guard: NonNull<T>,
     _pin: PhantomPinned,
 }
@@ -50,37 +102,89 @@ unsafe impl<'a, T> Sync for ReadCellInner<'a, T> {}
 
 impl<'a, T> ReadCellInner<'a, T> {
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(table)))]
-    pub async fn new<DB>(table: &'a DB, key: &Key) -> Option<Pin<Box<ReadCellInner<'a, T>>>>
+    pub async fn new<DB>(
+        table: &'a DB,
+        key: &Key,
+        site: &'static Location<'static>,
+    ) -> Result<Pin<Box<ReadCellInner<'a, T>>>, CellError>
     where
-        T: 'a,
-        DB: BorrowColumn<T>,
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
     {
-        let column_guard = ReadColumn::new(table).await;
+        if table.is_stale(key) {
+            return Err(CellError::Stale(*key));
+        }
+
+        let column_guard = ReadColumn::new_at(table, site).await;
+        Self::from_column(Arc::new(column_guard), key).await
+    }
+
+    pub fn try_new<DB>(
+        table: &'a DB,
+        key: &Key,
+        site: &'static Location<'static>,
+    ) -> Result<Pin<Box<ReadCellInner<'a, T>>>, CellError>
+    where
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
+    {
+        if table.is_stale(key) {
+            return Err(CellError::Stale(*key));
+        }
+
+        let column_guard = ReadColumn::try_new_at(table, site).ok_or(CellError::Locked(*key))?;
+        let column_guard = Arc::new(column_guard);
+        let (item_guard, cell_lock) = unsafe { column(&column_guard) }.try_read_cell(key)?;
 
+        Ok(Box::pin(ReadCellInner {
+            cell_lock: Some(cell_lock),
+            column_guard,
+            item_guard,
+            _pin: PhantomPinned,
+        }))
+    }
+
+    pub async fn from_column(
+        column_guard: Arc<ReadColumn<'a, T>>,
+        key: &Key,
+    ) -> Result<Pin<Box<ReadCellInner<'a, T>>>, CellError> {
         if !column_guard.contains_key(key) {
-            return None;
+            return Err(CellError::Missing(*key));
         }
 
         let guard = ReadCellInner {
+            cell_lock: None,
             column_guard,
             item_guard: NonNull::dangling(),
             _pin: PhantomPinned,
         };
-        
+
         let mut boxed = Box::pin(guard);
 
-        let item_guard =
-            NonNull::from(boxed.column_guard.get(&key).unwrap().read().await.deref());
+        let (item_guard, cell_lock) = unsafe { column(&boxed.column_guard) }
+            .read_cell(key)
+            .await
+            .unwrap();
 
         unsafe {
             let mut_ref: Pin<&mut Self> = Pin::as_mut(&mut boxed);
-            Pin::get_unchecked_mut(mut_ref).item_guard = item_guard;
+            let inner = Pin::get_unchecked_mut(mut_ref);
+            inner.cell_lock = Some(cell_lock);
+            inner.item_guard = item_guard;
         }
 
-        Some(boxed)
+        Ok(boxed)
     }
 }
 
+/// Borrow the column behind `column_guard` for as long as the guard's lock is held,
+/// so that a cell lock taken from it can be stored alongside the guard.
+///
+/// The caller must release the borrow before `column_guard`.
+unsafe fn column<'a, T>(column_guard: &Arc<ReadColumn<'a, T>>) -> &'a ColumnCollection<T> {
+    &*(&***column_guard as *const ColumnCollection<T>)
+}
+
 impl<'a, T> Deref for ReadCellInner<'a, T> {
     type Target = T;
 
@@ -1,30 +1,114 @@
-use std::ops::Deref;
+use std::{future::Future, ops::Deref, panic::Location, sync::MutexGuard};
 
-use async_std::sync::RwLockReadGuard;
+use crate::runtime::RwLockReadGuard;
 
-use crate::{BorrowColumn, ColumnCollection};
+use crate::{
+    BorrowColumn, Column, ColumnChanges, ColumnCollection, LockOrderToken, Observers, ReadSlices,
+};
 
 /// A view into a [`Column`]
 #[derive(Debug)]
 pub struct ReadColumn<'a, T> {
+    column: &'a Column<T>,
     column_guard: RwLockReadGuard<'a, ColumnCollection<T>>,
+    _lock_order: LockOrderToken,
 }
 
 impl<'a, T> ReadColumn<'a, T> {
+    #[track_caller]
+    pub fn new<DB>(table: &'a DB) -> impl Future<Output = ReadColumn<'a, T>> + 'a
+    where
+        T: 'static,
+        DB: BorrowColumn<T>,
+    {
+        Self::new_at(table, Location::caller())
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(table)))]
-    pub async fn new<DB>(table: &'a DB) -> ReadColumn<'a, T>
+    pub(crate) async fn new_at<DB>(
+        table: &'a DB,
+        site: &'static Location<'static>,
+    ) -> ReadColumn<'a, T>
     where
-        T: 'a,
+        T: 'static,
         DB: BorrowColumn<T>,
     {
+        let lock_order = LockOrderToken::acquire::<T>(site);
         let column = table.borrow();
         let column_guard = column.read().await;
-        ReadColumn { column_guard }
+        ReadColumn {
+            column,
+            column_guard,
+            _lock_order: lock_order,
+        }
+    }
+
+    /// Blocking counterpart of [`ReadColumn::new`]. See [`block_on`](crate::block_on).
+    #[track_caller]
+    pub fn blocking<DB>(table: &'a DB) -> ReadColumn<'a, T>
+    where
+        T: 'static,
+        DB: BorrowColumn<T>,
+    {
+        crate::block_on(Self::new_at(table, Location::caller()))
+    }
+
+    /// Lock the column if it is free, returning `None` instead of waiting otherwise.
+    #[track_caller]
+    pub fn try_new<DB>(table: &'a DB) -> Option<ReadColumn<'a, T>>
+    where
+        T: 'static,
+        DB: BorrowColumn<T>,
+    {
+        Self::try_new_at(table, Location::caller())
+    }
+
+    pub(crate) fn try_new_at<DB>(
+        table: &'a DB,
+        site: &'static Location<'static>,
+    ) -> Option<ReadColumn<'a, T>>
+    where
+        T: 'static,
+        DB: BorrowColumn<T>,
+    {
+        let column = table.borrow();
+        let column_guard = column.try_read()?;
+        let lock_order = LockOrderToken::hold::<T>(site);
+        Some(ReadColumn {
+            column,
+            column_guard,
+            _lock_order: lock_order,
+        })
     }
 
     pub fn column(&'a self) -> &'a ColumnCollection<T> {
         self.column_guard.deref()
     }
+
+    /// Return the keys and values of a dense column as parallel slices,
+    /// waiting for every cell's lock so that no [`WriteCell`](crate::WriteCell) can alias them.
+    pub async fn slices(&self) -> Option<ReadSlices<'_, T>> {
+        self.column_guard.read_slices().await
+    }
+
+    /// Lock and return the change record of the underlying [`Column`].
+    pub fn changes(&self) -> MutexGuard<'_, ColumnChanges> {
+        self.column.changes()
+    }
+
+    /// Return the observers registered on the underlying [`Column`].
+    pub fn observers(&self) -> &Observers {
+        self.column.observers()
+    }
+
+    pub(crate) fn index(&self) -> &crate::ColumnIndex<T> {
+        self.column.index()
+    }
+
+    /// Pass a write made through this guard to the column's index and journal.
+    pub(crate) fn record_write(&self, write: crate::ColumnWrite<'_, T>) {
+        self.column.record_write(write)
+    }
 }
 
 impl<'a, T> Deref for ReadColumn<'a, T> {
@@ -1,8 +1,6 @@
 use std::ops::Deref;
 
-use async_std::sync::{RwLockReadGuard};
-
-use crate::{BorrowSingleton};
+use crate::{runtime::RwLockReadGuard, BorrowSingleton};
 
 /// A view into a [`Singleton`].
 #[derive(Debug)]
@@ -22,6 +20,26 @@ impl<'a, T> ReadSingleton<'a, T> {
         ReadSingleton { singleton_guard }
     }
 
+    /// Blocking counterpart of [`ReadSingleton::new`]. See [`block_on`](crate::block_on).
+    #[track_caller]
+    pub fn blocking<DB>(table: &'a DB) -> ReadSingleton<'a, T>
+    where
+        T: 'a,
+        DB: BorrowSingleton<T>,
+    {
+        crate::block_on(Self::new(table))
+    }
+
+    /// Lock the singleton if it is free, returning `None` instead of waiting otherwise.
+    pub fn try_new<DB>(table: &'a DB) -> Option<ReadSingleton<'a, T>>
+    where
+        T: 'a,
+        DB: BorrowSingleton<T>,
+    {
+        let singleton_guard = table.borrow().try_read()?;
+        Some(ReadSingleton { singleton_guard })
+    }
+
     pub fn singleton(&'a self) -> &'a T {
         self.singleton_guard.deref()
     }
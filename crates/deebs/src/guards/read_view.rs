@@ -1,6 +1,6 @@
 use std::{collections::BTreeSet, ops::Deref};
 
-use async_std::sync::RwLockReadGuard;
+use crate::runtime::RwLockReadGuard;
 use futures::Stream;
 
 use crate::{BorrowView, Key};
@@ -25,12 +25,12 @@ impl<'a> ReadView<'a> {
 
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace"))]
     pub fn keys_cloned(&self) -> impl Stream<Item = Key> {
-        async_std::stream::from_iter(self.keys_guard.deref().clone().into_iter())
+        crate::runtime::from_iter(self.keys_guard.deref().clone())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace"))]
     pub fn keys(&self) -> impl Stream<Item = &Key> {
-        async_std::stream::from_iter(self.keys_guard.iter())
+        crate::runtime::from_iter(self.keys_guard.iter())
     }
 }
 
@@ -0,0 +1,211 @@
+use std::{any::TypeId, collections::BTreeSet, future::Future, ops::Deref, panic::Location};
+
+use crate::runtime::RwLockWriteGuard;
+
+use crate::{
+    BorrowColumn, Column, ColumnCollection, ColumnWrite, Key, KeyAllocator, LockOrderToken,
+    ObserveEvent, Table,
+};
+
+/// A reversible change made to a [`TransactionColumn`].
+#[derive(Debug)]
+enum Undo<T> {
+    Insert(Key, Option<T>),
+    Remove(Key, T),
+    Modify(Key, T),
+}
+
+impl<T> Undo<T> {
+    fn key(&self) -> Key {
+        match self {
+            Undo::Insert(key, _) | Undo::Remove(key, _) | Undo::Modify(key, _) => *key,
+        }
+    }
+}
+
+/// A write guard over a [`Column`] that journals its changes so they can be rolled back.
+///
+/// Change records are only written to the column once the transaction commits,
+/// and changes that were never committed are rolled back when the guard is dropped.
+#[derive(Debug)]
+pub struct TransactionColumn<'a, T> {
+    column: &'a Column<T>,
+    column_guard: RwLockWriteGuard<'a, ColumnCollection<T>>,
+    key_allocator: Option<&'a KeyAllocator>,
+    journal: Vec<Undo<T>>,
+    _lock_order: LockOrderToken,
+}
+
+impl<'a, T> TransactionColumn<'a, T> {
+    #[track_caller]
+    pub fn new<DB>(table: &'a DB) -> impl Future<Output = TransactionColumn<'a, T>> + 'a
+    where
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
+    {
+        Self::new_at(table, Location::caller())
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(table)))]
+    pub(crate) async fn new_at<DB>(
+        table: &'a DB,
+        site: &'static Location<'static>,
+    ) -> TransactionColumn<'a, T>
+    where
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
+    {
+        let lock_order = LockOrderToken::acquire::<T>(site);
+        let column = table.borrow();
+        let mut column_guard = column.write().await;
+        column_guard.set_storage(table.column_storage(TypeId::of::<T>()));
+        column
+            .index()
+            .install(&column_guard, || table.column_index::<T>());
+        TransactionColumn {
+            column,
+            column_guard,
+            key_allocator: table.key_allocator(),
+            journal: vec![],
+            _lock_order: lock_order,
+        }
+    }
+
+    pub fn column(&self) -> &ColumnCollection<T> {
+        self.column_guard.deref()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.column_guard.reserve(additional);
+    }
+
+    /// Insert or replace the cell at `key`,
+    /// returning false without writing if `key` is stale.
+    pub fn insert(&mut self, key: Key, value: T) -> bool {
+        if self.key_allocator.is_some_and(|keys| keys.is_stale(&key)) {
+            return false;
+        }
+
+        if let Some(occupant) = self.column_guard.occupant(&key) {
+            self.remove(&occupant);
+        }
+
+        let prev = self.column_guard.insert(key, value);
+        self.journal.push(Undo::Insert(key, prev));
+        true
+    }
+
+    /// Remove the cell at `key`, returning true if it existed.
+    pub fn remove(&mut self, key: &Key) -> bool {
+        if let Some(cell) = self.column_guard.remove(key) {
+            self.journal.push(Undo::Remove(*key, cell));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Mutate the cell at `key` in place, returning true if it existed.
+    pub fn modify<F>(&mut self, key: &Key, f: F) -> bool
+    where
+        T: Clone,
+        F: FnOnce(&mut T),
+    {
+        if let Some(cell) = self.column_guard.get_mut(key) {
+            self.journal.push(Undo::Modify(*key, cell.clone()));
+            f(cell);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record this transaction's changes, add the keys it touched to `keys`
+    /// and release the column, returning its [`TypeId`] if anything was touched.
+    pub(crate) fn commit(mut self, keys: &mut BTreeSet<Key>) -> Option<TypeId>
+    where
+        T: 'static,
+    {
+        let journal = std::mem::take(&mut self.journal);
+        if journal.is_empty() {
+            return None;
+        }
+
+        let mut changes = self.column.changes();
+        let observers = self.column.observers();
+
+        let mut touched = BTreeSet::new();
+
+        for undo in journal {
+            keys.insert(undo.key());
+            touched.insert(undo.key());
+
+            match undo {
+                Undo::Insert(key, None) => {
+                    changes.record_added(key);
+                    observers.record(ObserveEvent::OnInsert, key);
+                }
+                Undo::Insert(key, Some(_)) | Undo::Modify(key, _) => {
+                    changes.record_changed(key);
+                    observers.record(ObserveEvent::OnChange, key);
+                }
+                Undo::Remove(key, _) => {
+                    changes.record_removed(key);
+                    observers.record(ObserveEvent::OnRemove, key);
+                }
+            }
+        }
+
+        // Index and journal the final state of each key rather than every intermediate write
+        for key in touched {
+            match self.column_guard.get_mut(&key) {
+                Some(value) => self.column.record_write(ColumnWrite::Insert(key, value)),
+                None => self.column.record_write(ColumnWrite::Remove(key)),
+            }
+        }
+
+        Some(TypeId::of::<T>())
+    }
+
+    /// Revert this transaction's changes in reverse order and release the column.
+    pub(crate) fn rollback(mut self) {
+        self.undo();
+    }
+
+    fn undo(&mut self) {
+        while let Some(undo) = self.journal.pop() {
+            match undo {
+                Undo::Insert(key, Some(prev)) => {
+                    self.column_guard.insert(key, prev);
+                }
+                Undo::Insert(key, None) => {
+                    self.column_guard.remove(&key);
+                }
+                Undo::Remove(key, cell) => {
+                    self.column_guard.insert(key, cell);
+                }
+                Undo::Modify(key, prev) => {
+                    if let Some(cell) = self.column_guard.get_mut(&key) {
+                        *cell = prev;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A [`TransactionColumn`] dropped without committing, as when its transaction panics,
+/// is rolled back.
+impl<'a, T> Drop for TransactionColumn<'a, T> {
+    fn drop(&mut self) {
+        self.undo();
+    }
+}
+
+impl<'a, T> Deref for TransactionColumn<'a, T> {
+    type Target = ColumnCollection<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.column()
+    }
+}
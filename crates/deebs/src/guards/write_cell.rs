@@ -1,21 +1,120 @@
 use std::ops::{Deref, DerefMut};
-use std::{marker::PhantomPinned, pin::Pin, ptr::NonNull};
+use std::{
+    future::Future, marker::PhantomPinned, panic::Location, pin::Pin, ptr::NonNull, sync::Arc,
+};
 
-use crate::{BorrowColumn, ColumnCollection, ReadColumn, Key};
+use futures::future::BoxFuture;
+
+use crate::{
+    BorrowColumn, CellError, CellLock, ColumnCollection, ColumnWrite, Key, ObserveEvent,
+    ReadColumn, Table,
+};
+
+/// Delivers the events recorded by a [`WriteCell`] once it is released.
+type Notify<'a> = Box<dyn FnOnce() -> BoxFuture<'a, ()> + Send + Sync + 'a>;
+
+/// Return a [`Notify`] calling [`Table::notify_observers`] for the `T` column of `table`.
+fn notify<'a, DB, T>(table: &'a DB) -> Notify<'a>
+where
+    T: Send + Sync + 'static,
+    DB: BorrowColumn<T> + Table + Sync,
+{
+    Box::new(move || table.notify_observers::<T>())
+}
 
 /// A view into one of the [`Cell`]s of a [`Column`]
-#[derive(Debug)]
-pub struct WriteCell<'a, T>(Pin<Box<WriteCellInner<'a, T>>>);
+///
+/// Release it with [`WriteCell::release`] to update views and observers for the write.
+/// A cell that is simply dropped leaves its events pending
+/// until the next [`Table::notify_observers`] call for its column.
+pub struct WriteCell<'a, T>(Pin<Box<WriteCellInner<'a, T>>>, Option<Notify<'a>>);
+
+impl<'a, T> std::fmt::Debug for WriteCell<'a, T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WriteCell").field(&self.0).finish()
+    }
+}
 
 impl<'a, T> WriteCell<'a, T> {
+    #[track_caller]
+    pub fn new<'b, DB>(
+        table: &'a DB,
+        key: &'b Key,
+    ) -> impl Future<Output = Result<WriteCell<'a, T>, CellError>> + 'b
+    where
+        'a: 'b,
+        T: Send + Sync + 'static,
+        DB: BorrowColumn<T> + Table + Sync,
+    {
+        Self::new_at(table, key, Location::caller())
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(table)))]
-    pub async fn new<DB>(table: &'a DB, key: &Key) -> Option<WriteCell<'a, T>>
+    pub(crate) async fn new_at<DB>(
+        table: &'a DB,
+        key: &Key,
+        site: &'static Location<'static>,
+    ) -> Result<WriteCell<'a, T>, CellError>
     where
-        T: 'a,
-        DB: BorrowColumn<T>,
+        T: Send + Sync + 'static,
+        DB: BorrowColumn<T> + Table + Sync,
     {
-        let inner = WriteCellInner::new(table, key).await;
-        inner.map(WriteCell)
+        let inner = WriteCellInner::new(table, key, site).await;
+        inner.map(|inner| WriteCell(inner, Some(notify(table))))
+    }
+
+    /// Blocking counterpart of [`WriteCell::new`]. See [`block_on`](crate::block_on).
+    #[track_caller]
+    pub fn blocking<DB>(table: &'a DB, key: &Key) -> Result<WriteCell<'a, T>, CellError>
+    where
+        T: Send + Sync + 'static,
+        DB: BorrowColumn<T> + Table + Sync,
+    {
+        crate::block_on(Self::new_at(table, key, Location::caller()))
+    }
+
+    /// Create a cell if it and its column are free,
+    /// returning [`CellError::Locked`] instead of waiting otherwise.
+    #[track_caller]
+    pub fn try_new<DB>(table: &'a DB, key: &Key) -> Result<WriteCell<'a, T>, CellError>
+    where
+        T: Send + Sync + 'static,
+        DB: BorrowColumn<T> + Table + Sync,
+    {
+        WriteCellInner::try_new(table, key, Location::caller())
+            .map(|inner| WriteCell(inner, Some(notify(table))))
+    }
+
+    /// Create a cell that shares an already-held column guard,
+    /// so that many cells can be taken from one lock acquisition.
+    ///
+    /// The cell doesn't know its table, so [`WriteCell::release`] only drops it,
+    /// leaving the caller to call [`Table::notify_observers`] once the column guard is dropped.
+    pub async fn from_column(
+        column_guard: Arc<ReadColumn<'a, T>>,
+        key: &Key,
+    ) -> Result<WriteCell<'a, T>, CellError> {
+        let inner = WriteCellInner::from_column(column_guard, key).await;
+        inner.map(|inner| WriteCell(inner, None))
+    }
+
+    /// Release the cell, then update views and observers for the write with
+    /// [`Table::notify_observers`].
+    ///
+    /// The lock is dropped before this returns, so that releasing several cells
+    /// and then awaiting their futures lets observers lock any of them.
+    pub fn release(mut self) -> impl Future<Output = ()> + 'a {
+        let notify = self.1.take();
+        drop(self);
+
+        async move {
+            if let Some(notify) = notify {
+                notify().await;
+            }
+        }
     }
 
     pub fn cell(&self) -> &T {
@@ -31,7 +130,7 @@ impl<'a, T> WriteCell<'a, T> {
 
     #[allow(dead_code)]
     pub fn column(&'a self) -> &'a ColumnCollection<T> {
-        self.0.deref().column_guard.deref()
+        self.0.column_guard.deref().deref()
     }
 }
 
@@ -53,7 +152,10 @@ impl<'a, T> DerefMut for WriteCell<'a, T> {
 /// Self-referential struct that holds both the column and cell read guards
 #[derive(Debug)]
 struct WriteCellInner<'a, T> {
-    column_guard: ReadColumn<'a, T>,
+    // Declared before the column guard so that it is released first
+    cell_lock: Option<CellLock<'a, T>>,
+    column_guard: Arc<ReadColumn<'a, T>>,
+    key: Key,
     item_guard: NonNull<T>,
     _pin: PhantomPinned,
 }
@@ -62,41 +164,114 @@ unsafe impl<'a, T> Send for WriteCellInner<'a, T> {}
 unsafe impl<'a, T> Sync for WriteCellInner<'a, T> {}
 
 impl<'a, T> WriteCellInner<'a, T> {
-    pub async fn new<DB>(db: &'a DB, key: &Key) -> Option<Pin<Box<WriteCellInner<'a, T>>>>
+    pub async fn new<DB>(
+        db: &'a DB,
+        key: &Key,
+        site: &'static Location<'static>,
+    ) -> Result<Pin<Box<WriteCellInner<'a, T>>>, CellError>
     where
-        T: 'a,
-        DB: BorrowColumn<T>,
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
     {
-        let column_guard = ReadColumn::new(db).await;
+        if db.is_stale(key) {
+            return Err(CellError::Stale(*key));
+        }
+
+        let column_guard = ReadColumn::new_at(db, site).await;
+        Self::from_column(Arc::new(column_guard), key).await
+    }
 
+    pub fn try_new<DB>(
+        db: &'a DB,
+        key: &Key,
+        site: &'static Location<'static>,
+    ) -> Result<Pin<Box<WriteCellInner<'a, T>>>, CellError>
+    where
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
+    {
+        if db.is_stale(key) {
+            return Err(CellError::Stale(*key));
+        }
+
+        let column_guard = ReadColumn::try_new_at(db, site).ok_or(CellError::Locked(*key))?;
+        let column_guard = Arc::new(column_guard);
+        let (item_guard, cell_lock) = unsafe { column(&column_guard) }.try_write_cell(key)?;
+
+        let boxed = Box::pin(WriteCellInner {
+            cell_lock: Some(cell_lock),
+            column_guard,
+            key: *key,
+            item_guard,
+            _pin: PhantomPinned,
+        });
+        boxed.record_change();
+
+        Ok(boxed)
+    }
+
+    pub async fn from_column(
+        column_guard: Arc<ReadColumn<'a, T>>,
+        key: &Key,
+    ) -> Result<Pin<Box<WriteCellInner<'a, T>>>, CellError> {
         if !column_guard.contains_key(key) {
-            return None
+            return Err(CellError::Missing(*key));
         }
 
         let guard = WriteCellInner {
+            cell_lock: None,
             column_guard,
+            key: *key,
             item_guard: NonNull::dangling(),
             _pin: PhantomPinned,
         };
-        
+
         let mut boxed = Box::pin(guard);
 
-        let item_guard = NonNull::from(
-            boxed
-                .column_guard
-                .get(key)
-                .unwrap()
-                .write()
-                .await
-                .deref(),
-        );
+        let (item_guard, cell_lock) = unsafe { column(&boxed.column_guard) }
+            .write_cell(key)
+            .await
+            .unwrap();
 
         unsafe {
             let mut_ref: Pin<&mut Self> = Pin::as_mut(&mut boxed);
-            Pin::get_unchecked_mut(mut_ref).item_guard = item_guard;
+            let inner = Pin::get_unchecked_mut(mut_ref);
+            inner.cell_lock = Some(cell_lock);
+            inner.item_guard = item_guard;
+        }
+
+        boxed.record_change();
+
+        Ok(boxed)
+    }
+
+    /// Flag the cell as changed for change tracking and observers.
+    fn record_change(&self) {
+        self.column_guard.changes().record_written(self.key);
+        self.column_guard
+            .observers()
+            .record(ObserveEvent::OnChange, self.key);
+    }
+}
+
+/// Borrow the column behind `column_guard` for as long as the guard's lock is held,
+/// so that a cell lock taken from it can be stored alongside the guard.
+///
+/// The caller must release the borrow before `column_guard`.
+unsafe fn column<'a, T>(column_guard: &Arc<ReadColumn<'a, T>>) -> &'a ColumnCollection<T> {
+    &*(&***column_guard as *const ColumnCollection<T>)
+}
+
+/// A [`WriteCell`] commits its write to the column's index and journal when it is released.
+impl<'a, T> Drop for WriteCellInner<'a, T> {
+    fn drop(&mut self) {
+        if self.item_guard == NonNull::dangling() {
+            return;
         }
 
-        Some(boxed)
+        let value = unsafe { self.item_guard.as_ref() };
+        self.column_guard
+            .record_write(ColumnWrite::Insert(self.key, value));
     }
 }
 
@@ -1,34 +1,160 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    any::TypeId,
+    future::Future,
+    ops::{Deref, DerefMut},
+    panic::Location,
+};
 
-use async_std::sync::RwLockWriteGuard;
+use crate::runtime::RwLockWriteGuard;
 
-use crate::{BorrowColumn, ColumnCollection};
+use crate::{
+    BorrowColumn, CellError, Column, ColumnCollection, ColumnWrite, Key, KeyAllocator,
+    LockOrderToken, ObserveEvent, Table,
+};
 
 /// A view into a [`Column`]
 #[derive(Debug)]
 pub struct WriteColumn<'a, T> {
+    column: &'a Column<T>,
     column_guard: RwLockWriteGuard<'a, ColumnCollection<T>>,
+    key_allocator: Option<&'a KeyAllocator>,
+    pending: PendingWrite,
+    _lock_order: LockOrderToken,
+}
+
+/// Writes to a [`WriteColumn`] that can only be indexed and journaled once it is released.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum PendingWrite {
+    None,
+    /// Every cell may have been written through [`WriteColumn::slices_mut`].
+    Cells,
+    /// The collection may have been changed arbitrarily through [`WriteColumn::column_mut`].
+    Column,
 }
 
 impl<'a, T> WriteColumn<'a, T> {
+    #[track_caller]
+    pub fn new<DB>(table: &'a DB) -> impl Future<Output = WriteColumn<'a, T>> + 'a
+    where
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
+    {
+        Self::new_at(table, Location::caller())
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(table)))]
-    pub async fn new<DB>(table: &'a DB) -> WriteColumn<'a, T>
+    pub(crate) async fn new_at<DB>(
+        table: &'a DB,
+        site: &'static Location<'static>,
+    ) -> WriteColumn<'a, T>
     where
-        T: 'a,
-        DB: BorrowColumn<T>,
+        T: 'static,
+        DB: BorrowColumn<T> + Table,
     {
+        let lock_order = LockOrderToken::acquire::<T>(site);
         let column = table.borrow();
-        let column_guard = column.write().await;
-        WriteColumn { column_guard }
+        let mut column_guard = column.write().await;
+        column_guard.set_storage(table.column_storage(TypeId::of::<T>()));
+        column
+            .index()
+            .install(&column_guard, || table.column_index::<T>());
+        WriteColumn {
+            column,
+            column_guard,
+            key_allocator: table.key_allocator(),
+            pending: PendingWrite::None,
+            _lock_order: lock_order,
+        }
     }
 
     pub fn column(&self) -> &ColumnCollection<T> {
         self.column_guard.deref()
     }
 
+    /// Return the underlying collection for arbitrary writes,
+    /// which are recorded as a write to the column as a whole rather than to any key.
     pub fn column_mut(&mut self) -> &mut ColumnCollection<T> {
+        self.column.changes().touch();
+        self.pending = PendingWrite::Column;
+
         self.column_guard.deref_mut()
     }
+
+    /// Insert a cell, recording it as added or changed in the column's change record.
+    ///
+    /// Writes through a stale key are refused and `value` is dropped,
+    /// since the key's slot may have been reused. Use [`WriteColumn::try_insert`] to detect them.
+    pub fn insert(&mut self, key: Key, value: T) -> Option<T> {
+        self.try_insert(key, value).ok().flatten()
+    }
+
+    /// Insert a cell as by [`WriteColumn::insert`],
+    /// returning [`CellError::Stale`] without writing if `key` is stale.
+    pub fn try_insert(&mut self, key: Key, value: T) -> Result<Option<T>, CellError> {
+        if self.key_allocator.is_some_and(|keys| keys.is_stale(&key)) {
+            return Err(CellError::Stale(key));
+        }
+
+        // A dense column holds one generation per slot, so evict any other like a removal
+        if let Some(occupant) = self.column_guard.occupant(&key) {
+            self.remove(&occupant);
+        }
+
+        let prev = self.column_guard.insert(key, value);
+
+        let mut changes = self.column.changes();
+        if prev.is_some() {
+            changes.record_changed(key);
+            self.column.observers().record(ObserveEvent::OnChange, key);
+        } else {
+            changes.record_added(key);
+            self.column.observers().record(ObserveEvent::OnInsert, key);
+        }
+
+        if self.column.is_recording() {
+            if let Some(value) = self.column_guard.get_mut(&key) {
+                self.column.record_write(ColumnWrite::Insert(key, value));
+            }
+        }
+
+        Ok(prev)
+    }
+
+    /// Remove a cell, recording it as removed in the column's change record.
+    pub fn remove(&mut self, key: &Key) -> Option<T> {
+        let prev = self.column_guard.remove(key);
+
+        if prev.is_some() {
+            self.column.changes().record_removed(*key);
+            self.column.observers().record(ObserveEvent::OnRemove, *key);
+            self.column.record_write(ColumnWrite::Remove(*key));
+        }
+
+        prev
+    }
+
+    /// Return the keys and values of a dense column as parallel slices.
+    pub fn slices(&mut self) -> Option<(&[Key], &[T])> {
+        self.column_guard.slices()
+    }
+
+    /// Return the keys and mutable values of a dense column as parallel slices,
+    /// recording every cell as changed in the column's change record.
+    pub fn slices_mut(&mut self) -> Option<(&[Key], &mut [T])> {
+        if self.pending == PendingWrite::None {
+            self.pending = PendingWrite::Cells;
+        }
+
+        let (keys, values) = self.column_guard.slices_mut()?;
+
+        let mut changes = self.column.changes();
+        for key in keys {
+            changes.record_written(*key);
+            self.column.observers().record(ObserveEvent::OnChange, *key);
+        }
+
+        Some((keys, values))
+    }
 }
 
 impl<'a, T> Deref for WriteColumn<'a, T> {
@@ -43,4 +169,22 @@ impl<'a, T> DerefMut for WriteColumn<'a, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.column_mut()
     }
-}
\ No newline at end of file
+}
+
+impl<'a, T> Drop for WriteColumn<'a, T> {
+    fn drop(&mut self) {
+        match self.pending {
+            PendingWrite::None => (),
+            PendingWrite::Cells => {
+                if let Some((keys, values)) = self.column_guard.slices() {
+                    for (key, value) in keys.iter().zip(values) {
+                        self.column.record_write(ColumnWrite::Insert(*key, value));
+                    }
+                }
+            }
+            PendingWrite::Column => self
+                .column
+                .record_write(ColumnWrite::Replace(&self.column_guard)),
+        }
+    }
+}
@@ -1,8 +1,6 @@
 use std::ops::{Deref, DerefMut};
 
-use async_std::sync::RwLockWriteGuard;
-
-use crate::BorrowSingleton;
+use crate::{runtime::RwLockWriteGuard, BorrowSingleton};
 
 /// A view into one a [`Column`]
 #[derive(Debug)]
@@ -22,6 +20,26 @@ impl<'a, T> WriteSingleton<'a, T> {
         WriteSingleton { singleton_guard }
     }
 
+    /// Blocking counterpart of [`WriteSingleton::new`]. See [`block_on`](crate::block_on).
+    #[track_caller]
+    pub fn blocking<DB>(table: &'a DB) -> WriteSingleton<'a, T>
+    where
+        T: 'a,
+        DB: BorrowSingleton<T>,
+    {
+        crate::block_on(Self::new(table))
+    }
+
+    /// Lock the singleton if it is free, returning `None` instead of waiting otherwise.
+    pub fn try_new<DB>(table: &'a DB) -> Option<WriteSingleton<'a, T>>
+    where
+        T: 'a,
+        DB: BorrowSingleton<T>,
+    {
+        let singleton_guard = table.borrow().try_write()?;
+        Some(WriteSingleton { singleton_guard })
+    }
+
     pub fn singleton(&self) -> &T {
         self.singleton_guard.deref()
     }
@@ -0,0 +1,230 @@
+use std::{collections::BTreeSet, fmt::Display, ops::Deref};
+
+use crate::{BorrowColumn, Key, KeyMap, MapKeys, ReadColumn, Table};
+
+/// The entity a key is attached to, maintained alongside its [`Children`] by [`Table::set_parent`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Parent(Key);
+
+impl Parent {
+    pub fn key(&self) -> Key {
+        self.0
+    }
+}
+
+impl Deref for Parent {
+    type Target = Key;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl MapKeys for Parent {
+    fn held_keys(&self) -> Vec<Key> {
+        self.0.held_keys()
+    }
+
+    fn map_keys(&mut self, keys: &KeyMap) -> bool {
+        self.0.map_keys(keys)
+    }
+}
+
+/// The entities attached to a key, in the order they were attached.
+///
+/// Only present while the key has at least one child.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Children(Vec<Key>);
+
+impl Deref for Children {
+    type Target = [Key];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl MapKeys for Children {
+    fn held_keys(&self) -> Vec<Key> {
+        self.0.clone()
+    }
+
+    fn map_keys(&mut self, keys: &KeyMap) -> bool {
+        self.0.map_keys(keys);
+        !self.0.is_empty()
+    }
+}
+
+/// The reason a [`Table::set_parent`] call was refused.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HierarchyError {
+    /// The parent is the child itself, or one of its descendants.
+    Cycle { child: Key, parent: Key },
+    /// The key's entity has been freed, and its slot may since have been reused.
+    Stale(Key),
+}
+
+impl Display for HierarchyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HierarchyError::Cycle { child, parent } => write!(
+                f,
+                "Key {} can't be parented to {}, as it would form a cycle.",
+                child, parent
+            ),
+            HierarchyError::Stale(key) => write!(f, "Key {} is stale.", key),
+        }
+    }
+}
+
+impl std::error::Error for HierarchyError {}
+
+pub(crate) async fn set_parent<Tbl>(
+    table: &Tbl,
+    child: Key,
+    parent: Key,
+) -> Result<(), HierarchyError>
+where
+    Tbl: Table + BorrowColumn<Parent> + BorrowColumn<Children> + Send + Sync,
+{
+    for key in [child, parent].iter() {
+        if table.is_stale(key) {
+            return Err(HierarchyError::Stale(*key));
+        }
+    }
+
+    table
+        .transaction::<(Parent, Children), _, _, _>(|(parents, children)| {
+            let mut ancestor = Some(parent);
+            while let Some(key) = ancestor {
+                if key == child {
+                    return Err(HierarchyError::Cycle { child, parent });
+                }
+                ancestor = parents.try_with_cell(&key, Parent::key).ok();
+            }
+
+            if let Ok(prev) = parents.try_with_cell(&child, Parent::key) {
+                if prev == parent {
+                    return Ok(());
+                }
+                detach(children, prev, child);
+            }
+
+            parents.insert(child, Parent(parent));
+            if !children.modify(&parent, |children| children.0.push(child)) {
+                children.insert(parent, Children(vec![child]));
+            }
+
+            Ok(())
+        })
+        .await
+}
+
+pub(crate) async fn remove_parent<Tbl>(table: &Tbl, child: Key) -> Option<Key>
+where
+    Tbl: Table + BorrowColumn<Parent> + BorrowColumn<Children> + Send + Sync,
+{
+    table
+        .transaction::<(Parent, Children), _, _, ()>(|(parents, children)| {
+            let parent = parents.try_with_cell(&child, Parent::key).map_err(|_| ())?;
+            parents.remove(&child);
+            detach(children, parent, child);
+            Ok(parent)
+        })
+        .await
+        .ok()
+}
+
+/// Remove `child` from the [`Children`] of `parent`, dropping the cell once it is empty.
+fn detach(children: &mut crate::TransactionColumn<'_, Children>, parent: Key, child: Key) {
+    let empty = children
+        .try_with_cell(&parent, |children| children.0 == [child])
+        .unwrap_or_default();
+
+    if empty {
+        children.remove(&parent);
+    } else {
+        children.modify(&parent, |children| children.0.retain(|key| *key != child));
+    }
+}
+
+/// Return every descendant of `root` in depth-first order, excluding `root` itself.
+pub(crate) async fn descendants<Tbl>(table: &Tbl, root: Key) -> Vec<Key>
+where
+    Tbl: BorrowColumn<Children>,
+{
+    let children = ReadColumn::<Children>::new(table).await;
+
+    let mut descendants = vec![];
+    let mut stack = vec![root];
+    while let Some(key) = stack.pop() {
+        if key != root {
+            descendants.push(key);
+        }
+
+        if let Some(keys) = children
+            .with_cell(&key, |children| children.0.clone())
+            .await
+        {
+            stack.extend(keys.into_iter().rev());
+        }
+    }
+
+    descendants
+}
+
+/// Return every ancestor of `key`, nearest first.
+pub(crate) async fn ancestors<Tbl>(table: &Tbl, key: Key) -> Vec<Key>
+where
+    Tbl: BorrowColumn<Parent>,
+{
+    let parents = ReadColumn::<Parent>::new(table).await;
+
+    let mut ancestors = vec![];
+    let mut ancestor = parents.with_cell(&key, Parent::key).await;
+    while let Some(key) = ancestor {
+        ancestors.push(key);
+        ancestor = parents.with_cell(&key, Parent::key).await;
+    }
+
+    ancestors
+}
+
+/// Extend `keys` with all of their descendants, and detach each from any parent that is not
+/// itself being despawned, returning the full set of keys to despawn.
+///
+/// Called by the `Table` derive's `despawn_cells` for tables with both hierarchy columns.
+#[doc(hidden)]
+pub async fn despawn_hierarchy<Tbl>(table: &Tbl, keys: Vec<Key>) -> Vec<Key>
+where
+    Tbl: Table + BorrowColumn<Parent> + BorrowColumn<Children> + Send + Sync,
+{
+    // Descendants are walked under the same locks that detach them,
+    // so no child can be attached in between and outlive its despawned parent
+    table
+        .transaction::<(Parent, Children), _, _, ()>(|(parents, children)| {
+            let mut despawned = BTreeSet::new();
+            let mut stack = keys;
+            stack.reverse();
+            while let Some(key) = stack.pop() {
+                if despawned.insert(key) {
+                    if let Ok(keys) = children.try_with_cell(&key, |children| children.0.clone()) {
+                        stack.extend(keys.into_iter().rev());
+                    }
+                }
+            }
+
+            for key in despawned.iter() {
+                if let Ok(parent) = parents.try_with_cell(key, Parent::key) {
+                    if !despawned.contains(&parent) {
+                        detach(children, parent, *key);
+                    }
+                }
+            }
+            Ok(despawned.into_iter().collect())
+        })
+        .await
+        .unwrap_or_default()
+}
@@ -0,0 +1,275 @@
+use std::{
+    any::Any,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    hash::Hash,
+    ops::Bound,
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::{ColumnCollection, ColumnWrite, Key};
+
+/// A secondary index from the values of a [`Column`](crate::Column) to the keys holding them.
+///
+/// Indexes are created by [`Table::column_index`](crate::Table::column_index)
+/// for columns marked `#[index]`, and kept up to date by the deebs guards as cells are written.
+pub trait CellIndex<T>: Send + Sync {
+    /// Index `value` at `key`, replacing any value previously indexed there.
+    fn insert(&mut self, key: Key, value: &T);
+
+    /// Remove the value indexed at `key`.
+    fn remove(&mut self, key: &Key);
+
+    /// Remove every indexed value.
+    fn clear(&mut self);
+
+    /// Return the keys whose value equals `value`.
+    fn find(&self, value: &T) -> BTreeSet<Key>;
+
+    /// Return the keys whose value lies within `range`,
+    /// or `None` if this index doesn't order its values.
+    fn range(&self, range: (Bound<&T>, Bound<&T>)) -> Option<BTreeSet<Key>>;
+}
+
+/// A [`CellIndex`] over a hash map, created by `#[index(hash)]`.
+///
+/// Supports [`Table::find`](crate::Table::find);
+/// [`Table::range`](crate::Table::range) falls back to scanning the column.
+#[derive(Debug)]
+pub struct HashIndex<T> {
+    values: HashMap<T, BTreeSet<Key>>,
+    keys: HashMap<Key, T, fnv::FnvBuildHasher>,
+}
+
+impl<T> Default for HashIndex<T> {
+    fn default() -> Self {
+        HashIndex {
+            values: Default::default(),
+            keys: Default::default(),
+        }
+    }
+}
+
+impl<T> CellIndex<T> for HashIndex<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    fn insert(&mut self, key: Key, value: &T) {
+        self.remove(&key);
+        self.values.entry(value.clone()).or_default().insert(key);
+        self.keys.insert(key, value.clone());
+    }
+
+    fn remove(&mut self, key: &Key) {
+        if let Some(value) = self.keys.remove(key) {
+            if let Some(keys) = self.values.get_mut(&value) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.values.remove(&value);
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+        self.keys.clear();
+    }
+
+    fn find(&self, value: &T) -> BTreeSet<Key> {
+        self.values.get(value).cloned().unwrap_or_default()
+    }
+
+    fn range(&self, _: (Bound<&T>, Bound<&T>)) -> Option<BTreeSet<Key>> {
+        None
+    }
+}
+
+/// A [`CellIndex`] over a B-tree, created by `#[index]`.
+///
+/// Supports both [`Table::find`](crate::Table::find) and [`Table::range`](crate::Table::range).
+#[derive(Debug)]
+pub struct OrdIndex<T> {
+    values: BTreeMap<T, BTreeSet<Key>>,
+    keys: HashMap<Key, T, fnv::FnvBuildHasher>,
+}
+
+impl<T> Default for OrdIndex<T> {
+    fn default() -> Self {
+        OrdIndex {
+            values: Default::default(),
+            keys: Default::default(),
+        }
+    }
+}
+
+impl<T> CellIndex<T> for OrdIndex<T>
+where
+    T: Ord + Clone + Send + Sync,
+{
+    fn insert(&mut self, key: Key, value: &T) {
+        self.remove(&key);
+        self.values.entry(value.clone()).or_default().insert(key);
+        self.keys.insert(key, value.clone());
+    }
+
+    fn remove(&mut self, key: &Key) {
+        if let Some(value) = self.keys.remove(key) {
+            if let Some(keys) = self.values.get_mut(&value) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.values.remove(&value);
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+        self.keys.clear();
+    }
+
+    fn find(&self, value: &T) -> BTreeSet<Key> {
+        self.values.get(value).cloned().unwrap_or_default()
+    }
+
+    fn range(&self, range: (Bound<&T>, Bound<&T>)) -> Option<BTreeSet<Key>> {
+        // BTreeMap::range panics on inverted or empty exclusive ranges
+        let empty = match range {
+            (Bound::Included(start), Bound::Included(end)) => start > end,
+            (Bound::Included(start), Bound::Excluded(end))
+            | (Bound::Excluded(start), Bound::Included(end))
+            | (Bound::Excluded(start), Bound::Excluded(end)) => start >= end,
+            _ => false,
+        };
+
+        if empty {
+            return Some(BTreeSet::new());
+        }
+
+        Some(
+            self.values
+                .range(range)
+                .flat_map(|(_, keys)| keys.iter().copied())
+                .collect(),
+        )
+    }
+}
+
+/// Convert a boxed index over `U` into one over `T`, if they are the same type.
+///
+/// Used by the `Table` derive to return an index from the generic [`Table::column_index`](crate::Table::column_index).
+#[doc(hidden)]
+pub fn downcast_index<U, T>(index: Box<dyn CellIndex<U>>) -> Option<Box<dyn CellIndex<T>>>
+where
+    U: 'static,
+    T: 'static,
+{
+    let index: Box<dyn Any> = Box::new(index);
+    index
+        .downcast::<Box<dyn CellIndex<T>>>()
+        .ok()
+        .map(|index| *index)
+}
+
+struct ColumnIndexInner<T> {
+    /// Whether the owning table has been asked for this column's index.
+    checked: bool,
+    index: Option<Box<dyn CellIndex<T>>>,
+}
+
+/// The secondary index of a [`Column`](crate::Column), if it has one.
+///
+/// Created on the column's first write lock, and updated under the column lock
+/// by each write made through the deebs guards.
+pub(crate) struct ColumnIndex<T>(RwLock<ColumnIndexInner<T>>);
+
+impl<T> Default for ColumnIndex<T> {
+    fn default() -> Self {
+        ColumnIndex(RwLock::new(ColumnIndexInner {
+            checked: false,
+            index: None,
+        }))
+    }
+}
+
+impl<T> std::fmt::Debug for ColumnIndex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ColumnIndex")
+            .field(&self.is_installed())
+            .finish()
+    }
+}
+
+impl<T> ColumnIndex<T> {
+    fn read(&self) -> RwLockReadGuard<'_, ColumnIndexInner<T>> {
+        self.0.read().expect("Column index is poisoned.")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, ColumnIndexInner<T>> {
+        self.0.write().expect("Column index is poisoned.")
+    }
+
+    pub(crate) fn is_installed(&self) -> bool {
+        self.read().index.is_some()
+    }
+
+    /// Create this column's index with `f` and fill it from `cells`,
+    /// unless the table has already been asked for it.
+    pub(crate) fn install(
+        &self,
+        cells: &ColumnCollection<T>,
+        f: impl FnOnce() -> Option<Box<dyn CellIndex<T>>>,
+    ) {
+        if self.read().checked {
+            return;
+        }
+
+        let mut inner = self.write();
+        if inner.checked {
+            return;
+        }
+
+        inner.checked = true;
+        inner.index = f();
+        if let Some(index) = inner.index.as_mut() {
+            fill(index.as_mut(), cells);
+        }
+    }
+
+    pub(crate) fn record(&self, write: &ColumnWrite<'_, T>) {
+        if let Some(index) = self.write().index.as_mut() {
+            match write {
+                ColumnWrite::Insert(key, value) => index.insert(*key, value),
+                ColumnWrite::Remove(key) => index.remove(key),
+                ColumnWrite::Replace(cells) => {
+                    index.clear();
+                    fill(index.as_mut(), cells);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn find(&self, value: &T) -> Option<BTreeSet<Key>> {
+        self.read().index.as_ref().map(|index| index.find(value))
+    }
+
+    pub(crate) fn range(&self, range: (Bound<&T>, Bound<&T>)) -> Option<BTreeSet<Key>> {
+        self.read().index.as_ref()?.range(range)
+    }
+
+    /// Drop the index, to be recreated by the next write lock.
+    pub(crate) fn reset(&mut self) {
+        *self.0.get_mut().expect("Column index is poisoned.") = ColumnIndexInner {
+            checked: false,
+            index: None,
+        };
+    }
+}
+
+fn fill<T>(index: &mut dyn CellIndex<T>, cells: &ColumnCollection<T>) {
+    for key in cells.keys() {
+        cells
+            .try_with_cell(key, |value| index.insert(*key, value))
+            .ok();
+    }
+}
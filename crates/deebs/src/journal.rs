@@ -0,0 +1,658 @@
+//! An append-only journal of column writes, enabled by the `journal` feature.
+//!
+//! Once attached with [`Table::attach_journal`], every insert, remove and [`WriteCell`](crate::WriteCell)
+//! commit on a serializable column is appended to the journal's file before the writer's guard is released,
+//! along with every key the table allocates or frees.
+//! [`Table::replay`] reapplies a journal to reconstruct the table after a restart,
+//! and [`Table::compact_journal`] replaces its history with a snapshot of the current state.
+//!
+//! Each record is framed as a little-endian `u32` payload length, a little-endian CRC-32 of the payload,
+//! and the payload itself as JSON. A record that is cut short or fails its checksum marks the end of the journal;
+//! it and anything after it are discarded when the journal is opened.
+//!
+//! Writes that bypass the deebs guards, such as locking a [`Column`] directly, are not journaled.
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt::Display,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, RwLock},
+};
+
+use futures::future::BoxFuture;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    key::KeySlots, BorrowColumn, Column, ColumnCollection, ColumnWrite, Key, ReadColumn, Table,
+    WriteColumn,
+};
+
+const HEADER_LEN: usize = 8;
+
+/// One entry in a [`Journal`].
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum Record {
+    /// A key was allocated.
+    Alloc(Key),
+    /// A key was freed.
+    Free(Key),
+    /// The key allocator was replaced wholesale.
+    Keys(KeySlots),
+    /// A cell was inserted or written.
+    Insert {
+        column: String,
+        key: Key,
+        value: Value,
+    },
+    /// A cell was removed.
+    Remove { column: String, key: Key },
+    /// A column was replaced wholesale.
+    Column {
+        column: String,
+        cells: Vec<(Key, Value)>,
+    },
+    /// The state of the table when the journal was last compacted.
+    Snapshot {
+        keys: Option<KeySlots>,
+        columns: BTreeMap<String, Vec<(Key, Value)>>,
+    },
+}
+
+fn encode(record: &Record) -> io::Result<Vec<u8>> {
+    let payload = serde_json::to_vec(record)?;
+    let len = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Journal record is too long."))?;
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+    bytes.extend_from_slice(&len.to_le_bytes());
+    bytes.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+/// Decode every intact record at the start of `bytes`,
+/// returning them along with the number of bytes they occupy.
+fn decode(bytes: &[u8]) -> (Vec<Record>, usize) {
+    let mut records = vec![];
+    let mut offset = 0;
+
+    while let Some(header) = bytes.get(offset..offset + HEADER_LEN) {
+        let len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let crc = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+        let start = offset + HEADER_LEN;
+        let payload = match bytes.get(start..start + len) {
+            Some(payload) if crc32fast::hash(payload) == crc => payload,
+            _ => break,
+        };
+
+        match serde_json::from_slice(payload) {
+            Ok(record) => records.push(record),
+            Err(_) => break,
+        }
+
+        offset = start + len;
+    }
+
+    (records, offset)
+}
+
+/// Read the intact records of the file at `path`, truncating any damaged tail.
+fn recover(path: &Path) -> io::Result<Vec<Record>> {
+    let bytes = std::fs::read(path)?;
+    let (records, len) = decode(&bytes);
+
+    if len < bytes.len() {
+        let file = OpenOptions::new().write(true).open(path)?;
+        file.set_len(len as u64)?;
+        file.sync_all()?;
+    }
+
+    Ok(records)
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+struct JournalFile {
+    file: File,
+    /// Set by the first write failure, after which nothing more is appended.
+    failed: bool,
+    error: Option<io::Error>,
+}
+
+/// An append-only file of table writes.
+///
+/// Open one with [`Journal::open`], [`Table::replay`] it into a fresh table,
+/// then [`Table::attach_journal`] it to keep it up to date.
+pub struct Journal {
+    path: PathBuf,
+    file: Mutex<JournalFile>,
+    compaction: futures::lock::Mutex<()>,
+}
+
+impl std::fmt::Debug for Journal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Journal").field("path", &self.path).finish()
+    }
+}
+
+impl Journal {
+    /// Open the journal at `path`, creating it if it doesn't exist.
+    ///
+    /// A damaged tail is truncated, and a compaction interrupted by a crash is rolled back.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+
+        let mut file = open_append(&path)?;
+        recover(&path)?;
+
+        let compacted = Self::compacted_path(&path);
+        if compacted.exists() {
+            std::fs::remove_file(&compacted)?;
+        }
+
+        // Records appended while a compaction was in progress only exist in its side file
+        let pending = Self::pending_path(&path);
+        if pending.exists() {
+            for record in recover(&pending)? {
+                file.write_all(&encode(&record)?)?;
+            }
+            file.sync_all()?;
+            std::fs::remove_file(&pending)?;
+        }
+
+        Ok(Journal {
+            path,
+            file: Mutex::new(JournalFile {
+                file,
+                failed: false,
+                error: None,
+            }),
+            compaction: Default::default(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn pending_path(path: &Path) -> PathBuf {
+        path.with_extension("pending")
+    }
+
+    fn compacted_path(path: &Path) -> PathBuf {
+        path.with_extension("compacted")
+    }
+
+    fn file(&self) -> MutexGuard<'_, JournalFile> {
+        self.file.lock().expect("Journal is poisoned.")
+    }
+
+    /// Return the error that stopped this journal from being written, if any.
+    ///
+    /// Writes are journaled from inside table methods that can't fail,
+    /// so the first failure is stored here and every later record is dropped.
+    pub fn take_error(&self) -> Option<io::Error> {
+        self.file().error.take()
+    }
+
+    /// Return true if a write failure has stopped this journal from being written.
+    pub fn is_failed(&self) -> bool {
+        self.file().failed
+    }
+
+    /// Flush appended records to disk.
+    pub fn sync(&self) -> io::Result<()> {
+        self.file().file.sync_data()
+    }
+
+    pub(crate) fn append(&self, record: &Record) {
+        let mut file = self.file();
+        if file.failed {
+            return;
+        }
+
+        if let Err(e) = encode(record).and_then(|bytes| file.file.write_all(&bytes)) {
+            file.failed = true;
+            file.error = Some(e);
+        }
+    }
+
+    fn fail(&self, error: impl Into<io::Error>) {
+        let mut file = self.file();
+        if !file.failed {
+            file.failed = true;
+            file.error = Some(error.into());
+        }
+    }
+
+    pub(crate) fn records(&self) -> io::Result<Vec<Record>> {
+        let bytes = std::fs::read(&self.path)?;
+        Ok(decode(&bytes).0)
+    }
+
+    /// Divert appended records into a side file until [`Journal::finish_compaction`].
+    fn begin_compaction(&self) -> io::Result<()> {
+        let pending = open_append(&Self::pending_path(&self.path))?;
+        self.file().file = pending;
+        Ok(())
+    }
+
+    /// Append the records diverted since [`Journal::begin_compaction`] to the journal,
+    /// and resume appending to it.
+    fn abort_compaction(&self) -> io::Result<()> {
+        let mut file = self.file();
+
+        let pending_path = Self::pending_path(&self.path);
+        file.file.sync_all()?;
+        let pending = std::fs::read(&pending_path)?;
+        let (_, pending_len) = decode(&pending);
+
+        file.file = open_append(&self.path)?;
+        file.file.write_all(&pending[..pending_len])?;
+        file.file.sync_all()?;
+        std::fs::remove_file(&pending_path)?;
+
+        Ok(())
+    }
+
+    /// Replace the journal with `snapshot` followed by the records diverted since
+    /// [`Journal::begin_compaction`], and resume appending to it.
+    fn finish_compaction(&self, snapshot: &Record) -> io::Result<()> {
+        let mut file = self.file();
+
+        let pending_path = Self::pending_path(&self.path);
+        let compacted_path = Self::compacted_path(&self.path);
+
+        file.file.sync_all()?;
+        let pending = std::fs::read(&pending_path)?;
+        let (_, pending_len) = decode(&pending);
+
+        let mut compacted = File::create(&compacted_path)?;
+        compacted.write_all(&encode(snapshot)?)?;
+        compacted.write_all(&pending[..pending_len])?;
+        compacted.sync_all()?;
+
+        // Should this be interrupted, reopening replays the pending records on top of
+        // whichever journal survived; doing so twice is harmless, as replay is idempotent.
+        std::fs::rename(&compacted_path, &self.path)?;
+        file.file = open_append(&self.path)?;
+        std::fs::remove_file(&pending_path)?;
+
+        Ok(())
+    }
+}
+
+/// The reason a [`Journal`] could not be replayed or compacted.
+#[derive(Debug)]
+pub enum JournalError {
+    Io(io::Error),
+    /// A cell could not be converted to or from JSON.
+    Json(serde_json::Error),
+    /// A record names a column that the table doesn't journal.
+    UnknownColumn(String),
+}
+
+impl Display for JournalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JournalError::Io(e) => write!(f, "Journal IO failed: {}", e),
+            JournalError::Json(e) => write!(f, "Journal record is invalid: {}", e),
+            JournalError::UnknownColumn(column) => {
+                write!(f, "Journal names unknown column {}.", column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for JournalError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JournalError::Io(e) => Some(e),
+            JournalError::Json(e) => Some(e),
+            JournalError::UnknownColumn(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for JournalError {
+    fn from(e: io::Error) -> Self {
+        JournalError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for JournalError {
+    fn from(e: serde_json::Error) -> Self {
+        JournalError::Json(e)
+    }
+}
+
+type ColumnHook<T> = Box<dyn Fn(ColumnWrite<'_, T>) + Send + Sync>;
+
+/// The journal a [`Column`] records its writes to, if one is attached.
+pub(crate) struct ColumnJournal<T>(RwLock<Option<ColumnHook<T>>>);
+
+impl<T> Default for ColumnJournal<T> {
+    fn default() -> Self {
+        ColumnJournal(Default::default())
+    }
+}
+
+impl<T> std::fmt::Debug for ColumnJournal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ColumnJournal")
+            .field(&self.is_attached())
+            .finish()
+    }
+}
+
+impl<T> ColumnJournal<T> {
+    pub(crate) fn is_attached(&self) -> bool {
+        self.0
+            .read()
+            .expect("Column journal is poisoned.")
+            .is_some()
+    }
+
+    pub(crate) fn record(&self, write: ColumnWrite<'_, T>) {
+        if let Some(hook) = &*self.0.read().expect("Column journal is poisoned.") {
+            hook(write)
+        }
+    }
+
+    fn attach(&self, hook: ColumnHook<T>) {
+        *self.0.write().expect("Column journal is poisoned.") = Some(hook);
+    }
+}
+
+fn cells_to_json<T>(cells: &ColumnCollection<T>) -> Result<Vec<(Key, Value)>, serde_json::Error>
+where
+    T: Serialize,
+{
+    let mut keys = cells.keys().copied().collect::<Vec<_>>();
+    keys.sort();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            cells
+                .try_with_cell(&key, |value: &T| serde_json::to_value(value))
+                .ok()
+                .map(|value| Ok((key, value?)))
+        })
+        .collect()
+}
+
+pub type AttachColumnFn<Tbl> = fn(&Tbl, String, Arc<Journal>);
+pub type DumpColumnFn<Tbl> =
+    for<'a> fn(&'a Tbl) -> BoxFuture<'a, Result<Vec<(Key, Value)>, serde_json::Error>>;
+pub type WriteColumnFn<Tbl> =
+    for<'a> fn(&'a Tbl, JournalWrite) -> BoxFuture<'a, Result<(), serde_json::Error>>;
+pub type NotifyColumnFn<Tbl> = for<'a> fn(&'a Tbl) -> BoxFuture<'a, ()>;
+
+/// A replayed write to one column.
+pub enum JournalWrite {
+    Insert(Key, Value),
+    Remove(Key),
+    /// Replace every cell in the column.
+    Replace(Vec<(Key, Value)>),
+}
+
+/// Type-erased journal operations for a column whose type is serializable.
+pub struct JournalFns<Tbl> {
+    /// Start recording the column's writes to a journal under the given column name.
+    pub attach: AttachColumnFn<Tbl>,
+    /// Serialize every cell in the column.
+    pub dump: DumpColumnFn<Tbl>,
+    /// Apply a replayed write to the column.
+    pub write: WriteColumnFn<Tbl>,
+    /// Deliver the events recorded by `write` to the column's observers.
+    pub notify: NotifyColumnFn<Tbl>,
+}
+
+impl<Tbl> JournalFns<Tbl> {
+    pub fn new<T>() -> Self
+    where
+        Tbl: Table + BorrowColumn<T> + Sync,
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        JournalFns {
+            attach: attach_column::<Tbl, T>,
+            dump: dump_column::<Tbl, T>,
+            write: write_column::<Tbl, T>,
+            notify: notify_column::<Tbl, T>,
+        }
+    }
+}
+
+impl<Tbl> Clone for JournalFns<Tbl> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tbl> Copy for JournalFns<Tbl> {}
+
+fn attach_column<Tbl, T>(table: &Tbl, name: String, journal: Arc<Journal>)
+where
+    Tbl: BorrowColumn<T>,
+    T: Serialize + 'static,
+{
+    let column: &Column<T> = table.borrow();
+    column.journal().attach(Box::new(move |write| {
+        let column = name.clone();
+        let record = match write {
+            ColumnWrite::Insert(key, value) => match serde_json::to_value(value) {
+                Ok(value) => Record::Insert { column, key, value },
+                Err(e) => return journal.fail(e),
+            },
+            ColumnWrite::Remove(key) => Record::Remove { column, key },
+            ColumnWrite::Replace(cells) => match cells_to_json(cells) {
+                Ok(cells) => Record::Column { column, cells },
+                Err(e) => return journal.fail(e),
+            },
+        };
+        journal.append(&record);
+    }));
+}
+
+fn dump_column<Tbl, T>(table: &Tbl) -> BoxFuture<'_, Result<Vec<(Key, Value)>, serde_json::Error>>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: Serialize + Send + Sync + 'static,
+{
+    Box::pin(async move {
+        let cells = ReadColumn::<T>::new(table).await;
+
+        let mut keys = cells.keys().copied().collect::<Vec<_>>();
+        keys.sort();
+
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(value) = cells
+                .with_cell(&key, |value: &T| serde_json::to_value(value))
+                .await
+            {
+                values.push((key, value?));
+            }
+        }
+
+        Ok(values)
+    })
+}
+
+fn write_column<Tbl, T>(
+    table: &Tbl,
+    write: JournalWrite,
+) -> BoxFuture<'_, Result<(), serde_json::Error>>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    Box::pin(async move {
+        match write {
+            JournalWrite::Insert(key, value) => {
+                let value = serde_json::from_value::<T>(value)?;
+                WriteColumn::<T>::new(table).await.insert(key, value);
+            }
+            JournalWrite::Remove(key) => {
+                WriteColumn::<T>::new(table).await.remove(&key);
+            }
+            JournalWrite::Replace(cells) => {
+                let cells = cells
+                    .into_iter()
+                    .map(|(key, value)| Ok((key, serde_json::from_value::<T>(value)?)))
+                    .collect::<Result<BTreeMap<_, _>, serde_json::Error>>()?;
+
+                let mut column = WriteColumn::<T>::new(table).await;
+                let stale = column
+                    .keys()
+                    .filter(|key| !cells.contains_key(key))
+                    .copied()
+                    .collect::<Vec<_>>();
+
+                for key in stale.iter() {
+                    column.remove(key);
+                }
+
+                for (key, value) in cells {
+                    column.insert(key, value);
+                }
+            }
+        }
+
+        Ok(())
+    })
+}
+
+fn notify_column<Tbl, T>(table: &Tbl) -> BoxFuture<'_, ()>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: Send + Sync + 'static,
+{
+    Box::pin(table.notify_observers::<T>())
+}
+
+/// Attach `journal` to `table`'s key allocator and to every serializable column.
+pub(crate) fn attach<Tbl>(table: &Tbl, journal: Arc<Journal>)
+where
+    Tbl: Table,
+{
+    if let Some(keys) = table.key_allocator() {
+        keys.attach_journal(journal.clone());
+    }
+
+    for column in Tbl::column_registry().iter() {
+        if let Some(fns) = column.journal {
+            (fns.attach)(table, column.name().into_owned(), journal.clone());
+        }
+    }
+}
+
+/// Apply every record in `journal` to `table`, returning the number of records applied.
+pub(crate) async fn replay<Tbl>(table: &Tbl, journal: &Journal) -> Result<usize, JournalError>
+where
+    Tbl: Table + Sync,
+{
+    let records = journal.records()?;
+    let registry = Tbl::column_registry();
+
+    let column = |name: &str| {
+        registry
+            .get_by_field(name)
+            .and_then(|column| column.journal)
+            .ok_or_else(|| JournalError::UnknownColumn(name.to_string()))
+    };
+
+    let count = records.len();
+    for record in records {
+        match record {
+            Record::Alloc(key) => {
+                if let Some(keys) = table.key_allocator() {
+                    keys.claim(&key);
+                }
+            }
+            Record::Free(key) => {
+                table.free_key(&key);
+            }
+            Record::Keys(slots) => {
+                if let Some(keys) = table.key_allocator() {
+                    keys.restore(slots);
+                }
+            }
+            Record::Insert {
+                column: name,
+                key,
+                value,
+            } => (column(&name)?.write)(table, JournalWrite::Insert(key, value)).await?,
+            Record::Remove { column: name, key } => {
+                (column(&name)?.write)(table, JournalWrite::Remove(key)).await?
+            }
+            Record::Column {
+                column: name,
+                cells,
+            } => (column(&name)?.write)(table, JournalWrite::Replace(cells)).await?,
+            Record::Snapshot { keys, mut columns } => {
+                if let (Some(allocator), Some(slots)) = (table.key_allocator(), keys) {
+                    allocator.restore(slots);
+                }
+
+                for info in registry.iter() {
+                    if let (Some(fns), Some(cells)) = (info.journal, columns.remove(&*info.name()))
+                    {
+                        (fns.write)(table, JournalWrite::Replace(cells)).await?;
+                    }
+                }
+
+                if let Some(name) = columns.keys().next() {
+                    return Err(JournalError::UnknownColumn(name.clone()));
+                }
+            }
+        }
+    }
+
+    table.rebuild_views().await;
+
+    for info in registry.iter() {
+        if let Some(fns) = info.journal {
+            (fns.notify)(table).await;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Replace the history in `journal` with a snapshot of `table`.
+pub(crate) async fn compact<Tbl>(table: &Tbl, journal: &Journal) -> Result<(), JournalError>
+where
+    Tbl: Table + Sync,
+{
+    let _compaction = journal.compaction.lock().await;
+
+    journal.begin_compaction()?;
+
+    let keys = table.key_allocator().map(|keys| keys.snapshot());
+
+    let mut columns = BTreeMap::new();
+    for column in Tbl::column_registry().iter() {
+        if let Some(fns) = column.journal {
+            match (fns.dump)(table).await {
+                Ok(cells) => {
+                    columns.insert(column.name().into_owned(), cells);
+                }
+                Err(e) => {
+                    journal.abort_compaction()?;
+                    return Err(e.into());
+                }
+            }
+        }
+    }
+
+    journal.finish_compaction(&Record::Snapshot { keys, columns })?;
+    Ok(())
+}
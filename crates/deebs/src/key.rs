@@ -1,24 +1,45 @@
-use std::{fmt::Display, ops::{Deref, DerefMut}};
+use std::{fmt::Display, ops::Deref, sync::Mutex};
 
 /// A [`Table`] key.
+///
+/// Combines a slot index with the generation of that slot,
+/// so a key outliving its entity can be told apart from the slot's next occupant.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Key(usize);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Key {
+    index: usize,
+    generation: u32,
+}
+
+impl Key {
+    pub fn new(index: usize, generation: u32) -> Self {
+        Key { index, generation }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
 
 impl From<usize> for Key {
-    fn from(key: usize) -> Self {
-        Key(key)
+    fn from(index: usize) -> Self {
+        Key::new(index, 0)
     }
 }
 
 impl From<Key> for usize {
     fn from(key: Key) -> Self {
-        key.0
+        key.index
     }
 }
 
 impl Display for Key {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
+        write!(f, "{}v{}", self.index, self.generation)
     }
 }
 
@@ -26,12 +47,169 @@ impl Deref for Key {
     type Target = usize;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.index
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct KeySlot {
+    generation: u32,
+    alive: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct KeySlots {
+    slots: Vec<KeySlot>,
+    free: Vec<usize>,
+}
+
+/// Free-list allocator handing out generational [`Key`]s.
+///
+/// Freeing a key bumps the generation of its slot before the slot is reused.
+#[derive(Debug, Default)]
+pub struct KeyAllocator {
+    slots: Mutex<KeySlots>,
+    #[cfg(feature = "journal")]
+    journal: Mutex<Option<std::sync::Arc<crate::journal::Journal>>>,
+}
+
+impl KeyAllocator {
+    fn slots(&self) -> std::sync::MutexGuard<'_, KeySlots> {
+        self.slots.lock().expect("Key allocator is poisoned.")
+    }
+
+    #[cfg(feature = "journal")]
+    fn journal(&self, record: impl FnOnce() -> crate::journal::Record) {
+        if let Some(journal) = &*self.journal.lock().expect("Key allocator is poisoned.") {
+            journal.append(&record());
+        }
+    }
+
+    #[cfg(feature = "journal")]
+    pub(crate) fn attach_journal(&self, journal: std::sync::Arc<crate::journal::Journal>) {
+        *self.journal.lock().expect("Key allocator is poisoned.") = Some(journal);
+    }
+
+    /// Allocate a fresh key, reusing a freed slot if one is available.
+    pub fn alloc(&self) -> Key {
+        let mut slots = self.slots();
+
+        let key = if let Some(index) = slots.free.pop() {
+            let slot = &mut slots.slots[index];
+            slot.alive = true;
+            Key::new(index, slot.generation)
+        } else {
+            let index = slots.slots.len();
+            slots.slots.push(KeySlot {
+                generation: 0,
+                alive: true,
+            });
+            Key::new(index, 0)
+        };
+
+        #[cfg(feature = "journal")]
+        self.journal(|| crate::journal::Record::Alloc(key));
+
+        key
+    }
+
+    /// Free `key` so its slot can be reused, returning false if it was not alive.
+    pub fn free(&self, key: &Key) -> bool {
+        let mut slots = self.slots();
+
+        match slots.slots.get_mut(key.index) {
+            Some(slot) if slot.alive && slot.generation == key.generation => {
+                slot.alive = false;
+                slot.generation = slot.generation.wrapping_add(1);
+                slots.free.push(key.index);
+
+                #[cfg(feature = "journal")]
+                self.journal(|| crate::journal::Record::Free(*key));
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Return true if `key` was allocated by this allocator and has not been freed.
+    pub fn is_alive(&self, key: &Key) -> bool {
+        matches!(
+            self.slots().slots.get(key.index),
+            Some(slot) if slot.alive && slot.generation == key.generation
+        )
+    }
+
+    /// Return true if `key`'s slot has been freed since `key` was allocated.
+    ///
+    /// Keys that were never handed out by this allocator are not considered stale.
+    pub fn is_stale(&self, key: &Key) -> bool {
+        match self.slots().slots.get(key.index) {
+            Some(slot) => slot.generation != key.generation || !slot.alive,
+            None => false,
+        }
+    }
+
+    /// Copy the state of every slot.
+    pub(crate) fn snapshot(&self) -> KeySlots {
+        self.slots().clone()
+    }
+
+    /// Replace the state of every slot with one taken by [`KeyAllocator::snapshot`].
+    pub(crate) fn restore(&self, slots: KeySlots) {
+        let mut current = self.slots();
+
+        #[cfg(feature = "journal")]
+        self.journal(|| crate::journal::Record::Keys(slots.clone()));
+
+        *current = slots;
+    }
+
+    /// Mark `key` as allocated, as when replaying a journal,
+    /// unless its slot has already moved on to a later generation.
+    #[cfg(feature = "journal")]
+    pub(crate) fn claim(&self, key: &Key) {
+        let mut slots = self.slots();
+
+        while slots.slots.len() <= key.index {
+            let index = slots.slots.len();
+            slots.slots.push(KeySlot::default());
+            slots.free.push(index);
+        }
+
+        let slot = &mut slots.slots[key.index];
+        if slot.generation > key.generation || (slot.generation == key.generation && slot.alive) {
+            return;
+        }
+
+        slot.generation = key.generation;
+        slot.alive = true;
+        slots.free.retain(|index| *index != key.index);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for KeyAllocator {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&*self.slots(), serializer)
     }
 }
 
-impl DerefMut for Key {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for KeyAllocator {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <KeySlots as serde::Deserialize>::deserialize(deserializer).map(|slots| {
+            let keys = KeyAllocator::default();
+            *keys.slots() = slots;
+            keys
+        })
     }
 }
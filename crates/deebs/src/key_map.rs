@@ -0,0 +1,139 @@
+use std::{collections::BTreeMap, iter::FromIterator};
+
+use crate::{Key, Table};
+
+/// The keys given in one table to entities transferred from another,
+/// used to rewrite the [`Key`]s held by their cells through [`MapKeys`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KeyMap(BTreeMap<Key, Key>);
+
+impl KeyMap {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Return the key that `key` was given in the destination table, if it has been transferred.
+    pub fn get(&self, key: &Key) -> Option<Key> {
+        self.0.get(key).copied()
+    }
+
+    /// Record that `key` was given `mapped` in the destination table,
+    /// returning the key it was given before.
+    pub fn insert(&mut self, key: Key, mapped: Key) -> Option<Key> {
+        self.0.insert(key, mapped)
+    }
+
+    /// Return the key that `key` was given in `dst`,
+    /// allocating one from `dst` if it hasn't been transferred yet.
+    pub fn get_or_insert<Tbl>(&mut self, key: Key, dst: &Tbl) -> Key
+    where
+        Tbl: Table,
+    {
+        *self.0.entry(key).or_insert_with(|| dst.next_key())
+    }
+
+    /// Iterate over each transferred key and the key it was given, in source key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Key, &Key)> {
+        self.0.iter()
+    }
+
+    /// Iterate over the keys given in the destination table.
+    pub fn values(&self) -> impl Iterator<Item = &Key> {
+        self.0.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Return true if mapping `held` through this map would change any of them.
+    pub fn remaps(&self, held: &[Key]) -> bool {
+        held.iter().any(|key| self.get(key) != Some(*key))
+    }
+
+    /// Return the map from the destination table's keys back to the source table's,
+    /// for transferring entities back where they came from.
+    pub fn inverse(&self) -> KeyMap {
+        self.0.iter().map(|(key, mapped)| (*mapped, *key)).collect()
+    }
+}
+
+impl FromIterator<(Key, Key)> for KeyMap {
+    fn from_iter<I: IntoIterator<Item = (Key, Key)>>(iter: I) -> Self {
+        KeyMap(iter.into_iter().collect())
+    }
+}
+
+impl Extend<(Key, Key)> for KeyMap {
+    fn extend<I: IntoIterator<Item = (Key, Key)>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+/// A cell type that refers to other entities by [`Key`].
+///
+/// Rows moved or copied between tables with `transfer` and `transfer_clone`
+/// rewrite these references through a [`KeyMap`] once every entity has been transferred.
+pub trait MapKeys {
+    /// Return the keys held by this value.
+    fn held_keys(&self) -> Vec<Key>;
+
+    /// Replace each held key with the key it was given in `keys`,
+    /// dropping references to keys that weren't transferred.
+    ///
+    /// Returns false if the value is meaningless without the references it dropped,
+    /// in which case its cell is removed.
+    fn map_keys(&mut self, keys: &KeyMap) -> bool;
+}
+
+impl MapKeys for Key {
+    fn held_keys(&self) -> Vec<Key> {
+        vec![*self]
+    }
+
+    fn map_keys(&mut self, keys: &KeyMap) -> bool {
+        match keys.get(self) {
+            Some(key) => {
+                *self = key;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<T> MapKeys for Option<T>
+where
+    T: MapKeys,
+{
+    fn held_keys(&self) -> Vec<Key> {
+        self.as_ref().map(T::held_keys).unwrap_or_default()
+    }
+
+    fn map_keys(&mut self, keys: &KeyMap) -> bool {
+        if let Some(inner) = self {
+            if !inner.map_keys(keys) {
+                *self = None;
+            }
+        }
+        true
+    }
+}
+
+impl<T> MapKeys for Vec<T>
+where
+    T: MapKeys,
+{
+    fn held_keys(&self) -> Vec<Key> {
+        self.iter().flat_map(T::held_keys).collect()
+    }
+
+    fn map_keys(&mut self, keys: &KeyMap) -> bool {
+        self.retain_mut(|inner| inner.map_keys(keys));
+        true
+    }
+}
@@ -1,32 +1,101 @@
 //! Struct-based async table-row database.
 
+mod blocking;
+mod changes;
 mod column;
+mod commands;
+mod dyn_table;
+mod error;
+mod events;
 mod guards;
+mod hierarchy;
+mod index;
+#[cfg(feature = "journal")]
+pub mod journal;
 mod key;
+mod key_map;
+mod observer;
+#[cfg(feature = "prefab")]
+pub mod prefab;
+pub mod reflect;
 mod row;
+pub mod runtime;
+#[cfg(feature = "serde")]
+mod serialize;
 mod singleton;
+mod snapshot;
+mod storage;
+mod sub_table;
 mod table;
+mod tick;
+mod transaction;
 mod view;
 
+pub use blocking::*;
+pub use changes::*;
 pub use column::*;
+pub use commands::*;
+pub use dyn_table::*;
+pub use error::*;
+pub use events::*;
 pub use guards::*;
+pub use hierarchy::*;
+pub use index::*;
 pub use key::*;
+pub use key_map::*;
+pub use observer::*;
 pub use row::*;
 pub use singleton::*;
+pub use snapshot::*;
+pub use storage::*;
+pub use sub_table::*;
 pub use table::*;
+pub use tick::*;
+pub use transaction::*;
 pub use view::*;
 
 pub use deebs_macros as macros;
 
-use async_std::sync::RwLock;
-use std::collections::HashMap;
+#[cfg(feature = "serde")]
+pub use serde;
 
-pub type ColumnCollection<T> = HashMap<Key, RwLock<T>, fnv::FnvBuildHasher>;
+/// Expand to its input if deebs' `serde` feature is enabled.
+///
+/// Derived code is wrapped in this rather than a `cfg` attribute,
+/// which would test the features of the deriving crate instead.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_serde {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_serde {
+    ($($tokens:tt)*) => {};
+}
+
+/// Expand to its input if deebs' `journal` feature is enabled. See [`cfg_serde`].
+#[cfg(feature = "journal")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_journal {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[cfg(not(feature = "journal"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_journal {
+    ($($tokens:tt)*) => {};
+}
 
 pub fn slice_stream<T>(slice: &[T]) -> impl futures::Stream<Item = &T> {
-    async_std::stream::from_iter(slice)
+    runtime::from_iter(slice)
 }
 
 pub fn array_stream<T, const N: usize>(array: [T; N]) -> impl futures::Stream<Item = T> {
-    async_std::stream::from_iter(std::array::IntoIter::new(array))
+    runtime::from_iter(IntoIterator::into_iter(array))
 }
@@ -0,0 +1,219 @@
+use std::{
+    future::Future,
+    ops::BitOr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use async_channel::{Receiver, Sender, TrySendError};
+use futures::future::BoxFuture;
+
+use crate::Key;
+
+/// A change to a [`Column`] or [`View`] that can be observed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ObserveEvent {
+    /// A key gained a cell in the column, or entered the view.
+    OnInsert,
+    /// A key lost its cell in the column, or left the view.
+    OnRemove,
+    /// A key's existing cell was written.
+    OnChange,
+}
+
+impl ObserveEvent {
+    fn bit(self) -> u8 {
+        match self {
+            ObserveEvent::OnInsert => 1,
+            ObserveEvent::OnRemove => 1 << 1,
+            ObserveEvent::OnChange => 1 << 2,
+        }
+    }
+}
+
+/// A set of [`ObserveEvent`]s, built with `|`.
+///
+/// ex. `OnInsert | OnRemove`
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ObserveEvents(u8);
+
+impl ObserveEvents {
+    /// Return true if `event` is in this set.
+    pub fn contains(&self, event: ObserveEvent) -> bool {
+        self.0 & event.bit() != 0
+    }
+
+    /// Return true if this set holds no events.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl From<ObserveEvent> for ObserveEvents {
+    fn from(event: ObserveEvent) -> Self {
+        ObserveEvents(event.bit())
+    }
+}
+
+impl<T> BitOr<T> for ObserveEvent
+where
+    T: Into<ObserveEvents>,
+{
+    type Output = ObserveEvents;
+
+    fn bitor(self, rhs: T) -> Self::Output {
+        ObserveEvents::from(self) | rhs
+    }
+}
+
+impl<T> BitOr<T> for ObserveEvents
+where
+    T: Into<ObserveEvents>,
+{
+    type Output = ObserveEvents;
+
+    fn bitor(self, rhs: T) -> Self::Output {
+        ObserveEvents(self.0 | rhs.into().0)
+    }
+}
+
+type Callback = Arc<dyn Fn(ObserveEvent, Key) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// How an observer receives its events.
+enum Delivery {
+    /// Awaited in turn by the writer once its guard has been released.
+    Callback(Callback),
+    /// Sent without waiting; events are dropped while the channel is full.
+    Channel(Sender<(ObserveEvent, Key)>),
+}
+
+struct Observer {
+    events: ObserveEvents,
+    delivery: Delivery,
+}
+
+#[derive(Default)]
+struct ObserversInner {
+    observers: Vec<Observer>,
+    interest: ObserveEvents,
+    pending: Vec<(ObserveEvent, Key)>,
+}
+
+/// The observers registered on a [`Column`] or [`View`],
+/// along with the events recorded for them since they were last notified.
+#[derive(Default)]
+pub struct Observers(Mutex<ObserversInner>);
+
+impl std::fmt::Debug for Observers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner();
+        f.debug_struct("Observers")
+            .field("observers", &inner.observers.len())
+            .field("pending", &inner.pending.len())
+            .finish()
+    }
+}
+
+impl Observers {
+    fn inner(&self) -> MutexGuard<'_, ObserversInner> {
+        self.0.lock().expect("Observers are poisoned.")
+    }
+
+    fn push(&self, events: ObserveEvents, delivery: Delivery) {
+        let mut inner = self.inner();
+        inner.interest = inner.interest | events;
+        inner.observers.push(Observer { events, delivery });
+    }
+
+    /// Register `callback` to be awaited for each of `events`.
+    ///
+    /// Callbacks run on the writing task after its guard has been released,
+    /// so they may lock the table freely but delay the writer until they finish.
+    pub fn observe<E, F, Fut>(&self, events: E, callback: F)
+    where
+        E: Into<ObserveEvents>,
+        F: Fn(ObserveEvent, Key) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let callback: Callback = Arc::new(move |event, key| Box::pin(callback(event, key)));
+        self.push(events.into(), Delivery::Callback(callback));
+    }
+
+    /// Return a channel that receives each of `events`.
+    ///
+    /// The channel holds at most `capacity` events; further events are dropped until it is drained,
+    /// so a slow receiver never blocks a writer. The observer is removed once the receiver is dropped.
+    pub fn observe_channel<E>(&self, events: E, capacity: usize) -> Receiver<(ObserveEvent, Key)>
+    where
+        E: Into<ObserveEvents>,
+    {
+        let (sender, receiver) = async_channel::bounded(capacity);
+        self.push(events.into(), Delivery::Channel(sender));
+        receiver
+    }
+
+    /// Queue `event` for delivery by the next call to [`Observers::notify`],
+    /// if any observer is interested in it.
+    pub(crate) fn record(&self, event: ObserveEvent, key: Key) {
+        let mut inner = self.inner();
+        if inner.interest.contains(event) {
+            inner.pending.push((event, key));
+        }
+    }
+
+    /// Deliver every queued event to the observers interested in it.
+    pub async fn notify(&self) {
+        let (pending, callbacks) = {
+            let mut inner = self.inner();
+            if inner.pending.is_empty() {
+                return;
+            }
+
+            let pending = std::mem::take(&mut inner.pending);
+
+            let mut closed = false;
+            for (event, key) in pending.iter() {
+                for observer in inner.observers.iter() {
+                    if let Delivery::Channel(sender) = &observer.delivery {
+                        if observer.events.contains(*event) {
+                            if let Err(TrySendError::Closed(_)) = sender.try_send((*event, *key)) {
+                                closed = true;
+                            }
+                        }
+                    }
+                }
+            }
+
+            if closed {
+                inner.observers.retain(|observer| match &observer.delivery {
+                    Delivery::Channel(sender) => !sender.is_closed(),
+                    Delivery::Callback(_) => true,
+                });
+                inner.interest = inner
+                    .observers
+                    .iter()
+                    .fold(ObserveEvents::default(), |acc, observer| {
+                        acc | observer.events
+                    });
+            }
+
+            let callbacks = inner
+                .observers
+                .iter()
+                .filter_map(|observer| match &observer.delivery {
+                    Delivery::Callback(callback) => Some((observer.events, callback.clone())),
+                    Delivery::Channel(_) => None,
+                })
+                .collect::<Vec<_>>();
+
+            (pending, callbacks)
+        };
+
+        for (event, key) in pending {
+            for (events, callback) in callbacks.iter() {
+                if events.contains(event) {
+                    callback(event, key).await;
+                }
+            }
+        }
+    }
+}
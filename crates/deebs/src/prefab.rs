@@ -0,0 +1,216 @@
+//! Data-driven entity spawning, enabled by the `prefab` feature.
+//!
+//! A [`Prefab`] describes entities as maps of component names to JSON values,
+//! which a [`ComponentRegistry`] resolves to column types.
+//!
+//! ```json
+//! {
+//!     "quad_a": { "position": [0.0, 0.0], "size": [32.0, 32.0] },
+//!     "quad_b": { "position": [64.0, 0.0], "size": [16.0, 16.0] }
+//! }
+//! ```
+
+use std::{any::Any, collections::BTreeMap, fmt::Display};
+
+use futures::future::BoxFuture;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{BorrowColumn, Key, Table};
+
+/// The components of a single prefab entity, keyed by registered component name.
+pub type PrefabEntity = BTreeMap<String, Value>;
+
+/// A set of named entities to be spawned into a [`Table`].
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Prefab {
+    pub entities: BTreeMap<String, PrefabEntity>,
+}
+
+impl Prefab {
+    /// Parse a prefab from a JSON document.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// The reason a [`Prefab`] could not be spawned.
+#[derive(Debug)]
+pub enum PrefabError {
+    /// An entity names a component that is not in the [`ComponentRegistry`].
+    UnknownComponent { entity: String, component: String },
+    /// A component value could not be deserialized into its registered type.
+    InvalidValue {
+        entity: String,
+        component: String,
+        error: serde_json::Error,
+    },
+}
+
+impl Display for PrefabError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrefabError::UnknownComponent { entity, component } => write!(
+                f,
+                "Entity {} has unregistered component {}.",
+                entity, component
+            ),
+            PrefabError::InvalidValue {
+                entity,
+                component,
+                error,
+            } => write!(
+                f,
+                "Entity {} has an invalid {} component: {}",
+                entity, component, error
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PrefabError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PrefabError::UnknownComponent { .. } => None,
+            PrefabError::InvalidValue { error, .. } => Some(error),
+        }
+    }
+}
+
+type BoxedCell = Box<dyn Any + Send>;
+type InsertCells<Tbl> = for<'a> fn(&'a Tbl, Vec<(Key, BoxedCell)>) -> BoxFuture<'a, ()>;
+
+/// Type-erased operations for one registered component type.
+struct Component<Tbl> {
+    parse: fn(Value) -> Result<BoxedCell, serde_json::Error>,
+    insert: InsertCells<Tbl>,
+}
+
+fn parse_cell<T>(value: Value) -> Result<BoxedCell, serde_json::Error>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    Ok(Box::new(serde_json::from_value::<T>(value)?))
+}
+
+fn insert_cells<Tbl, T>(table: &Tbl, cells: Vec<(Key, BoxedCell)>) -> BoxFuture<'_, ()>
+where
+    Tbl: Table + BorrowColumn<T> + Send + Sync,
+    T: Send + Sync + 'static,
+{
+    let cells = cells
+        .into_iter()
+        .map(|(key, cell)| {
+            (
+                key,
+                *cell.downcast::<T>().expect("Cell has the wrong type."),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    table.insert_multi(cells.into_iter())
+}
+
+/// Maps component names used in [`Prefab`]s to the column types of `Tbl`.
+pub struct ComponentRegistry<Tbl> {
+    components: BTreeMap<String, Component<Tbl>>,
+}
+
+impl<Tbl> Default for ComponentRegistry<Tbl> {
+    fn default() -> Self {
+        ComponentRegistry {
+            components: Default::default(),
+        }
+    }
+}
+
+impl<Tbl> std::fmt::Debug for ComponentRegistry<Tbl> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.components.keys()).finish()
+    }
+}
+
+impl<Tbl> ComponentRegistry<Tbl> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Register `T` as the column type for components called `name`,
+    /// replacing any type previously registered under it.
+    pub fn register<T>(&mut self, name: impl Into<String>) -> &mut Self
+    where
+        Tbl: Table + BorrowColumn<T> + Send + Sync,
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        self.components.insert(
+            name.into(),
+            Component {
+                parse: parse_cell::<T>,
+                insert: insert_cells::<Tbl, T>,
+            },
+        );
+        self
+    }
+
+    /// Return true if a component type is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.components.contains_key(name)
+    }
+}
+
+/// Insert every component of every entity in `prefab` under freshly allocated keys,
+/// returning the key allocated for each entity name.
+///
+/// All values are deserialized before any key is allocated,
+/// so a prefab that fails to spawn leaves `table` untouched.
+/// Each component type is inserted with a single [`Table::insert_multi`].
+pub async fn spawn<Tbl>(
+    table: &Tbl,
+    registry: &ComponentRegistry<Tbl>,
+    prefab: &Prefab,
+) -> Result<BTreeMap<String, Key>, PrefabError>
+where
+    Tbl: Table + Send + Sync,
+{
+    let mut cells: BTreeMap<&str, Vec<(usize, BoxedCell)>> = BTreeMap::new();
+
+    for (index, (entity, components)) in prefab.entities.iter().enumerate() {
+        for (component, value) in components {
+            let (name, registered) =
+                registry
+                    .components
+                    .get_key_value(component)
+                    .ok_or_else(|| PrefabError::UnknownComponent {
+                        entity: entity.clone(),
+                        component: component.clone(),
+                    })?;
+
+            let cell =
+                (registered.parse)(value.clone()).map_err(|error| PrefabError::InvalidValue {
+                    entity: entity.clone(),
+                    component: component.clone(),
+                    error,
+                })?;
+
+            cells.entry(name.as_str()).or_default().push((index, cell));
+        }
+    }
+
+    let keys = prefab
+        .entities
+        .keys()
+        .map(|_| table.next_key())
+        .collect::<Vec<_>>();
+
+    for (name, cells) in cells {
+        let cells = cells
+            .into_iter()
+            .map(|(index, cell)| (keys[index], cell))
+            .collect();
+
+        (registry.components[name].insert)(table, cells).await;
+    }
+
+    Ok(prefab.entities.keys().cloned().zip(keys).collect())
+}
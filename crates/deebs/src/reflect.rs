@@ -0,0 +1,491 @@
+//! Runtime reflection over the columns of a [`Table`].
+//!
+//! The `Table` derive builds a [`ColumnRegistry`] describing each of its columns,
+//! so tools can enumerate columns and render or edit cells without a bespoke [`Row`] struct.
+//!
+//! Every cell access here uses `try_*` locks, failing with [`CellError::Locked`]
+//! instead of waiting, so registries can be driven from synchronous UI code.
+
+use std::{
+    any::{Any, TypeId},
+    borrow::Cow,
+    collections::HashMap,
+    fmt::{Debug, Display},
+    marker::PhantomData,
+};
+
+use crate::{
+    BorrowColumn, CellError, Column, ColumnWrite, Key, KeyMap, MapKeys, ObserveEvent, SnapshotFns,
+    Table,
+};
+
+/// Run `f` on the `T` cell at `key` without waiting for any lock.
+pub fn try_with_cell<Tbl, T, R>(
+    table: &Tbl,
+    key: &Key,
+    f: impl FnOnce(&T) -> R,
+) -> Result<R, CellError>
+where
+    Tbl: Table + BorrowColumn<T>,
+{
+    if table.is_stale(key) {
+        return Err(CellError::Stale(*key));
+    }
+
+    let column: &Column<T> = table.borrow();
+    let cells = column.try_read().ok_or(CellError::Locked(*key))?;
+    cells.try_with_cell(key, f)
+}
+
+/// Run `f` on the `T` cell at `key` without waiting for any lock,
+/// recording it as changed in the column's change record.
+pub fn try_with_cell_mut<Tbl, T, R>(
+    table: &Tbl,
+    key: &Key,
+    f: impl FnOnce(&mut T) -> R,
+) -> Result<R, CellError>
+where
+    Tbl: Table + BorrowColumn<T>,
+{
+    try_edit_cell(table, key, |cell| (f(cell), true))
+}
+
+/// Run `f` on the `T` cell at `key` without waiting for any lock.
+///
+/// `f` returns its result alongside whether it changed the cell,
+/// so that editors which leave a cell untouched don't mark it as changed.
+pub fn try_edit_cell<Tbl, T, R>(
+    table: &Tbl,
+    key: &Key,
+    f: impl FnOnce(&mut T) -> (R, bool),
+) -> Result<R, CellError>
+where
+    Tbl: Table + BorrowColumn<T>,
+{
+    if table.is_stale(key) {
+        return Err(CellError::Stale(*key));
+    }
+
+    let column: &Column<T> = table.borrow();
+    let mut cells = column.try_write().ok_or(CellError::Locked(*key))?;
+    let cell = cells.get_mut(key).ok_or(CellError::Missing(*key))?;
+    let (result, changed) = f(cell);
+
+    if changed {
+        column.changes().record_written(*key);
+        column.observers().record(ObserveEvent::OnChange, *key);
+
+        if let Some(cell) = cells.get_mut(key) {
+            column.record_write(ColumnWrite::Insert(*key, cell));
+        }
+    }
+
+    Ok(result)
+}
+
+/// A type-erased read of one cell.
+pub type CellFn<Tbl, R> = fn(&Tbl, &Key) -> Result<R, CellError>;
+
+/// A type-erased serialization of one cell.
+#[cfg(feature = "serde")]
+pub type SerializeFn<Tbl> = CellFn<Tbl, Result<serde_json::Value, serde_json::Error>>;
+
+/// A type-erased overwrite of one cell from a serialized value.
+#[cfg(feature = "serde")]
+pub type DeserializeFn<Tbl> =
+    fn(&Tbl, &Key, serde_json::Value) -> Result<Result<(), serde_json::Error>, CellError>;
+
+/// Reflection data for one [`Column`] of a [`Table`].
+///
+/// Vtables are `None` when the column's type doesn't implement the matching trait.
+pub struct ColumnInfo<Tbl> {
+    /// The name of the table field holding this column.
+    pub field: &'static str,
+    /// The `#[flatten]` field of the table holding this column's bundle, if it was flattened.
+    pub flatten: Option<&'static str>,
+    /// The [`std::any::type_name`] of the column's cell type.
+    pub type_name: &'static str,
+    /// The [`TypeId`] of the column's cell type.
+    pub type_id: TypeId,
+    /// Return the keys that have a cell in this column, or `None` if it is write-locked.
+    pub keys: fn(&Tbl) -> Option<Vec<Key>>,
+    /// Format a cell with [`Debug`].
+    pub debug: Option<CellFn<Tbl, String>>,
+    /// Format a cell with [`Display`].
+    pub display: Option<CellFn<Tbl, String>>,
+    /// Capture and restore the column, if its type is [`Clone`].
+    pub snapshot: Option<SnapshotFns<Tbl>>,
+    /// Serialize a cell into a JSON value.
+    #[cfg(feature = "serde")]
+    pub serialize: Option<SerializeFn<Tbl>>,
+    /// Overwrite a cell with a deserialized JSON value.
+    #[cfg(feature = "serde")]
+    pub deserialize: Option<DeserializeFn<Tbl>>,
+    /// Journal and replay the column, if its type is serializable.
+    #[cfg(feature = "journal")]
+    pub journal: Option<crate::journal::JournalFns<Tbl>>,
+    extensions: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl<Tbl> ColumnInfo<Tbl> {
+    /// Describe the `T` column held in `field`, with every optional vtable unset.
+    pub fn new<T>(field: &'static str) -> Self
+    where
+        Tbl: Table + BorrowColumn<T>,
+        T: 'static,
+    {
+        ColumnInfo {
+            field,
+            flatten: None,
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            keys: column_keys::<Tbl, T>,
+            debug: None,
+            display: None,
+            snapshot: None,
+            #[cfg(feature = "serde")]
+            serialize: None,
+            #[cfg(feature = "serde")]
+            deserialize: None,
+            #[cfg(feature = "journal")]
+            journal: None,
+            extensions: Default::default(),
+        }
+    }
+
+    /// Return the name of this column, which is its field qualified as `flatten.field`
+    /// if it was flattened, so that bundles sharing field names don't collide.
+    pub fn name(&self) -> Cow<'static, str> {
+        match self.flatten {
+            Some(flatten) => Cow::Owned(format!("{}.{}", flatten, self.field)),
+            None => Cow::Borrowed(self.field),
+        }
+    }
+
+    /// Attach an extension vtable, such as a UI widget,
+    /// replacing any previous extension of the same type.
+    pub fn insert_extension<E>(&mut self, extension: E)
+    where
+        E: Any + Send + Sync,
+    {
+        self.extensions
+            .insert(TypeId::of::<E>(), Box::new(extension));
+    }
+
+    /// Return the extension vtable of type `E`, if one is attached.
+    pub fn extension<E>(&self) -> Option<&E>
+    where
+        E: Any + Send + Sync,
+    {
+        self.extensions
+            .get(&TypeId::of::<E>())
+            .and_then(|extension| extension.downcast_ref())
+    }
+}
+
+impl<Tbl> Debug for ColumnInfo<Tbl> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColumnInfo")
+            .field("field", &self.field)
+            .field("flatten", &self.flatten)
+            .field("type_name", &self.type_name)
+            .field("debug", &self.debug.is_some())
+            .field("display", &self.display.is_some())
+            .finish()
+    }
+}
+
+/// Reflection data for every [`Column`] of a [`Table`], in field order.
+pub struct ColumnRegistry<Tbl> {
+    columns: Vec<ColumnInfo<Tbl>>,
+}
+
+impl<Tbl> Debug for ColumnRegistry<Tbl> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.columns.iter()).finish()
+    }
+}
+
+impl<Tbl> ColumnRegistry<Tbl> {
+    pub fn new(columns: Vec<ColumnInfo<Tbl>>) -> Self {
+        ColumnRegistry { columns }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ColumnInfo<Tbl>> {
+        self.columns.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut ColumnInfo<Tbl>> {
+        self.columns.iter_mut()
+    }
+
+    /// Return the column holding cells of `type_id`.
+    pub fn get(&self, type_id: TypeId) -> Option<&ColumnInfo<Tbl>> {
+        self.columns.iter().find(|column| column.type_id == type_id)
+    }
+
+    /// Return the column called `name`, as returned by [`ColumnInfo::name`].
+    pub fn get_by_field(&self, name: &str) -> Option<&ColumnInfo<Tbl>> {
+        self.columns.iter().find(|column| match column.flatten {
+            Some(flatten) => name.split_once('.') == Some((flatten, column.field)),
+            None => column.field == name,
+        })
+    }
+
+    /// Return the columns that have a cell for `key`, skipping any that are locked.
+    pub fn columns_of<'a>(
+        &'a self,
+        table: &'a Tbl,
+        key: &'a Key,
+    ) -> impl Iterator<Item = &'a ColumnInfo<Tbl>> + 'a {
+        self.columns.iter().filter(move |column| {
+            (column.keys)(table)
+                .map(|keys| keys.contains(key))
+                .unwrap_or_default()
+        })
+    }
+}
+
+fn column_keys<Tbl, T>(table: &Tbl) -> Option<Vec<Key>>
+where
+    Tbl: Table + BorrowColumn<T>,
+{
+    let column: &Column<T> = table.borrow();
+    let mut keys = column.try_read()?.keys().copied().collect::<Vec<_>>();
+    keys.sort();
+    Some(keys)
+}
+
+fn debug_cell<Tbl, T>(table: &Tbl, key: &Key) -> Result<String, CellError>
+where
+    Tbl: Table + BorrowColumn<T>,
+    T: Debug,
+{
+    try_with_cell(table, key, |cell: &T| format!("{:?}", cell))
+}
+
+fn display_cell<Tbl, T>(table: &Tbl, key: &Key) -> Result<String, CellError>
+where
+    Tbl: Table + BorrowColumn<T>,
+    T: Display,
+{
+    try_with_cell(table, key, |cell: &T| cell.to_string())
+}
+
+#[cfg(feature = "serde")]
+fn serialize_cell<Tbl, T>(
+    table: &Tbl,
+    key: &Key,
+) -> Result<Result<serde_json::Value, serde_json::Error>, CellError>
+where
+    Tbl: Table + BorrowColumn<T>,
+    T: serde::Serialize,
+{
+    try_with_cell(table, key, |cell: &T| serde_json::to_value(cell))
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_cell<Tbl, T>(
+    table: &Tbl,
+    key: &Key,
+    value: serde_json::Value,
+) -> Result<Result<(), serde_json::Error>, CellError>
+where
+    Tbl: Table + BorrowColumn<T>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = match serde_json::from_value::<T>(value) {
+        Ok(value) => value,
+        Err(e) => return Ok(Err(e)),
+    };
+
+    try_with_cell_mut(table, key, |cell: &mut T| *cell = value).map(Ok)
+}
+
+/// Selects optional vtables for a column of `T` in `Tbl`.
+///
+/// Each vtable is looked up through a pair of traits: one implemented for `Probe` when `T` has
+/// the required trait, and a fallback implemented for `&Probe` that returns `None`.
+/// Calling the method on `&Probe` prefers the former, so this only works for concrete types,
+/// as in code generated by the `Table` derive.
+pub struct Probe<Tbl, T>(PhantomData<fn() -> (Tbl, T)>);
+
+impl<Tbl, T> Default for Probe<Tbl, T> {
+    fn default() -> Self {
+        Probe(PhantomData)
+    }
+}
+
+impl<Tbl, T> Probe<Tbl, T> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+pub trait ProbeDebug<Tbl> {
+    fn debug_fn(&self) -> Option<CellFn<Tbl, String>>;
+}
+
+impl<Tbl, T> ProbeDebug<Tbl> for Probe<Tbl, T>
+where
+    Tbl: Table + BorrowColumn<T>,
+    T: Debug,
+{
+    fn debug_fn(&self) -> Option<CellFn<Tbl, String>> {
+        Some(debug_cell::<Tbl, T>)
+    }
+}
+
+pub trait ProbeNoDebug<Tbl> {
+    fn debug_fn(&self) -> Option<CellFn<Tbl, String>> {
+        None
+    }
+}
+
+impl<Tbl, T> ProbeNoDebug<Tbl> for &Probe<Tbl, T> {}
+
+pub trait ProbeDisplay<Tbl> {
+    fn display_fn(&self) -> Option<CellFn<Tbl, String>>;
+}
+
+impl<Tbl, T> ProbeDisplay<Tbl> for Probe<Tbl, T>
+where
+    Tbl: Table + BorrowColumn<T>,
+    T: Display,
+{
+    fn display_fn(&self) -> Option<CellFn<Tbl, String>> {
+        Some(display_cell::<Tbl, T>)
+    }
+}
+
+pub trait ProbeNoDisplay<Tbl> {
+    fn display_fn(&self) -> Option<CellFn<Tbl, String>> {
+        None
+    }
+}
+
+impl<Tbl, T> ProbeNoDisplay<Tbl> for &Probe<Tbl, T> {}
+
+pub trait ProbeClone<Tbl> {
+    fn snapshot_fns(&self) -> Option<SnapshotFns<Tbl>>;
+}
+
+impl<Tbl, T> ProbeClone<Tbl> for Probe<Tbl, T>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: Clone + Send + Sync + 'static,
+{
+    fn snapshot_fns(&self) -> Option<SnapshotFns<Tbl>> {
+        Some(SnapshotFns::new::<T>())
+    }
+}
+
+pub trait ProbeNoClone<Tbl> {
+    fn snapshot_fns(&self) -> Option<SnapshotFns<Tbl>> {
+        None
+    }
+}
+
+impl<Tbl, T> ProbeNoClone<Tbl> for &Probe<Tbl, T> {}
+
+/// The [`MapKeys`] methods of a cell type.
+pub type MapKeysFns<T> = (fn(&T) -> Vec<Key>, fn(&mut T, &KeyMap) -> bool);
+
+pub trait ProbeMapKeys<T> {
+    fn map_keys_fns(&self) -> Option<MapKeysFns<T>>;
+}
+
+impl<Tbl, T> ProbeMapKeys<T> for Probe<Tbl, T>
+where
+    T: MapKeys,
+{
+    fn map_keys_fns(&self) -> Option<MapKeysFns<T>> {
+        Some((T::held_keys, T::map_keys))
+    }
+}
+
+pub trait ProbeNoMapKeys<T> {
+    fn map_keys_fns(&self) -> Option<MapKeysFns<T>> {
+        None
+    }
+}
+
+impl<Tbl, T> ProbeNoMapKeys<T> for &Probe<Tbl, T> {}
+
+#[cfg(feature = "serde")]
+pub trait ProbeSerialize<Tbl> {
+    fn serialize_fn(&self) -> Option<SerializeFn<Tbl>>;
+}
+
+#[cfg(feature = "serde")]
+impl<Tbl, T> ProbeSerialize<Tbl> for Probe<Tbl, T>
+where
+    Tbl: Table + BorrowColumn<T>,
+    T: serde::Serialize,
+{
+    fn serialize_fn(&self) -> Option<SerializeFn<Tbl>> {
+        Some(serialize_cell::<Tbl, T>)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub trait ProbeNoSerialize<Tbl> {
+    fn serialize_fn(&self) -> Option<SerializeFn<Tbl>> {
+        None
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Tbl, T> ProbeNoSerialize<Tbl> for &Probe<Tbl, T> {}
+
+#[cfg(feature = "serde")]
+pub trait ProbeDeserialize<Tbl> {
+    fn deserialize_fn(&self) -> Option<DeserializeFn<Tbl>>;
+}
+
+#[cfg(feature = "serde")]
+impl<Tbl, T> ProbeDeserialize<Tbl> for Probe<Tbl, T>
+where
+    Tbl: Table + BorrowColumn<T>,
+    T: serde::de::DeserializeOwned,
+{
+    fn deserialize_fn(&self) -> Option<DeserializeFn<Tbl>> {
+        Some(deserialize_cell::<Tbl, T>)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub trait ProbeNoDeserialize<Tbl> {
+    fn deserialize_fn(&self) -> Option<DeserializeFn<Tbl>> {
+        None
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Tbl, T> ProbeNoDeserialize<Tbl> for &Probe<Tbl, T> {}
+
+#[cfg(feature = "journal")]
+pub trait ProbeJournal<Tbl> {
+    fn journal_fns(&self) -> Option<crate::journal::JournalFns<Tbl>>;
+}
+
+#[cfg(feature = "journal")]
+impl<Tbl, T> ProbeJournal<Tbl> for Probe<Tbl, T>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: serde::Serialize + serde::de::DeserializeOwned + Send + Sync + 'static,
+{
+    fn journal_fns(&self) -> Option<crate::journal::JournalFns<Tbl>> {
+        Some(crate::journal::JournalFns::new::<T>())
+    }
+}
+
+#[cfg(feature = "journal")]
+pub trait ProbeNoJournal<Tbl> {
+    fn journal_fns(&self) -> Option<crate::journal::JournalFns<Tbl>> {
+        None
+    }
+}
+
+#[cfg(feature = "journal")]
+impl<Tbl, T> ProbeNoJournal<Tbl> for &Probe<Tbl, T> {}
@@ -1,10 +1,28 @@
-use crate::{Key};
+use std::collections::BTreeSet;
+
+use crate::{Key, Tick};
 use async_trait::async_trait;
 
 #[async_trait]
 pub trait CommonKeys<Tbl> {
-    /// Return the set of all keys common to the types in this [`Row`]
+    /// Return the set of all keys common to the types in this [`Row`],
+    /// treating any [`Added`] or [`Changed`] filter fields as relative to the default [`Tick`]
     async fn common_keys(
         db: &Tbl,
-    ) -> async_std::stream::FromIter<std::collections::btree_set::IntoIter<Key>>;
-}
\ No newline at end of file
+    ) -> crate::runtime::FromIter<std::collections::btree_set::IntoIter<Key>>;
+
+    /// Return the set of all keys common to the types in this [`Row`],
+    /// evaluating any [`Added`] or [`Changed`] filter fields against changes made after `tick`
+    async fn common_keys_since(
+        db: &Tbl,
+        tick: Tick,
+    ) -> crate::runtime::FromIter<std::collections::btree_set::IntoIter<Key>>;
+
+    /// Return the subset of `keys` common to the types in this [`Row`],
+    /// treating any [`Added`] or [`Changed`] filter fields as relative to the default [`Tick`]
+    async fn common_keys_in(db: &Tbl, keys: &[Key]) -> BTreeSet<Key>;
+
+    /// Return the subset of `keys` common to the types in this [`Row`],
+    /// evaluating any [`Added`] or [`Changed`] filter fields against changes made after `tick`
+    async fn common_keys_in_since(db: &Tbl, keys: &[Key], tick: Tick) -> BTreeSet<Key>;
+}
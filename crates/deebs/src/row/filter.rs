@@ -0,0 +1,190 @@
+use std::{any::TypeId, collections::BTreeSet, marker::PhantomData};
+
+use async_trait::async_trait;
+
+use crate::{BorrowColumn, Column, Key, ReadColumn, Tick};
+
+/// The evaluated form of a [`Filter`], used to test individual keys.
+#[derive(Debug, Clone)]
+pub enum FilterKeys {
+    /// Keys contained in this set pass the filter.
+    Include(BTreeSet<Key>),
+    /// Keys contained in this set fail the filter.
+    Exclude(BTreeSet<Key>),
+    /// Keys passing any of these filters pass the filter.
+    Any(Vec<FilterKeys>),
+}
+
+impl FilterKeys {
+    /// Return true if `key` passes this filter.
+    pub fn contains(&self, key: &Key) -> bool {
+        match self {
+            FilterKeys::Include(keys) => keys.contains(key),
+            FilterKeys::Exclude(keys) => !keys.contains(key),
+            FilterKeys::Any(filters) => filters.iter().any(|filter| filter.contains(key)),
+        }
+    }
+}
+
+/// A zero-sized [`Row`] field type that restricts which keys a query yields
+/// without borrowing any cell data.
+#[async_trait]
+pub trait Filter<Tbl> {
+    /// Return the [`TypeId`]s of the column types this filter depends on.
+    fn inner_types() -> Vec<TypeId>;
+
+    /// Evaluate this filter against `db`,
+    /// treating [`Added`] and [`Changed`] as relative to `tick`.
+    async fn filter_keys(db: &Tbl, tick: Tick) -> FilterKeys;
+}
+
+/// [`Row`] field that restricts a query to keys whose `T` cell was inserted since a given [`Tick`].
+#[derive(Debug)]
+pub struct Added<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for Added<T> {
+    fn default() -> Self {
+        Added(PhantomData)
+    }
+}
+
+#[async_trait]
+impl<T, Tbl> Filter<Tbl> for Added<T>
+where
+    T: 'static,
+    Tbl: BorrowColumn<T> + Sync,
+{
+    fn inner_types() -> Vec<TypeId> {
+        vec![TypeId::of::<T>()]
+    }
+
+    async fn filter_keys(db: &Tbl, tick: Tick) -> FilterKeys {
+        let column: &Column<T> = db.borrow();
+        let keys = column.changes().added_since(tick);
+        FilterKeys::Include(keys)
+    }
+}
+
+/// [`Row`] field that restricts a query to keys whose `T` cell was written since a given [`Tick`].
+#[derive(Debug)]
+pub struct Changed<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for Changed<T> {
+    fn default() -> Self {
+        Changed(PhantomData)
+    }
+}
+
+#[async_trait]
+impl<T, Tbl> Filter<Tbl> for Changed<T>
+where
+    T: 'static,
+    Tbl: BorrowColumn<T> + Sync,
+{
+    fn inner_types() -> Vec<TypeId> {
+        vec![TypeId::of::<T>()]
+    }
+
+    async fn filter_keys(db: &Tbl, tick: Tick) -> FilterKeys {
+        let column: &Column<T> = db.borrow();
+        let keys = column.changes().changed_since(tick);
+        FilterKeys::Include(keys)
+    }
+}
+
+/// [`Row`] field that restricts a query to keys with a `T` cell.
+#[derive(Debug)]
+pub struct With<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for With<T> {
+    fn default() -> Self {
+        With(PhantomData)
+    }
+}
+
+#[async_trait]
+impl<T, Tbl> Filter<Tbl> for With<T>
+where
+    T: Send + Sync + 'static,
+    Tbl: BorrowColumn<T> + Sync,
+{
+    fn inner_types() -> Vec<TypeId> {
+        vec![TypeId::of::<T>()]
+    }
+
+    async fn filter_keys(db: &Tbl, _: Tick) -> FilterKeys {
+        let keys = ReadColumn::<T>::new(db).await.keys().copied().collect();
+        FilterKeys::Include(keys)
+    }
+}
+
+/// [`Row`] field that restricts a query to keys without a `T` cell.
+#[derive(Debug)]
+pub struct Without<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for Without<T> {
+    fn default() -> Self {
+        Without(PhantomData)
+    }
+}
+
+#[async_trait]
+impl<T, Tbl> Filter<Tbl> for Without<T>
+where
+    T: Send + Sync + 'static,
+    Tbl: BorrowColumn<T> + Sync,
+{
+    fn inner_types() -> Vec<TypeId> {
+        vec![TypeId::of::<T>()]
+    }
+
+    async fn filter_keys(db: &Tbl, _: Tick) -> FilterKeys {
+        let keys = ReadColumn::<T>::new(db).await.keys().copied().collect();
+        FilterKeys::Exclude(keys)
+    }
+}
+
+/// [`Row`] field that restricts a query to keys passing any of the filters in the tuple `T`,
+/// ex. `Or<(With<A>, Without<B>)>`.
+#[derive(Debug)]
+pub struct Or<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for Or<T> {
+    fn default() -> Self {
+        Or(PhantomData)
+    }
+}
+
+macro_rules! impl_or_filter {
+    ($($ty:ident),*) => {
+        #[async_trait]
+        impl<Tbl, $($ty,)*> Filter<Tbl> for Or<($($ty,)*)>
+        where
+            Tbl: Sync,
+            $(
+                $ty: Filter<Tbl>,
+            )*
+        {
+            fn inner_types() -> Vec<TypeId> {
+                let mut types = vec![];
+                $(
+                    types.extend($ty::inner_types());
+                )*
+                types
+            }
+
+            async fn filter_keys(db: &Tbl, tick: Tick) -> FilterKeys {
+                FilterKeys::Any(vec![$($ty::filter_keys(db, tick).await,)*])
+            }
+        }
+    };
+}
+
+impl_or_filter!(A);
+impl_or_filter!(A, B);
+impl_or_filter!(A, B, C);
+impl_or_filter!(A, B, C, D);
+impl_or_filter!(A, B, C, D, E);
+impl_or_filter!(A, B, C, D, E, F);
+impl_or_filter!(A, B, C, D, E, F, G);
+impl_or_filter!(A, B, C, D, E, F, G, H);
@@ -1,5 +1,3 @@
-use std::{borrow::Borrow, sync::atomic::AtomicUsize};
-
 use crate::{Key, Table};
 use async_trait::async_trait;
 
@@ -16,7 +14,7 @@ pub trait Insert<Tbl> {
     /// Insert values of this row's types into a [`Table`]
     async fn insert_auto(db: &Tbl, row: Self::Insert) -> Key
     where
-        Tbl: Table + Borrow<AtomicUsize>;
+        Tbl: Table;
 
     /// Insert values of this row's types into a [`Table`]
     async fn insert_multi<I>(db: &Tbl, rows: I)
@@ -27,6 +25,6 @@ pub trait Insert<Tbl> {
     /// Insert values of this row's types into a [`Table`]
     async fn insert_auto_multi<I>(db: &Tbl, rows: I) -> Vec<Key>
     where
-        Tbl: Table + Borrow<AtomicUsize>,
+        Tbl: Table,
         I: Iterator<Item = Self::Insert> + Send;
 }
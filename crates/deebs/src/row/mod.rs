@@ -1,17 +1,20 @@
 mod insert;
 mod remove;
 mod common_keys;
+mod filter;
 mod map;
 
 pub use insert::*;
 pub use remove::*;
 pub use common_keys::*;
+pub use filter::*;
 pub use map::*;
 
 use std::any::TypeId;
 
-use crate::{Key};
+use crate::{Key, Tick};
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 
 /// A type that can act as a virtual table row, containing views into the underlying cell data.
 #[async_trait]
@@ -23,5 +26,28 @@ pub trait Row<'a, Tbl> {
     fn inner_types() -> Vec<TypeId>;
 
     /// Create a new row
+    ///
+    /// Release it with the derived `release` function to deliver its writes to observers.
     async fn new(db: &'a Tbl, key: &Key) -> Self;
+
+    /// Lock each of this row's columns once and stream a row for every key that has all of its
+    /// required cells.
+    ///
+    /// The column guards are shared between the yielded rows,
+    /// and are released once the stream and all of its rows have been dropped.
+    /// Writes made through the rows are delivered to observers by the derived
+    /// `notify_observers` function, which must only be called after that.
+    ///
+    /// [`Added`] and [`Changed`] fields are evaluated against the default [`Tick`],
+    /// so pass the tick a system last ran at to [`Row::query_since`] instead.
+    async fn query(db: &'a Tbl) -> BoxStream<'a, Self>;
+
+    /// As [`Row::query`], evaluating [`Added`] and [`Changed`] fields against changes made after `tick`.
+    async fn query_since(db: &'a Tbl, tick: Tick) -> BoxStream<'a, Self>;
+
+    /// As [`Row::query`], but only yield rows for the matching subset of `keys`.
+    async fn query_keys(db: &'a Tbl, keys: Vec<Key>) -> BoxStream<'a, Self>;
+
+    /// As [`Row::query_keys`], evaluating [`Added`] and [`Changed`] fields against changes made after `tick`.
+    async fn query_keys_since(db: &'a Tbl, keys: Vec<Key>, tick: Tick) -> BoxStream<'a, Self>;
 }
@@ -0,0 +1,359 @@
+//! The executor-specific primitives deebs is built on.
+//!
+//! The backend is selected by cargo feature. The features are additive;
+//! if several are enabled, the first of these is used:
+//!
+//! * `tokio`: tokio locks and tasks. Spawning requires a running tokio runtime.
+//! * `smol`: smol locks and tasks.
+//! * `async-std` (default): async_std locks and tasks.
+//! * `parking_lot`: blocking parking_lot locks and thread-backed tasks, for sync-only use through
+//!   [`block_on`](crate::block_on) and the `try_` accessors.
+//!
+//! Key streams are built with [`futures::stream::iter`], which runs under any executor.
+
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+
+pub use futures::stream::{iter as from_iter, Iter as FromIter};
+
+#[cfg(not(any(
+    deebs_backend = "tokio",
+    deebs_backend = "smol",
+    deebs_backend = "async-std",
+    deebs_backend = "parking_lot"
+)))]
+compile_error!("deebs needs one of the tokio, smol, async-std or parking_lot features.");
+
+pub use imp::{RwLockReadGuard, RwLockWriteGuard};
+
+/// A reader-writer lock from the selected backend.
+#[derive(Debug, Default)]
+pub struct RwLock<T>(imp::RwLock<T>);
+
+impl<T> RwLock<T> {
+    pub fn new(value: T) -> Self {
+        RwLock(imp::RwLock::new(value))
+    }
+
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        imp::read(&self.0).await
+    }
+
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        imp::write(&self.0).await
+    }
+
+    /// Lock for reading if no writer holds the lock, returning `None` instead of waiting otherwise.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        imp::try_read(&self.0)
+    }
+
+    /// Lock for writing if the lock is free, returning `None` instead of waiting otherwise.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        imp::try_write(&self.0)
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut()
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<T> From<T> for RwLock<T> {
+    fn from(value: T) -> Self {
+        RwLock::new(value)
+    }
+}
+
+/// A task spawned with [`spawn`], resolving to its output.
+///
+/// Dropping the handle detaches the task rather than cancelling it.
+pub struct JoinHandle<T>(BoxFuture<'static, T>);
+
+impl<T> Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("JoinHandle")
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.as_mut().poll(cx)
+    }
+}
+
+/// Spawn `future` onto the selected backend's executor.
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    JoinHandle(imp::spawn(future))
+}
+
+/// Run `future` to completion on a new executor of the selected backend,
+/// inside of which tasks can be [`spawn`]ed.
+///
+/// Unlike [`block_on`](crate::block_on), `future` runs as a task of the backend,
+/// so it must not make blocking table calls itself.
+pub fn run<F: Future>(future: F) -> F::Output {
+    imp::run(future)
+}
+
+/// Run `future` to completion on the current thread without checking for an enclosing task.
+///
+/// Used by derived code for futures that can't contend on a lock,
+/// such as those over a table that is still being constructed.
+#[doc(hidden)]
+pub fn block_on_unchecked<F: Future>(future: F) -> F::Output {
+    futures::executor::block_on(future)
+}
+
+/// Return whether the caller is running inside a task of the selected backend.
+///
+/// Backends that can't detect their tasks always return `false`.
+pub(crate) fn in_task() -> bool {
+    imp::in_task()
+}
+
+/// Forward the output of `future` through a oneshot channel,
+/// for backends whose tasks can't be detached and awaited at once.
+#[cfg(any(deebs_backend = "smol", deebs_backend = "parking_lot"))]
+fn oneshot<F>(future: F) -> (impl Future<Output = ()>, BoxFuture<'static, F::Output>)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    let task = async move {
+        sender.send(future.await).ok();
+    };
+    let output = async move { receiver.await.expect("Spawned task panicked.") };
+    (task, Box::pin(output))
+}
+
+#[cfg(deebs_backend = "async-std")]
+mod imp {
+    use std::future::Future;
+
+    use futures::future::BoxFuture;
+
+    pub use async_std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+    pub async fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+        lock.read().await
+    }
+
+    pub async fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+        lock.write().await
+    }
+
+    pub fn try_read<T>(lock: &RwLock<T>) -> Option<RwLockReadGuard<'_, T>> {
+        lock.try_read()
+    }
+
+    pub fn try_write<T>(lock: &RwLock<T>) -> Option<RwLockWriteGuard<'_, T>> {
+        lock.try_write()
+    }
+
+    pub fn spawn<F>(future: F) -> BoxFuture<'static, F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        Box::pin(async_std::task::spawn(future))
+    }
+
+    pub fn run<F: Future>(future: F) -> F::Output {
+        async_std::task::block_on(future)
+    }
+
+    async_std::task_local! {
+        static TASK: () = ();
+    }
+
+    /// Task-locals are only available inside an async_std task.
+    pub fn in_task() -> bool {
+        TASK.try_with(|_| ()).is_ok()
+    }
+}
+
+#[cfg(deebs_backend = "tokio")]
+mod imp {
+    use std::{
+        future::Future,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        task::Context,
+    };
+
+    use futures::{future::BoxFuture, task::ArcWake};
+
+    pub use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+    pub async fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+        lock.read().await
+    }
+
+    pub async fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+        lock.write().await
+    }
+
+    pub fn try_read<T>(lock: &RwLock<T>) -> Option<RwLockReadGuard<'_, T>> {
+        lock.try_read().ok()
+    }
+
+    pub fn try_write<T>(lock: &RwLock<T>) -> Option<RwLockWriteGuard<'_, T>> {
+        lock.try_write().ok()
+    }
+
+    pub fn spawn<F>(future: F) -> BoxFuture<'static, F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let handle = tokio::spawn(future);
+        Box::pin(async move {
+            match handle.await {
+                Ok(output) => output,
+                Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+                Err(e) => panic!("Spawned task failed: {}", e),
+            }
+        })
+    }
+
+    pub fn run<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("Failed to build a tokio runtime.")
+            .block_on(future)
+    }
+
+    /// Sets its flag when woken.
+    struct Woken(AtomicBool);
+
+    impl ArcWake for Woken {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// tokio can't be asked whether its scheduler is polling the current thread.
+    /// Threads of `spawn_blocking` have a runtime handle and a task ID all the same,
+    /// so poll `yield_now` instead, which hands its waker to the scheduler if there is one,
+    /// and wakes it at once otherwise.
+    pub fn in_task() -> bool {
+        if tokio::runtime::Handle::try_current().is_err() {
+            return false;
+        }
+
+        let woken = Arc::new(Woken(AtomicBool::new(false)));
+        let waker = futures::task::waker(woken.clone());
+        let yield_now = tokio::task::yield_now();
+        futures::pin_mut!(yield_now);
+        let _ = yield_now.poll(&mut Context::from_waker(&waker));
+        !woken.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(deebs_backend = "smol")]
+mod imp {
+    use std::future::Future;
+
+    use futures::future::BoxFuture;
+
+    pub use smol::lock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+    pub async fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+        lock.read().await
+    }
+
+    pub async fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+        lock.write().await
+    }
+
+    pub fn try_read<T>(lock: &RwLock<T>) -> Option<RwLockReadGuard<'_, T>> {
+        lock.try_read()
+    }
+
+    pub fn try_write<T>(lock: &RwLock<T>) -> Option<RwLockWriteGuard<'_, T>> {
+        lock.try_write()
+    }
+
+    pub fn spawn<F>(future: F) -> BoxFuture<'static, F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (task, output) = super::oneshot(future);
+        smol::spawn(task).detach();
+        output
+    }
+
+    pub fn run<F: Future>(future: F) -> F::Output {
+        smol::block_on(future)
+    }
+
+    /// smol exposes no way to detect its tasks.
+    pub fn in_task() -> bool {
+        false
+    }
+}
+
+#[cfg(deebs_backend = "parking_lot")]
+mod imp {
+    use std::future::Future;
+
+    use futures::future::BoxFuture;
+
+    pub use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+    pub async fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+        lock.read()
+    }
+
+    pub async fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+        lock.write()
+    }
+
+    pub fn try_read<T>(lock: &RwLock<T>) -> Option<RwLockReadGuard<'_, T>> {
+        lock.try_read()
+    }
+
+    pub fn try_write<T>(lock: &RwLock<T>) -> Option<RwLockWriteGuard<'_, T>> {
+        lock.try_write()
+    }
+
+    /// Run `future` to completion on its own thread.
+    pub fn spawn<F>(future: F) -> BoxFuture<'static, F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (task, output) = super::oneshot(future);
+        std::thread::spawn(move || futures::executor::block_on(task));
+        output
+    }
+
+    pub fn run<F: Future>(future: F) -> F::Output {
+        futures::executor::block_on(future)
+    }
+
+    /// There are no tasks to detect without an executor.
+    pub fn in_task() -> bool {
+        false
+    }
+}
@@ -0,0 +1,138 @@
+//! [`serde`] support for table storage, enabled by the `serde` feature.
+//!
+//! Locks are taken with `try_read`, so serializing a table that is being written to
+//! fails instead of blocking.
+
+use std::ops::Deref;
+
+use serde::{
+    de::Error as _, ser::Error as _, ser::SerializeSeq, Deserialize, Deserializer, Serialize,
+    Serializer,
+};
+
+use crate::{Column, ColumnCollection, Key, ReadCell, Singleton, WriteCell};
+
+/// Serialized as a sequence of `(Key, T)` pairs in key order.
+impl<T> Serialize for ColumnCollection<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+
+        let mut keys = self.keys().copied().collect::<Vec<_>>();
+        keys.sort();
+
+        for key in keys {
+            self.try_with_cell(&key, |cell| seq.serialize_element(&(key, cell)))
+                .map_err(|_| S::Error::custom(format!("Cell {} is locked.", key)))??;
+        }
+
+        seq.end()
+    }
+}
+
+/// Deserialized into map storage;
+/// a [`Table`](crate::Table) moves it into its own [`ColumnStorage`](crate::ColumnStorage).
+impl<'de, T> Deserialize<'de> for ColumnCollection<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let cells = Vec::<(Key, T)>::deserialize(deserializer)?;
+
+        let mut collection = ColumnCollection::default();
+        collection.reserve(cells.len());
+        for (key, value) in cells {
+            if collection.insert(key, value).is_some() {
+                return Err(D::Error::custom(format!("Duplicate key {}.", key)));
+            }
+        }
+
+        Ok(collection)
+    }
+}
+
+impl<T> Serialize for Column<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.try_read()
+            .ok_or_else(|| S::Error::custom("Column is locked."))?
+            .serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Column<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut column = Column::default();
+        *column.get_mut() = ColumnCollection::deserialize(deserializer)?;
+        Ok(column)
+    }
+}
+
+impl<T> Serialize for Singleton<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.try_read()
+            .ok_or_else(|| S::Error::custom("Singleton is locked."))?
+            .serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Singleton<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Singleton::new)
+    }
+}
+
+impl<'a, T> Serialize for ReadCell<'a, T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.deref().serialize(serializer)
+    }
+}
+
+impl<'a, T> Serialize for WriteCell<'a, T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.deref().serialize(serializer)
+    }
+}
@@ -3,11 +3,17 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
-use async_std::sync::RwLock;
+use crate::runtime::RwLock;
 
 #[derive(Debug, Default)]
 pub struct Singleton<T>(RwLock<T>);
 
+impl<T> Singleton<T> {
+    pub fn new(value: T) -> Self {
+        Singleton(RwLock::new(value))
+    }
+}
+
 impl<T> Deref for Singleton<T> {
     type Target = RwLock<T>;
 
@@ -0,0 +1,234 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use futures::future::BoxFuture;
+
+use crate::{key::KeySlots, BorrowColumn, Column, Key, ReadColumn, Table, Tick, WriteColumn};
+
+/// The cells of one column, sorted by key.
+type Cells<T> = Arc<Vec<(Key, T)>>;
+
+/// The captured cells of one [`Column`], shared with its [`SnapshotCache`]
+/// and with every other [`Snapshot`] taken while the column was unchanged.
+#[derive(Debug, Clone)]
+pub struct ColumnSnapshot(Arc<dyn Any + Send + Sync>);
+
+impl ColumnSnapshot {
+    fn cells<T>(&self) -> Option<Cells<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.0.clone().downcast().ok()
+    }
+}
+
+/// The state of a set of columns, and of the table's [`KeyAllocator`](crate::KeyAllocator),
+/// captured by [`Table::snapshot`] and applied by [`Table::restore`].
+///
+/// Cloning a snapshot is cheap, as columns are shared rather than copied.
+#[derive(Debug, Default, Clone)]
+pub struct Snapshot {
+    keys: Option<KeySlots>,
+    columns: HashMap<TypeId, ColumnSnapshot>,
+}
+
+impl Snapshot {
+    /// Return true if this snapshot captured the column holding `type_id`.
+    pub fn contains(&self, type_id: TypeId) -> bool {
+        self.columns.contains_key(&type_id)
+    }
+
+    /// Return the type of each captured column.
+    pub fn type_ids(&self) -> impl Iterator<Item = &TypeId> {
+        self.columns.keys()
+    }
+
+    /// Return the captured cells of the `T` column in key order.
+    pub fn cells<T>(&self) -> Option<&[(Key, T)]>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.columns
+            .get(&TypeId::of::<T>())?
+            .0
+            .downcast_ref::<Vec<(Key, T)>>()
+            .map(Vec::as_slice)
+    }
+
+    pub(crate) fn set_keys(&mut self, keys: KeySlots) {
+        self.keys = Some(keys);
+    }
+
+    pub(crate) fn keys(&self) -> Option<&KeySlots> {
+        self.keys.as_ref()
+    }
+
+    pub(crate) fn insert(&mut self, type_id: TypeId, column: ColumnSnapshot) {
+        self.columns.insert(type_id, column);
+    }
+
+    pub(crate) fn get(&self, type_id: TypeId) -> Option<&ColumnSnapshot> {
+        self.columns.get(&type_id)
+    }
+}
+
+/// The most recent [`ColumnSnapshot`] of a [`Column`],
+/// along with the column's [`ColumnChanges::latest`](crate::ColumnChanges::latest) tick when it was taken.
+///
+/// Snapshotting a column that hasn't been written since reuses the cached cells.
+pub(crate) struct SnapshotCache<T>(Mutex<Option<(Tick, Cells<T>)>>);
+
+impl<T> Default for SnapshotCache<T> {
+    fn default() -> Self {
+        SnapshotCache(Default::default())
+    }
+}
+
+impl<T> std::fmt::Debug for SnapshotCache<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SnapshotCache")
+            .field(&self.inner().as_ref().map(|(tick, _)| *tick))
+            .finish()
+    }
+}
+
+impl<T> SnapshotCache<T> {
+    fn inner(&self) -> MutexGuard<'_, Option<(Tick, Cells<T>)>> {
+        self.0.lock().expect("Snapshot cache is poisoned.")
+    }
+
+    fn get(&self, latest: Tick) -> Option<Cells<T>> {
+        match &*self.inner() {
+            Some((tick, cells)) if *tick == latest => Some(cells.clone()),
+            _ => None,
+        }
+    }
+
+    fn set(&self, latest: Tick, cells: Cells<T>) {
+        *self.inner() = Some((latest, cells));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self.0.get_mut().expect("Snapshot cache is poisoned.") = None;
+    }
+}
+
+pub type SnapshotColumnFn<Tbl> = for<'a> fn(&'a Tbl) -> BoxFuture<'a, ColumnSnapshot>;
+pub type RestoreColumnFn<Tbl> = for<'a> fn(&'a Tbl, &'a ColumnSnapshot) -> BoxFuture<'a, Vec<Key>>;
+pub type NotifyColumnFn<Tbl> = for<'a> fn(&'a Tbl) -> BoxFuture<'a, ()>;
+
+/// Type-erased snapshot operations for a column whose type is `Clone`.
+pub struct SnapshotFns<Tbl> {
+    /// Capture the column's cells.
+    pub snapshot: SnapshotColumnFn<Tbl>,
+    /// Replace the column's cells with captured ones, returning every key that was touched.
+    pub restore: RestoreColumnFn<Tbl>,
+    /// Deliver the events recorded by `restore` to the column's observers.
+    pub notify: NotifyColumnFn<Tbl>,
+}
+
+impl<Tbl> SnapshotFns<Tbl> {
+    pub fn new<T>() -> Self
+    where
+        Tbl: Table + BorrowColumn<T> + Sync,
+        T: Clone + Send + Sync + 'static,
+    {
+        SnapshotFns {
+            snapshot: snapshot_column::<Tbl, T>,
+            restore: restore_column::<Tbl, T>,
+            notify: notify_column::<Tbl, T>,
+        }
+    }
+}
+
+impl<Tbl> Clone for SnapshotFns<Tbl> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tbl> Copy for SnapshotFns<Tbl> {}
+
+fn snapshot_column<Tbl, T>(table: &Tbl) -> BoxFuture<'_, ColumnSnapshot>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: Clone + Send + Sync + 'static,
+{
+    Box::pin(async move {
+        let column: &Column<T> = table.borrow();
+        let cells = ReadColumn::<T>::new(table).await;
+
+        // Read under the column lock so that writes made after it is released
+        // claim a later tick and invalidate the cache.
+        let latest = column.changes().latest();
+        if let Some(cached) = column.snapshot_cache().get(latest) {
+            return ColumnSnapshot(cached);
+        }
+
+        let mut keys = cells.keys().copied().collect::<Vec<_>>();
+        keys.sort();
+
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(value) = cells.with_cell(&key, T::clone).await {
+                values.push((key, value));
+            }
+        }
+
+        let values = Arc::new(values);
+        column.snapshot_cache().set(latest, values.clone());
+        ColumnSnapshot(values)
+    })
+}
+
+fn restore_column<'a, Tbl, T>(
+    table: &'a Tbl,
+    snapshot: &'a ColumnSnapshot,
+) -> BoxFuture<'a, Vec<Key>>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: Clone + Send + Sync + 'static,
+{
+    Box::pin(async move {
+        let values = snapshot
+            .cells::<T>()
+            .expect("Column snapshot has the wrong type.");
+
+        let column: &Column<T> = table.borrow();
+        let mut cells = WriteColumn::<T>::new(table).await;
+
+        let stale = cells
+            .keys()
+            .filter(|key| values.binary_search_by_key(*key, |(key, _)| *key).is_err())
+            .copied()
+            .collect::<Vec<_>>();
+
+        for key in stale.iter() {
+            cells.remove(key);
+        }
+
+        for (key, value) in values.iter() {
+            cells.insert(*key, value.clone());
+        }
+
+        // The column now matches the snapshot, so the next one can share it.
+        let latest = column.changes().latest();
+        column.snapshot_cache().set(latest, values.clone());
+
+        stale
+            .into_iter()
+            .chain(values.iter().map(|(key, _)| *key))
+            .collect()
+    })
+}
+
+pub(crate) fn notify_column<Tbl, T>(table: &Tbl) -> BoxFuture<'_, ()>
+where
+    Tbl: Table + BorrowColumn<T> + Sync,
+    T: Send + Sync + 'static,
+{
+    Box::pin(table.notify_observers::<T>())
+}
@@ -0,0 +1,441 @@
+use std::{
+    cell::UnsafeCell,
+    collections::HashMap,
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+};
+
+use crate::runtime::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{CellError, Key};
+
+const VACANT: usize = usize::MAX;
+
+/// The layout a [`Column`] uses to store its cells.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColumnStorage {
+    /// A hash map of individually locked cells.
+    Map,
+    /// A sparse set of contiguous cells, indexed by [`Key::index`].
+    Dense,
+}
+
+/// The cells of a [`Column`], laid out according to its [`ColumnStorage`].
+#[derive(Debug)]
+pub enum ColumnCollection<T> {
+    Map(HashMap<Key, RwLock<T>, fnv::FnvBuildHasher>),
+    Dense(DenseCollection<T>),
+}
+
+impl<T> Default for ColumnCollection<T> {
+    fn default() -> Self {
+        ColumnCollection::Map(Default::default())
+    }
+}
+
+impl<T> ColumnCollection<T> {
+    pub fn with_storage(storage: ColumnStorage) -> Self {
+        match storage {
+            ColumnStorage::Map => ColumnCollection::Map(Default::default()),
+            ColumnStorage::Dense => ColumnCollection::Dense(Default::default()),
+        }
+    }
+
+    pub fn storage(&self) -> ColumnStorage {
+        match self {
+            ColumnCollection::Map(_) => ColumnStorage::Map,
+            ColumnCollection::Dense(_) => ColumnStorage::Dense,
+        }
+    }
+
+    /// Move every cell into `storage` if this collection isn't already laid out that way.
+    pub fn set_storage(&mut self, storage: ColumnStorage) {
+        if self.storage() == storage {
+            return;
+        }
+
+        let mut cells = match std::mem::replace(self, ColumnCollection::with_storage(storage)) {
+            ColumnCollection::Map(map) => map
+                .into_iter()
+                .map(|(key, cell)| (key, cell.into_inner()))
+                .collect::<Vec<_>>(),
+            ColumnCollection::Dense(dense) => dense
+                .keys
+                .into_iter()
+                .zip(dense.cells.into_iter().map(UnsafeCell::into_inner))
+                .collect::<Vec<_>>(),
+        };
+
+        cells.sort_by_key(|(key, _)| *key);
+
+        self.reserve(cells.len());
+        for (key, value) in cells {
+            self.insert(key, value);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnCollection::Map(map) => map.len(),
+            ColumnCollection::Dense(dense) => dense.keys.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains_key(&self, key: &Key) -> bool {
+        match self {
+            ColumnCollection::Map(map) => map.contains_key(key),
+            ColumnCollection::Dense(dense) => dense.index_of(key).is_some(),
+        }
+    }
+
+    pub fn keys(&self) -> Box<dyn Iterator<Item = &Key> + '_> {
+        match self {
+            ColumnCollection::Map(map) => Box::new(map.keys()),
+            ColumnCollection::Dense(dense) => Box::new(dense.keys.iter()),
+        }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        match self {
+            ColumnCollection::Map(map) => map.reserve(additional),
+            ColumnCollection::Dense(dense) => {
+                dense.keys.reserve(additional);
+                dense.cells.reserve(additional);
+                dense.locks.reserve(additional);
+            }
+        }
+    }
+
+    /// Insert or replace the cell at `key`, returning its previous value.
+    pub fn insert(&mut self, key: Key, value: T) -> Option<T> {
+        match self {
+            ColumnCollection::Map(map) => map.insert(key, value.into()).map(RwLock::into_inner),
+            ColumnCollection::Dense(dense) => dense.insert(key, value),
+        }
+    }
+
+    /// Return the key of another generation whose cell occupies `key`'s slot.
+    ///
+    /// Only a dense collection can hold one; inserting at `key` would replace its cell,
+    /// so guards remove it first to record the removal.
+    pub fn occupant(&self, key: &Key) -> Option<Key> {
+        match self {
+            ColumnCollection::Map(_) => None,
+            ColumnCollection::Dense(dense) => dense.occupant(key),
+        }
+    }
+
+    /// Remove the cell at `key`, returning its value.
+    pub fn remove(&mut self, key: &Key) -> Option<T> {
+        match self {
+            ColumnCollection::Map(map) => map.remove(key).map(RwLock::into_inner),
+            ColumnCollection::Dense(dense) => dense.remove(key),
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &Key) -> Option<&mut T> {
+        match self {
+            ColumnCollection::Map(map) => map.get_mut(key).map(RwLock::get_mut),
+            ColumnCollection::Dense(dense) => {
+                let index = dense.index_of(key)?;
+                Some(dense.cells[index].get_mut())
+            }
+        }
+    }
+
+    /// Return the keys and values of a dense collection as parallel slices.
+    ///
+    /// Takes `&mut self` so that no [`WriteCell`](crate::WriteCell) can alias the values.
+    pub fn slices(&mut self) -> Option<(&[Key], &[T])> {
+        match self {
+            ColumnCollection::Map(_) => None,
+            ColumnCollection::Dense(dense) => Some(dense.slices()),
+        }
+    }
+
+    /// Read-lock every cell of a dense collection, returning its keys and values as parallel slices
+    /// that keep the cells locked against [`WriteCell`](crate::WriteCell)s while borrowed.
+    pub async fn read_slices(&self) -> Option<ReadSlices<'_, T>> {
+        match self {
+            ColumnCollection::Map(_) => None,
+            ColumnCollection::Dense(dense) => {
+                let mut locks = Vec::with_capacity(dense.locks.len());
+                for lock in dense.locks.iter() {
+                    locks.push(lock.read().await);
+                }
+
+                // UnsafeCell<T> has the same in-memory representation as T.
+                let values = unsafe {
+                    std::slice::from_raw_parts(dense.cells.as_ptr() as *const T, dense.cells.len())
+                };
+
+                Some(ReadSlices {
+                    keys: &dense.keys,
+                    values,
+                    _locks: locks,
+                })
+            }
+        }
+    }
+
+    /// Return the keys and mutable values of a dense collection as parallel slices.
+    pub fn slices_mut(&mut self) -> Option<(&[Key], &mut [T])> {
+        match self {
+            ColumnCollection::Map(_) => None,
+            ColumnCollection::Dense(dense) => Some(dense.slices_mut()),
+        }
+    }
+
+    /// Run `f` on the cell at `key` without waiting for its lock.
+    pub(crate) fn try_with_cell<R>(
+        &self,
+        key: &Key,
+        f: impl FnOnce(&T) -> R,
+    ) -> Result<R, CellError> {
+        match self {
+            ColumnCollection::Map(map) => {
+                let cell = map.get(key).ok_or(CellError::Missing(*key))?;
+                let cell = cell.try_read().ok_or(CellError::Locked(*key))?;
+                Ok(f(cell.deref()))
+            }
+            ColumnCollection::Dense(dense) => {
+                let index = dense.index_of(key).ok_or(CellError::Missing(*key))?;
+                let _lock = dense.locks[index]
+                    .try_read()
+                    .ok_or(CellError::Locked(*key))?;
+                Ok(f(unsafe { dense.cell(index).as_ref() }))
+            }
+        }
+    }
+
+    /// Run `f` on the cell at `key`, waiting for its lock.
+    pub(crate) async fn with_cell<R>(&self, key: &Key, f: impl FnOnce(&T) -> R) -> Option<R> {
+        match self {
+            ColumnCollection::Map(map) => Some(f(map.get(key)?.read().await.deref())),
+            ColumnCollection::Dense(dense) => {
+                let index = dense.index_of(key)?;
+                let _lock = dense.locks[index].read().await;
+                Some(f(unsafe { dense.cell(index).as_ref() }))
+            }
+        }
+    }
+
+    /// Lock the cell at `key` for a [`ReadCell`], returning a pointer to it and its lock.
+    pub(crate) async fn read_cell(&self, key: &Key) -> Option<(NonNull<T>, CellLock<'_, T>)> {
+        match self {
+            ColumnCollection::Map(map) => {
+                let guard = map.get(key)?.read().await;
+                Some((NonNull::from(guard.deref()), CellLock::MapRead(guard)))
+            }
+            ColumnCollection::Dense(dense) => {
+                let index = dense.index_of(key)?;
+                let guard = dense.locks[index].read().await;
+                Some((dense.cell(index), CellLock::DenseRead(guard)))
+            }
+        }
+    }
+
+    /// Lock the cell at `key` for a [`WriteCell`], returning a pointer to it and its lock.
+    pub(crate) async fn write_cell(&self, key: &Key) -> Option<(NonNull<T>, CellLock<'_, T>)> {
+        match self {
+            ColumnCollection::Map(map) => {
+                let mut guard = map.get(key)?.write().await;
+                Some((NonNull::from(guard.deref_mut()), CellLock::MapWrite(guard)))
+            }
+            ColumnCollection::Dense(dense) => {
+                let index = dense.index_of(key)?;
+                let guard = dense.locks[index].write().await;
+                Some((dense.cell(index), CellLock::DenseWrite(guard)))
+            }
+        }
+    }
+
+    /// As [`ColumnCollection::read_cell`], without waiting for the cell's lock.
+    pub(crate) fn try_read_cell(
+        &self,
+        key: &Key,
+    ) -> Result<(NonNull<T>, CellLock<'_, T>), CellError> {
+        match self {
+            ColumnCollection::Map(map) => {
+                let cell = map.get(key).ok_or(CellError::Missing(*key))?;
+                let guard = cell.try_read().ok_or(CellError::Locked(*key))?;
+                Ok((NonNull::from(guard.deref()), CellLock::MapRead(guard)))
+            }
+            ColumnCollection::Dense(dense) => {
+                let index = dense.index_of(key).ok_or(CellError::Missing(*key))?;
+                let guard = dense.locks[index]
+                    .try_read()
+                    .ok_or(CellError::Locked(*key))?;
+                Ok((dense.cell(index), CellLock::DenseRead(guard)))
+            }
+        }
+    }
+
+    /// As [`ColumnCollection::write_cell`], without waiting for the cell's lock.
+    pub(crate) fn try_write_cell(
+        &self,
+        key: &Key,
+    ) -> Result<(NonNull<T>, CellLock<'_, T>), CellError> {
+        match self {
+            ColumnCollection::Map(map) => {
+                let cell = map.get(key).ok_or(CellError::Missing(*key))?;
+                let mut guard = cell.try_write().ok_or(CellError::Locked(*key))?;
+                Ok((NonNull::from(guard.deref_mut()), CellLock::MapWrite(guard)))
+            }
+            ColumnCollection::Dense(dense) => {
+                let index = dense.index_of(key).ok_or(CellError::Missing(*key))?;
+                let guard = dense.locks[index]
+                    .try_write()
+                    .ok_or(CellError::Locked(*key))?;
+                Ok((dense.cell(index), CellLock::DenseWrite(guard)))
+            }
+        }
+    }
+}
+
+/// The keys and values of a dense [`ColumnCollection`] as parallel slices,
+/// holding the read lock of every cell for as long as they are borrowed.
+#[derive(Debug)]
+pub struct ReadSlices<'a, T> {
+    keys: &'a [Key],
+    values: &'a [T],
+    _locks: Vec<RwLockReadGuard<'a, ()>>,
+}
+
+impl<'a, T> ReadSlices<'a, T> {
+    pub fn keys(&self) -> &[Key] {
+        self.keys
+    }
+
+    pub fn values(&self) -> &[T] {
+        self.values
+    }
+}
+
+/// The lock on a single cell of a [`ColumnCollection`], held by a [`ReadCell`](crate::ReadCell)
+/// or [`WriteCell`](crate::WriteCell) for as long as it points into the cell.
+#[allow(dead_code)] // The guards are only held to be released on drop
+pub(crate) enum CellLock<'a, T> {
+    MapRead(RwLockReadGuard<'a, T>),
+    MapWrite(RwLockWriteGuard<'a, T>),
+    DenseRead(RwLockReadGuard<'a, ()>),
+    DenseWrite(RwLockWriteGuard<'a, ()>),
+}
+
+impl<'a, T> Debug for CellLock<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellLock::MapRead(_) | CellLock::DenseRead(_) => f.write_str("CellLock::Read"),
+            CellLock::MapWrite(_) | CellLock::DenseWrite(_) => f.write_str("CellLock::Write"),
+        }
+    }
+}
+
+/// Sparse set storage for [`ColumnCollection`].
+///
+/// Cells are packed into a contiguous vector and located through a sparse vector indexed by
+/// [`Key::index`], so a dense collection holds at most one generation of each key index.
+/// Inserting another generation replaces its cell, so the guards first remove it
+/// through their change tracking; see [`ColumnCollection::occupant`].
+///
+/// Each cell has its own lock, kept apart from the cells so that they stay contiguous.
+#[derive(Debug)]
+pub struct DenseCollection<T> {
+    keys: Vec<Key>,
+    cells: Vec<UnsafeCell<T>>,
+    locks: Vec<RwLock<()>>,
+    sparse: Vec<usize>,
+}
+
+// Shared access to a cell goes through its lock, as with the map storage's cells;
+// the cells as a whole are only reached through `&mut self`.
+unsafe impl<T: Send + Sync> Sync for DenseCollection<T> {}
+
+impl<T> Default for DenseCollection<T> {
+    fn default() -> Self {
+        DenseCollection {
+            keys: vec![],
+            cells: vec![],
+            locks: vec![],
+            sparse: vec![],
+        }
+    }
+}
+
+impl<T> DenseCollection<T> {
+    fn index_of(&self, key: &Key) -> Option<usize> {
+        match self.sparse.get(key.index()) {
+            Some(&index) if index != VACANT && self.keys[index] == *key => Some(index),
+            _ => None,
+        }
+    }
+
+    fn occupant(&self, key: &Key) -> Option<Key> {
+        match self.sparse.get(key.index()) {
+            Some(&index) if index != VACANT && self.keys[index] != *key => Some(self.keys[index]),
+            _ => None,
+        }
+    }
+
+    fn insert(&mut self, key: Key, value: T) -> Option<T> {
+        if let Some(index) = self.index_of(&key) {
+            return Some(std::mem::replace(self.cells[index].get_mut(), value));
+        }
+
+        if self.sparse.len() <= key.index() {
+            self.sparse.resize(key.index() + 1, VACANT);
+        }
+
+        let index = self.sparse[key.index()];
+        if index != VACANT {
+            self.keys[index] = key;
+            *self.cells[index].get_mut() = value;
+            return None;
+        }
+
+        self.sparse[key.index()] = self.keys.len();
+        self.keys.push(key);
+        self.cells.push(UnsafeCell::new(value));
+        self.locks.push(RwLock::default());
+        None
+    }
+
+    fn remove(&mut self, key: &Key) -> Option<T> {
+        let index = self.index_of(key)?;
+
+        self.sparse[key.index()] = VACANT;
+        self.keys.swap_remove(index);
+        let cell = self.cells.swap_remove(index);
+        self.locks.swap_remove(index);
+
+        if let Some(moved) = self.keys.get(index) {
+            self.sparse[moved.index()] = index;
+        }
+
+        Some(cell.into_inner())
+    }
+
+    fn cell(&self, index: usize) -> NonNull<T> {
+        NonNull::new(self.cells[index].get()).unwrap()
+    }
+
+    fn slices(&mut self) -> (&[Key], &[T]) {
+        // UnsafeCell<T> has the same in-memory representation as T.
+        let values = unsafe {
+            std::slice::from_raw_parts(self.cells.as_ptr() as *const T, self.cells.len())
+        };
+        (&self.keys, values)
+    }
+
+    fn slices_mut(&mut self) -> (&[Key], &mut [T]) {
+        let values = unsafe {
+            std::slice::from_raw_parts_mut(self.cells.as_mut_ptr() as *mut T, self.cells.len())
+        };
+        (&self.keys, values)
+    }
+}
@@ -0,0 +1,48 @@
+use std::any::TypeId;
+
+use crate::{reflect::ColumnInfo, CellIndex, ColumnStorage, Key, LockDespawnFn, Table};
+
+/// A bundle of columns, singletons and views that a [`Table`] embeds with a `#[flatten]` field.
+///
+/// Usually derived with [`macros::SubTable`](crate::macros::SubTable) alongside `Borrow`,
+/// so that a crate can ship its columns as one struct instead of having every table list them.
+/// The `Table` derive of the embedding table forwards its per-column work to each bundle,
+/// and its `Borrow` derive implements [`BorrowColumn`](crate::BorrowColumn),
+/// [`BorrowSingleton`](crate::BorrowSingleton) and [`BorrowView`](crate::BorrowView)
+/// for every field of the bundle.
+#[async_trait::async_trait]
+pub trait SubTable<Tbl>: Sync
+where
+    Tbl: Table + Sync,
+{
+    /// Extend `keys` with their descendants if this bundle holds both hierarchy columns.
+    async fn despawn_hierarchy(&self, table: &Tbl, keys: Vec<Key>) -> Vec<Key>;
+
+    /// Return the type of each of this bundle's columns alongside a function write-locking it,
+    /// so the embedding table can lock them together with its own columns to despawn.
+    fn despawn_columns() -> Vec<(TypeId, LockDespawnFn<Tbl>)>
+    where
+        Self: Sized;
+
+    /// Notify the observers of this bundle's columns.
+    async fn notify_all_observers(&self, table: &Tbl);
+
+    /// Return the [`ColumnStorage`] of the column holding `type_id`, if this bundle holds it.
+    fn column_storage(&self, type_id: TypeId) -> Option<ColumnStorage>;
+
+    /// Return a new, empty [`CellIndex`] for the `T` column, if this bundle indexes it.
+    fn column_index<T>(&self) -> Option<Box<dyn CellIndex<T>>>
+    where
+        T: 'static;
+
+    /// Update this bundle's views that depend on one of `type_ids`.
+    async fn update_views(&self, table: &Tbl, type_ids: &[TypeId], keys: &[Key]);
+
+    /// Recompute this bundle's views from scratch.
+    async fn rebuild_views(&self, table: &Tbl);
+
+    /// Describe this bundle's columns for runtime reflection.
+    fn column_infos() -> Vec<ColumnInfo<Tbl>>
+    where
+        Self: Sized;
+}
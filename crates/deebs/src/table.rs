@@ -1,36 +1,50 @@
 use std::{
     any::TypeId,
-    borrow::Borrow,
     collections::BTreeSet,
-    sync::atomic::{AtomicUsize, Ordering},
+    future::Future,
+    ops::{Bound, RangeBounds},
 };
 
-use async_std::stream::FromIter;
+use async_channel::Receiver;
+use futures::stream::BoxStream;
 
-use crate::{BorrowColumn, Key, ReadCell, ReadColumn, WriteCell, WriteColumn};
+use crate::{
+    reflect::{ColumnInfo, ColumnRegistry},
+    runtime::FromIter,
+    BorrowColumn, BorrowView, CellError, CellIndex, Children, Column, ColumnStorage,
+    DespawnedCells, HierarchyError, Key, KeyAllocator, ObserveEvent, ObserveEvents, Parent,
+    ReadCell, ReadColumn, Row, Snapshot, Tick, TransactionColumns, View, WriteCell, WriteColumn,
+};
 
 /// A type that holds [`View`] structs.
 #[async_trait::async_trait]
 pub trait Table {
     type Key: Ord + Copy;
 
+    /// Insert `value` into the `T` column at `key`.
+    ///
+    /// Writes through a stale key are refused, as by [`WriteColumn::insert`].
     async fn insert<T>(&self, key: Key, value: T)
     where
         Self: Sized + BorrowColumn<T>,
         T: Send + Sync + 'static,
     {
-        WriteColumn::new(self).await.insert(key, value.into());
-        self.update_views(&[std::any::TypeId::of::<T>()]).await;
+        WriteColumn::new(self).await.insert(key, value);
+        self.update_views(&[std::any::TypeId::of::<T>()], &[key])
+            .await;
+        self.notify_observers::<T>().await;
     }
 
     async fn insert_auto<T>(&self, value: T) -> Key
     where
-        Self: Sized + Borrow<AtomicUsize> + BorrowColumn<T>,
+        Self: Sized + BorrowColumn<T>,
         T: Send + Sync + 'static,
     {
         let key = self.next_key();
-        WriteColumn::new(self).await.insert(key, value.into());
-        self.update_views(&[std::any::TypeId::of::<T>()]).await;
+        WriteColumn::new(self).await.insert(key, value);
+        self.update_views(&[std::any::TypeId::of::<T>()], &[key])
+            .await;
+        self.notify_observers::<T>().await;
         key
     }
 
@@ -40,18 +54,22 @@ pub trait Table {
         I: Iterator<Item = (Key, T)> + Send + Sync,
         T: Send + Sync + 'static,
     {
+        let mut keys = vec![];
         {
             let mut column = WriteColumn::new(self).await;
             for (key, value) in values {
-                column.insert(key, value.into());
+                column.insert(key, value);
+                keys.push(key);
             }
         }
-        self.update_views(&[std::any::TypeId::of::<T>()]).await;
+        self.update_views(&[std::any::TypeId::of::<T>()], &keys)
+            .await;
+        self.notify_observers::<T>().await;
     }
 
     async fn insert_auto_multi<I, T>(&self, values: I) -> Vec<Key>
     where
-        Self: Sized + Borrow<AtomicUsize> + BorrowColumn<T>,
+        Self: Sized + BorrowColumn<T>,
         I: Iterator<Item = T> + Send + Sync,
         T: Send + Sync + 'static,
     {
@@ -60,11 +78,13 @@ pub trait Table {
             let mut column = WriteColumn::new(self).await;
             for value in values {
                 let key = self.next_key();
-                column.insert(key, value.into());
+                column.insert(key, value);
                 keys.push(key);
             }
         }
-        self.update_views(&[std::any::TypeId::of::<T>()]).await;
+        self.update_views(&[std::any::TypeId::of::<T>()], &keys)
+            .await;
+        self.notify_observers::<T>().await;
         keys
     }
 
@@ -77,7 +97,9 @@ pub trait Table {
             let mut column = WriteColumn::new(self).await;
             column.remove(&key);
         }
-        self.update_views(&[std::any::TypeId::of::<T>()]).await;
+        self.update_views(&[std::any::TypeId::of::<T>()], &[key])
+            .await;
+        self.notify_observers::<T>().await;
     }
 
     async fn remove_multi<T, I>(&self, keys: I)
@@ -86,55 +108,550 @@ pub trait Table {
         I: Iterator<Item = Key> + Send + Sync,
         T: Send + Sync + 'static,
     {
+        let keys = keys.collect::<Vec<_>>();
         {
             let mut column = WriteColumn::new(self).await;
-            for key in keys {
-                column.remove(&key);
+            for key in keys.iter() {
+                column.remove(key);
             }
         }
-        self.update_views(&[std::any::TypeId::of::<T>()]).await;
+        self.update_views(&[std::any::TypeId::of::<T>()], &keys)
+            .await;
+        self.notify_observers::<T>().await;
+    }
+
+    /// Remove `key` from every column and free it, returning the number of cells dropped.
+    async fn despawn(&self, key: Key) -> usize {
+        self.despawn_multi(std::iter::once(key)).await
+    }
+
+    /// Remove each of `keys` from every column and free them, returning the number of cells dropped.
+    ///
+    /// Affected [`View`]s are updated once after all keys have been removed.
+    async fn despawn_multi<I>(&self, keys: I) -> usize
+    where
+        I: Iterator<Item = Key> + Send + Sync,
+    {
+        let despawned = self.despawn_cells(keys.collect()).await;
+        if !despawned.type_ids.is_empty() {
+            self.update_views(&despawned.type_ids, &despawned.keys)
+                .await;
+        }
+        self.notify_all_observers().await;
+        despawned.dropped
     }
 
-    async fn get<T>(&self, key: &Key) -> Option<ReadCell<T>>
+    /// Remove each of `keys` from every column and free them without updating views or
+    /// notifying observers, which is left to the caller.
+    ///
+    /// Implemented by the `Table` derive; use [`Table::despawn_multi`] instead.
+    #[doc(hidden)]
+    async fn despawn_cells(&self, keys: Vec<Key>) -> DespawnedCells;
+
+    async fn get<'a, T>(&'a self, key: &Key) -> Result<ReadCell<'a, T>, CellError>
     where
         Self: Sized + BorrowColumn<T>,
-        T: Send + Sync,
+        T: Send + Sync + 'static,
     {
         ReadCell::new(self, key).await
     }
 
-    async fn get_mut<T>(&self, key: &Key) -> Option<WriteCell<T>>
+    /// Write-lock the `T` cell at `key`.
+    ///
+    /// Release the cell with [`WriteCell::release`]
+    /// to update views and observers for the write.
+    async fn get_mut<'a, T>(&'a self, key: &Key) -> Result<WriteCell<'a, T>, CellError>
     where
         Self: Sized + BorrowColumn<T>,
-        T: Send + Sync,
+        T: Send + Sync + 'static,
     {
         WriteCell::new(self, key).await
     }
 
+    /// Blocking counterpart of [`Table::get`]. See [`block_on`](crate::block_on).
+    #[track_caller]
+    fn get_blocking<T>(&self, key: &Key) -> Result<ReadCell<'_, T>, CellError>
+    where
+        Self: Sized + BorrowColumn<T>,
+        T: Send + Sync + 'static,
+    {
+        ReadCell::blocking(self, key)
+    }
+
+    /// Blocking counterpart of [`Table::get_mut`]. See [`block_on`](crate::block_on).
+    #[track_caller]
+    fn get_mut_blocking<T>(&self, key: &Key) -> Result<WriteCell<'_, T>, CellError>
+    where
+        Self: Sized + Sync + BorrowColumn<T>,
+        T: Send + Sync + 'static,
+    {
+        WriteCell::blocking(self, key)
+    }
+
+    /// Read the cell at `key` if it is free, returning [`CellError::Locked`] instead of waiting.
+    #[track_caller]
+    fn try_get<T>(&self, key: &Key) -> Result<ReadCell<'_, T>, CellError>
+    where
+        Self: Sized + BorrowColumn<T>,
+        T: Send + Sync + 'static,
+    {
+        ReadCell::try_new(self, key)
+    }
+
+    /// Write the cell at `key` if it is free, returning [`CellError::Locked`] instead of waiting.
+    #[track_caller]
+    fn try_get_mut<T>(&self, key: &Key) -> Result<WriteCell<'_, T>, CellError>
+    where
+        Self: Sized + Sync + BorrowColumn<T>,
+        T: Send + Sync + 'static,
+    {
+        WriteCell::try_new(self, key)
+    }
+
     async fn keys<T>(&self) -> FromIter<std::collections::btree_set::IntoIter<Key>>
     where
         Self: Sized + BorrowColumn<T>,
-        T: Send + Sync,
+        T: Send + Sync + 'static,
     {
-        async_std::stream::from_iter(
+        crate::runtime::from_iter(
             ReadColumn::new(self)
                 .await
                 .keys()
                 .copied()
-                .collect::<BTreeSet<_>>()
-                .into_iter(),
+                .collect::<BTreeSet<_>>(),
         )
     }
 
-    fn next_key(&self) -> Key
+    /// Return a stream of keys whose `T` cell equals `value`.
+    ///
+    /// Uses the column's [`CellIndex`] if it is marked `#[index]`, and scans every cell otherwise.
+    async fn find<T>(&self, value: &T) -> FromIter<std::collections::btree_set::IntoIter<Key>>
     where
-        Self: Borrow<AtomicUsize>,
+        Self: Sized + BorrowColumn<T>,
+        T: PartialEq + Send + Sync + 'static,
     {
-        let next = self.borrow();
-        let next = next.fetch_add(1, Ordering::Relaxed);
-        next.into()
+        let column = ReadColumn::new(self).await;
+
+        let keys = match column.index().find(value) {
+            Some(keys) => keys,
+            None => {
+                let mut keys = column.keys().copied().collect::<BTreeSet<_>>();
+                for key in keys.clone() {
+                    if column.with_cell(&key, |cell| cell == value).await != Some(true) {
+                        keys.remove(&key);
+                    }
+                }
+                keys
+            }
+        };
+
+        crate::runtime::from_iter(keys)
     }
 
-    /// Check each held [`View`]'s type and update it if its valid keys have changed.
-    async fn update_views(&self, type_ids: &[TypeId]);
+    /// Return a stream of keys whose `T` cell lies within `range`.
+    ///
+    /// Uses the column's [`CellIndex`] if it is marked `#[index]`, and scans every cell otherwise,
+    /// or if it is marked `#[index(hash)]`.
+    async fn range<T, R>(&self, range: R) -> FromIter<std::collections::btree_set::IntoIter<Key>>
+    where
+        Self: Sized + BorrowColumn<T>,
+        T: PartialOrd + Send + Sync + 'static,
+        R: RangeBounds<T> + Send + Sync,
+    {
+        let column = ReadColumn::new(self).await;
+
+        let bounds: (Bound<&T>, Bound<&T>) = (range.start_bound(), range.end_bound());
+        let keys = match column.index().range(bounds) {
+            Some(keys) => keys,
+            None => {
+                let mut keys = column.keys().copied().collect::<BTreeSet<_>>();
+                for key in keys.clone() {
+                    if column.with_cell(&key, |cell| range.contains(cell)).await != Some(true) {
+                        keys.remove(&key);
+                    }
+                }
+                keys
+            }
+        };
+
+        crate::runtime::from_iter(keys)
+    }
+
+    /// Return a stream of keys whose `T` cell was inserted after `tick`.
+    fn added<T>(&self, tick: Tick) -> FromIter<std::collections::btree_set::IntoIter<Key>>
+    where
+        Self: Sized + BorrowColumn<T>,
+    {
+        let column: &Column<T> = self.borrow();
+        crate::runtime::from_iter(column.changes().added_since(tick))
+    }
+
+    /// Return a stream of keys whose `T` cell was written after `tick`.
+    fn changed<T>(&self, tick: Tick) -> FromIter<std::collections::btree_set::IntoIter<Key>>
+    where
+        Self: Sized + BorrowColumn<T>,
+    {
+        let column: &Column<T> = self.borrow();
+        crate::runtime::from_iter(column.changes().changed_since(tick))
+    }
+
+    /// Return a stream of keys whose `T` cell was removed after `tick`.
+    ///
+    /// See [`Table::prune_changes`].
+    fn removed<T>(&self, tick: Tick) -> FromIter<std::collections::btree_set::IntoIter<Key>>
+    where
+        Self: Sized + BorrowColumn<T>,
+    {
+        let column: &Column<T> = self.borrow();
+        crate::runtime::from_iter(column.changes().removed_since(tick))
+    }
+
+    /// Forget all changes to the `T` column recorded at or before `tick`.
+    ///
+    /// Removals are otherwise kept for as long as the column lives,
+    /// so call this with the oldest tick any reader still needs.
+    fn prune_changes<T>(&self, tick: Tick)
+    where
+        Self: Sized + BorrowColumn<T>,
+    {
+        let column: &Column<T> = self.borrow();
+        column.changes().prune(tick);
+    }
+
+    /// Register `callback` to be awaited for each of `events` on the `T` column.
+    ///
+    /// See [`Observers::observe`](crate::Observers::observe).
+    fn observe<T, E, F, Fut>(&self, events: E, callback: F)
+    where
+        Self: Sized + BorrowColumn<T>,
+        E: Into<ObserveEvents>,
+        F: Fn(ObserveEvent, Key) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let column: &Column<T> = self.borrow();
+        column.observers().observe(events, callback);
+    }
+
+    /// Return a bounded channel receiving each of `events` on the `T` column.
+    ///
+    /// See [`Observers::observe_channel`](crate::Observers::observe_channel).
+    fn observe_channel<T, E>(&self, events: E, capacity: usize) -> Receiver<(ObserveEvent, Key)>
+    where
+        Self: Sized + BorrowColumn<T>,
+        E: Into<ObserveEvents>,
+    {
+        let column: &Column<T> = self.borrow();
+        column.observers().observe_channel(events, capacity)
+    }
+
+    /// Register `callback` to be awaited as keys enter or leave the [`View`] of `R`.
+    fn observe_view<R, E, F, Fut>(&self, events: E, callback: F)
+    where
+        Self: Sized + BorrowView<R>,
+        E: Into<ObserveEvents>,
+        F: Fn(ObserveEvent, Key) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let view: &View<R> = self.borrow();
+        view.observers().observe(events, callback);
+    }
+
+    /// Return a bounded channel receiving keys as they enter or leave the [`View`] of `R`.
+    fn observe_view_channel<R, E>(
+        &self,
+        events: E,
+        capacity: usize,
+    ) -> Receiver<(ObserveEvent, Key)>
+    where
+        Self: Sized + BorrowView<R>,
+        E: Into<ObserveEvents>,
+    {
+        let view: &View<R> = self.borrow();
+        view.observers().observe_channel(events, capacity)
+    }
+
+    /// Update views for the `T` cells written in place since the last call,
+    /// then deliver the events recorded on the `T` column to its observers.
+    ///
+    /// Called by this trait's write methods once their guards are released,
+    /// and by [`WriteCell::release`]; call it after writing through [`WriteColumn`]
+    /// or dropping a [`WriteCell`] directly.
+    async fn notify_observers<T>(&self)
+    where
+        Self: Sized + BorrowColumn<T>,
+        T: Send + Sync + 'static,
+    {
+        let column: &Column<T> = self.borrow();
+        let written = column.changes().take_unviewed();
+        if !written.is_empty() {
+            self.update_views(&[std::any::TypeId::of::<T>()], &written)
+                .await;
+        }
+        column.observers().notify().await;
+    }
+
+    /// Deliver the events recorded on every column to its observers.
+    async fn notify_all_observers(&self);
+
+    /// Write-lock the column types in `C` and pass their guards to `f`.
+    ///
+    /// If `f` returns `Ok`, its changes are committed, [`Table::update_views`] is called once
+    /// with the columns and keys that were touched, and each column's observers are notified.
+    /// If it returns `Err` or panics, every change is rolled back.
+    async fn transaction<'a, C, F, R, E>(&'a self, f: F) -> Result<R, E>
+    where
+        Self: Sized,
+        C: TransactionColumns<'a, Self>,
+        F: FnOnce(&mut C::Guards) -> Result<R, E> + Send,
+        R: Send,
+        E: Send,
+    {
+        let mut guards = C::lock(self).await;
+        match f(&mut guards) {
+            Ok(result) => {
+                let (type_ids, keys) = C::commit(guards);
+                if !type_ids.is_empty() {
+                    self.update_views(&type_ids, &keys).await;
+                    C::notify_observers(self).await;
+                }
+                Ok(result)
+            }
+            Err(e) => {
+                C::rollback(guards);
+                Err(e)
+            }
+        }
+    }
+
+    /// Attach `child` to `parent`, detaching it from any previous parent.
+    ///
+    /// The [`Parent`] and [`Children`] columns are updated in one transaction,
+    /// and despawning `parent` will despawn `child` along with it.
+    async fn set_parent(&self, child: Key, parent: Key) -> Result<(), HierarchyError>
+    where
+        Self: Sized + BorrowColumn<Parent> + BorrowColumn<Children> + Send + Sync,
+    {
+        crate::hierarchy::set_parent(self, child, parent).await
+    }
+
+    /// Detach `child` from its parent, returning the parent if it had one.
+    async fn remove_parent(&self, child: Key) -> Option<Key>
+    where
+        Self: Sized + BorrowColumn<Parent> + BorrowColumn<Children> + Send + Sync,
+    {
+        crate::hierarchy::remove_parent(self, child).await
+    }
+
+    /// Return a stream of the ancestors of `key`, nearest first.
+    async fn ancestors(&self, key: Key) -> FromIter<std::vec::IntoIter<Key>>
+    where
+        Self: Sized + BorrowColumn<Parent>,
+    {
+        crate::runtime::from_iter(crate::hierarchy::ancestors(self, key).await)
+    }
+
+    /// Return the nearest ancestor of `key` with a `T` cell.
+    async fn ancestor_with<T>(&self, key: Key) -> Option<Key>
+    where
+        Self: Sized + BorrowColumn<Parent> + BorrowColumn<T>,
+        T: Send + Sync + 'static,
+    {
+        let ancestors = crate::hierarchy::ancestors(self, key).await;
+        let column = ReadColumn::<T>::new(self).await;
+        ancestors
+            .into_iter()
+            .find(|ancestor| column.contains_key(ancestor))
+    }
+
+    /// Return a stream of the descendants of `key` in depth-first order.
+    async fn descendants(&self, key: Key) -> FromIter<std::vec::IntoIter<Key>>
+    where
+        Self: Sized + BorrowColumn<Children>,
+    {
+        crate::runtime::from_iter(crate::hierarchy::descendants(self, key).await)
+    }
+
+    /// Stream a row of `R` for each descendant of `key` that has its required cells,
+    /// in depth-first order.
+    async fn query_descendants<'a, R>(&'a self, key: Key) -> BoxStream<'a, R>
+    where
+        Self: Sized + BorrowColumn<Children> + Sync,
+        R: Row<'a, Self>,
+    {
+        R::query_keys(self, crate::hierarchy::descendants(self, key).await).await
+    }
+
+    /// Capture the cells of every column accepted by `filter`, along with the key allocator.
+    ///
+    /// Only columns whose type is `Clone` can be captured; others are skipped.
+    /// A column that hasn't been written since its last snapshot is shared rather than copied.
+    /// Columns are locked one at a time, so concurrent writes may land between them.
+    async fn snapshot<F>(&self, filter: F) -> Snapshot
+    where
+        Self: Sized + Sync,
+        F: Fn(&ColumnInfo<Self>) -> bool + Send,
+    {
+        let mut snapshot = Snapshot::default();
+
+        if let Some(keys) = self.key_allocator() {
+            snapshot.set_keys(keys.snapshot());
+        }
+
+        for column in Self::column_registry().iter() {
+            if let Some(fns) = column.snapshot.filter(|_| filter(column)) {
+                snapshot.insert(column.type_id, (fns.snapshot)(self).await);
+            }
+        }
+
+        snapshot
+    }
+
+    /// Return every column captured in `snapshot`, and the key allocator, to their captured state.
+    ///
+    /// Columns not in `snapshot` are left untouched.
+    /// Cells are removed and reinserted through [`WriteColumn`], so the restore is visible to
+    /// change queries, views and observers like any other write.
+    async fn restore(&self, snapshot: Snapshot)
+    where
+        Self: Sized + Sync,
+    {
+        if let (Some(keys), Some(slots)) = (self.key_allocator(), snapshot.keys()) {
+            keys.restore(slots.clone());
+        }
+
+        let registry = Self::column_registry();
+
+        let mut type_ids = vec![];
+        let mut keys = BTreeSet::new();
+        let mut notify = vec![];
+        for column in registry.iter() {
+            if let (Some(fns), Some(cells)) = (column.snapshot, snapshot.get(column.type_id)) {
+                keys.extend((fns.restore)(self, cells).await);
+                type_ids.push(column.type_id);
+                notify.push(fns.notify);
+            }
+        }
+
+        let keys = keys.into_iter().collect::<Vec<_>>();
+        self.update_views(&type_ids, &keys).await;
+
+        for notify in notify {
+            notify(self).await;
+        }
+    }
+
+    /// Record every subsequent write to this table's serializable columns,
+    /// and every key it allocates or frees, in `journal`.
+    ///
+    /// Replay the journal with [`Table::replay`] before attaching it,
+    /// or the replayed writes will be journaled a second time.
+    #[cfg(feature = "journal")]
+    fn attach_journal(&self, journal: std::sync::Arc<crate::journal::Journal>)
+    where
+        Self: Sized,
+    {
+        crate::journal::attach(self, journal)
+    }
+
+    /// Apply every record in `journal` to this table, returning the number of records applied.
+    ///
+    /// Views are rebuilt and observers notified once every record has been applied.
+    #[cfg(feature = "journal")]
+    async fn replay(
+        &self,
+        journal: &crate::journal::Journal,
+    ) -> Result<usize, crate::journal::JournalError>
+    where
+        Self: Sized + Sync,
+    {
+        crate::journal::replay(self, journal).await
+    }
+
+    /// Replace the history in `journal` with a single snapshot of this table's journaled state.
+    ///
+    /// Writes made during compaction are kept, and may be journaled from other tasks meanwhile.
+    #[cfg(feature = "journal")]
+    async fn compact_journal(
+        &self,
+        journal: &crate::journal::Journal,
+    ) -> Result<(), crate::journal::JournalError>
+    where
+        Self: Sized + Sync,
+    {
+        crate::journal::compact(self, journal).await
+    }
+
+    /// Return this table's [`KeyAllocator`], if it has one.
+    fn key_allocator(&self) -> Option<&KeyAllocator>;
+
+    /// Allocate a fresh key from this table's [`KeyAllocator`].
+    fn next_key(&self) -> Key {
+        self.key_allocator()
+            .expect("Table has no KeyAllocator field.")
+            .alloc()
+    }
+
+    /// Free `key` so its slot can be reused, returning false if it was not alive.
+    fn free_key(&self, key: &Key) -> bool {
+        self.key_allocator()
+            .expect("Table has no KeyAllocator field.")
+            .free(key)
+    }
+
+    /// Return true if `key` was allocated by this table and has not been freed.
+    fn is_alive(&self, key: &Key) -> bool {
+        self.key_allocator()
+            .map(|keys| keys.is_alive(key))
+            .unwrap_or_default()
+    }
+
+    /// Return true if `key` has been freed since it was allocated by this table.
+    fn is_stale(&self, key: &Key) -> bool {
+        self.key_allocator()
+            .map(|keys| keys.is_stale(key))
+            .unwrap_or_default()
+    }
+
+    /// Return the [`ColumnStorage`] the column holding `type_id` should use.
+    fn column_storage(&self, _type_id: TypeId) -> ColumnStorage {
+        ColumnStorage::Map
+    }
+
+    /// Return a new, empty [`CellIndex`] for the `T` column, if it should be indexed.
+    ///
+    /// Called the first time the column is write-locked, after which the index is
+    /// maintained by every write made through the deebs guards.
+    fn column_index<T>(&self) -> Option<Box<dyn CellIndex<T>>>
+    where
+        T: 'static,
+    {
+        None
+    }
+
+    /// Check each held [`View`]'s type and, if it depends on one of `type_ids`,
+    /// re-evaluate whether each of `keys` belongs to it.
+    async fn update_views(&self, type_ids: &[TypeId], keys: &[Key]);
+
+    /// Recompute every held [`View`] from scratch.
+    ///
+    /// Deserialized tables start with empty views, and must be rebuilt with this before use.
+    /// [`Table::load`] does so.
+    async fn rebuild_views(&self);
+
+    /// Deserialize a table from `deserializer`, then rebuild its views, which aren't serialized.
+    #[cfg(feature = "serde")]
+    async fn load<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        Self: Sized + Send + Sync + serde::Deserialize<'de>,
+        D: serde::Deserializer<'de> + Send,
+    {
+        let table = Self::deserialize(deserializer)?;
+        table.rebuild_views().await;
+        Ok(table)
+    }
+
+    /// Describe every column of this table for runtime reflection.
+    fn column_registry() -> ColumnRegistry<Self>
+    where
+        Self: Sized;
 }
@@ -0,0 +1,52 @@
+use std::{
+    fmt::Display,
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static CHANGE_TICK: AtomicUsize = AtomicUsize::new(0);
+
+/// A point in the global change timeline, used to query column changes.
+///
+/// Every recorded change claims a fresh tick, so a system that stores [`Tick::now`]
+/// after running will see exactly the changes made since on its next run.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tick(usize);
+
+impl Tick {
+    /// Return the most recently claimed tick.
+    pub fn now() -> Self {
+        Tick(CHANGE_TICK.load(Ordering::Acquire))
+    }
+
+    /// Claim a new tick for a change that is about to be recorded.
+    pub(crate) fn next() -> Self {
+        Tick(CHANGE_TICK.fetch_add(1, Ordering::AcqRel) + 1)
+    }
+}
+
+impl From<usize> for Tick {
+    fn from(tick: usize) -> Self {
+        Tick(tick)
+    }
+}
+
+impl From<Tick> for usize {
+    fn from(tick: Tick) -> Self {
+        tick.0
+    }
+}
+
+impl Display for Tick {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Deref for Tick {
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
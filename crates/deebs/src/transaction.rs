@@ -0,0 +1,120 @@
+use std::{any::TypeId, collections::BTreeSet};
+
+use async_trait::async_trait;
+
+use crate::{lock_order, BorrowColumn, Key, Table, TransactionColumn};
+
+/// A set of column types that can be write-locked together for a [`Table::transaction`].
+///
+/// Implemented for tuples of up to 16 column types.
+/// Locks are always acquired in ascending [`TypeId`] order,
+/// so transactions over overlapping column sets cannot deadlock one another.
+#[async_trait]
+pub trait TransactionColumns<'a, Tbl> {
+    /// Tuple of [`TransactionColumn`] guards handed to the transaction closure.
+    type Guards: Send;
+
+    /// Return the [`TypeId`]s of this set's column types in declaration order.
+    fn type_ids() -> Vec<TypeId>;
+
+    /// Write-lock every column in this set.
+    async fn lock(db: &'a Tbl) -> Self::Guards;
+
+    /// Commit every guard's changes,
+    /// returning the [`TypeId`]s of the columns and the keys that were touched.
+    fn commit(guards: Self::Guards) -> (Vec<TypeId>, Vec<Key>);
+
+    /// Roll back every guard's changes.
+    fn rollback(guards: Self::Guards);
+
+    /// Deliver the events recorded on every column in this set to its observers.
+    async fn notify_observers(db: &'a Tbl);
+}
+
+/// Return the indices of `type_ids` sorted into canonical lock order,
+/// asserting that no column type is declared twice.
+fn transaction_lock_order(type_ids: &[TypeId]) -> Vec<usize> {
+    let order = lock_order(type_ids);
+
+    for pair in order.windows(2) {
+        assert!(
+            type_ids[pair[0]] != type_ids[pair[1]],
+            "Transaction declares the same column type more than once."
+        );
+    }
+
+    order
+}
+
+macro_rules! impl_transaction_columns {
+    ($($ty:ident $guard:ident $index:tt),*) => {
+        #[async_trait]
+        impl<'a, Tbl, $($ty,)*> TransactionColumns<'a, Tbl> for ($($ty,)*)
+        where
+            Tbl: Table + $(BorrowColumn<$ty> +)* Send + Sync,
+            $(
+                $ty: Send + Sync + 'static,
+            )*
+        {
+            type Guards = ($(TransactionColumn<'a, $ty>,)*);
+
+            fn type_ids() -> Vec<TypeId> {
+                vec![$(TypeId::of::<$ty>(),)*]
+            }
+
+            async fn lock(db: &'a Tbl) -> Self::Guards {
+                $(
+                    let mut $guard = None;
+                )*
+
+                for i in transaction_lock_order(&[$(TypeId::of::<$ty>(),)*]) {
+                    match i {
+                        $(
+                            $index => $guard = Some(TransactionColumn::<$ty>::new(db).await),
+                        )*
+                        _ => unreachable!(),
+                    }
+                }
+
+                ($($guard.unwrap(),)*)
+            }
+
+            fn commit(guards: Self::Guards) -> (Vec<TypeId>, Vec<Key>) {
+                let ($($guard,)*) = guards;
+                let mut keys = BTreeSet::new();
+                let type_ids = vec![$($guard.commit(&mut keys),)*].into_iter().flatten().collect();
+                (type_ids, keys.into_iter().collect())
+            }
+
+            fn rollback(guards: Self::Guards) {
+                let ($($guard,)*) = guards;
+                $(
+                    $guard.rollback();
+                )*
+            }
+
+            async fn notify_observers(db: &'a Tbl) {
+                $(
+                    db.notify_observers::<$ty>().await;
+                )*
+            }
+        }
+    };
+}
+
+impl_transaction_columns!(A a 0);
+impl_transaction_columns!(A a 0, B b 1);
+impl_transaction_columns!(A a 0, B b 1, C c 2);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8, J j 9);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8, J j 9, K k 10);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8, J j 9, K k 10, L l 11);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8, J j 9, K k 10, L l 11, M m 12);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8, J j 9, K k 10, L l 11, M m 12, N n 13);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8, J j 9, K k 10, L l 11, M m 12, N n 13, O o 14);
+impl_transaction_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7, I i 8, J j 9, K k 10, L l 11, M m 12, N n 13, O o 14, P p 15);
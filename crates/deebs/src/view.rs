@@ -1,14 +1,19 @@
-use crate::{CommonKeys, Table};
-use async_std::sync::RwLock;
+use crate::{CommonKeys, ObserveEvent, Observers, Table, Tick};
+use crate::runtime::RwLock;
 use futures::StreamExt;
-use std::{borrow::Borrow, collections::BTreeSet, marker::PhantomData};
+use std::{borrow::Borrow, collections::BTreeSet, marker::PhantomData, sync::Mutex};
 
 use crate::Key;
 
 /// A thread-safe set of valid keys for a given [`Row`] type.
+///
+/// [`Added`](crate::Added) and [`Changed`](crate::Changed) fields of `R` are evaluated against
+/// the view's tick, which starts at the default [`Tick`] and is moved on with [`View::advance`].
 #[derive(Debug)]
 pub struct View<R> {
     pub keys: RwLock<BTreeSet<Key>>,
+    since: Mutex<Tick>,
+    observers: Observers,
     _phantom: PhantomData<R>
 }
 
@@ -19,6 +24,8 @@ impl<R> Default for View<R> {
     fn default() -> Self {
         View {
             keys: Default::default(),
+            since: Default::default(),
+            observers: Default::default(),
             _phantom: Default::default()
         }
     }
@@ -31,14 +38,80 @@ impl<R> View<R> {
         std::collections::BTreeSet<Key>: std::iter::Extend<<T as Table>::Key>,
         R: CommonKeys<T>,
     {
-        let mut keys = self.keys.write().await;
-        *keys = R::common_keys(db).await.collect().await;
+        let valid: BTreeSet<Key> = R::common_keys_since(db, self.since()).await.collect().await;
+
+        {
+            let mut keys = self.keys.write().await;
+            for key in keys.difference(&valid) {
+                self.observers.record(ObserveEvent::OnRemove, *key);
+            }
+            for key in valid.difference(&*keys) {
+                self.observers.record(ObserveEvent::OnInsert, *key);
+            }
+            *keys = valid;
+        }
+
+        self.observers.notify().await;
+    }
+
+    /// Return the tick this view's [`Added`](crate::Added) and [`Changed`](crate::Changed) fields
+    /// are evaluated against.
+    pub fn since(&self) -> Tick {
+        *self.since.lock().expect("View tick is poisoned.")
+    }
+
+    /// Evaluate this view's [`Added`](crate::Added) and [`Changed`](crate::Changed) fields
+    /// against changes made after `tick` from now on, and recompute its keys.
+    ///
+    /// A system consuming a filtered view calls this with [`Tick::now`] once it has run,
+    /// so that the keys it has seen leave the view until they change again.
+    pub async fn advance<T>(&self, db: &T, tick: Tick)
+    where
+        T: Table,
+        std::collections::BTreeSet<Key>: std::iter::Extend<<T as Table>::Key>,
+        R: CommonKeys<T>,
+    {
+        *self.since.lock().expect("View tick is poisoned.") = tick;
+        self.update(db).await;
+    }
+
+    /// Re-evaluate whether each of `keys` belongs to this view,
+    /// leaving the rest of its keys untouched.
+    pub async fn update_keys<T>(&self, db: &T, keys: &[Key])
+    where
+        T: Table,
+        R: CommonKeys<T>,
+    {
+        let valid = R::common_keys_in_since(db, keys, self.since()).await;
+
+        {
+            let mut view_keys = self.keys.write().await;
+            for key in keys {
+                if valid.contains(key) {
+                    if view_keys.insert(*key) {
+                        self.observers.record(ObserveEvent::OnInsert, *key);
+                    }
+                } else if view_keys.remove(key) {
+                    self.observers.record(ObserveEvent::OnRemove, *key);
+                }
+            }
+        }
+
+        self.observers.notify().await;
+    }
+
+    /// Return the observers registered on this view.
+    ///
+    /// Views only emit [`ObserveEvent::OnInsert`] and [`ObserveEvent::OnRemove`],
+    /// as keys enter and leave them.
+    pub fn observers(&self) -> &Observers {
+        &self.observers
     }
 
     pub async fn keys(
         &self,
-    ) -> async_std::stream::FromIter<std::collections::btree_set::IntoIter<Key>> {
-        async_std::stream::from_iter(self.keys.read().await.clone().into_iter())
+    ) -> crate::runtime::FromIter<std::collections::btree_set::IntoIter<Key>> {
+        crate::runtime::from_iter(self.keys.read().await.clone())
     }
 }
 
@@ -0,0 +1,175 @@
+//! Checks the synchronous API against the same locks used by the async one.
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::Table, CellError, Column, KeyAllocator, ReadColumn, ReadSingleton, Singleton, Table,
+    Tick, WriteColumn, WriteSingleton,
+};
+use futures::StreamExt;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable {
+    key_allocator: KeyAllocator,
+
+    counter: Singleton<usize>,
+
+    ints: Column<i32>,
+    #[dense]
+    floats: Column<f32>,
+}
+
+#[test]
+fn blocking_calls_read_and_write_cells() {
+    let table = TestTable::default();
+    let key = deebs::block_on(table.insert_auto(1));
+
+    *table.get_mut_blocking::<i32>(&key).unwrap() += 1;
+    assert_eq!(*table.get_blocking::<i32>(&key).unwrap(), 2);
+    assert_eq!(
+        table.get_blocking::<f32>(&key).unwrap_err(),
+        CellError::Missing(key)
+    );
+
+    *WriteSingleton::<usize>::blocking(&table) += 1;
+    assert_eq!(*ReadSingleton::<usize>::blocking(&table), 1);
+}
+
+#[test]
+fn try_calls_never_wait() {
+    let table = TestTable::default();
+    let key = deebs::block_on(table.insert_auto(1));
+    deebs::block_on(table.insert(key, 1.0f32));
+
+    {
+        let _ints = deebs::block_on(WriteColumn::<i32>::new(&table));
+        assert_eq!(
+            table.try_get::<i32>(&key).unwrap_err(),
+            CellError::Locked(key)
+        );
+        assert_eq!(
+            table.try_get_mut::<i32>(&key).unwrap_err(),
+            CellError::Locked(key)
+        );
+
+        // Other columns are unaffected
+        *table.try_get_mut::<f32>(&key).unwrap() = 2.0;
+        assert_eq!(*table.try_get::<f32>(&key).unwrap(), 2.0);
+    }
+    assert_eq!(*table.try_get::<i32>(&key).unwrap(), 1);
+
+    {
+        let _counter = WriteSingleton::<usize>::try_new(&table).unwrap();
+        assert!(ReadSingleton::<usize>::try_new(&table).is_none());
+        assert!(WriteSingleton::<usize>::try_new(&table).is_none());
+    }
+    assert!(ReadSingleton::<usize>::try_new(&table).is_some());
+
+    deebs::block_on(table.despawn(key));
+    assert_eq!(
+        table.try_get::<i32>(&key).unwrap_err(),
+        CellError::Stale(key)
+    );
+}
+
+#[test]
+fn cells_are_locked_individually() {
+    let table = TestTable::default();
+    let keys = deebs::block_on(table.insert_auto_multi(vec![1, 2].into_iter()));
+    for key in keys.iter() {
+        deebs::block_on(table.insert(*key, 1.0f32));
+    }
+
+    // Both storages lock the written cell for as long as it is held, and only that cell
+    {
+        let _int = table.try_get_mut::<i32>(&keys[0]).unwrap();
+        let _float = table.try_get_mut::<f32>(&keys[0]).unwrap();
+        assert_eq!(
+            table.try_get::<i32>(&keys[0]).unwrap_err(),
+            CellError::Locked(keys[0])
+        );
+        assert_eq!(
+            table.try_get::<f32>(&keys[0]).unwrap_err(),
+            CellError::Locked(keys[0])
+        );
+        assert_eq!(
+            table.try_get_mut::<f32>(&keys[0]).unwrap_err(),
+            CellError::Locked(keys[0])
+        );
+
+        *table.try_get_mut::<f32>(&keys[1]).unwrap() = 2.0;
+        assert_eq!(*table.try_get::<i32>(&keys[1]).unwrap(), 2);
+    }
+
+    // Read cells share their lock with other readers only
+    {
+        let _float = table.try_get::<f32>(&keys[0]).unwrap();
+        assert_eq!(*table.try_get::<f32>(&keys[0]).unwrap(), 1.0);
+        assert_eq!(
+            table.try_get_mut::<f32>(&keys[0]).unwrap_err(),
+            CellError::Locked(keys[0])
+        );
+    }
+
+    *table.try_get_mut::<f32>(&keys[0]).unwrap() = 3.0;
+    let mut floats = deebs::block_on(WriteColumn::<f32>::new(&table));
+    let (_, values) = floats.slices().unwrap();
+    assert_eq!(values, &[3.0, 2.0]);
+}
+
+#[test]
+fn read_slices_lock_every_cell() {
+    let table = TestTable::default();
+    let keys = deebs::block_on(table.insert_auto_multi(vec![1.0f32, 2.0].into_iter()));
+
+    let floats = ReadColumn::<f32>::blocking(&table);
+    {
+        let slices = deebs::block_on(floats.slices()).unwrap();
+        assert_eq!(slices.keys(), &keys[..]);
+        assert_eq!(slices.values(), &[1.0, 2.0]);
+
+        // Readers share the cells, but writers wait for the slices to be dropped
+        assert_eq!(*table.try_get::<f32>(&keys[1]).unwrap(), 2.0);
+        assert_eq!(
+            table.try_get_mut::<f32>(&keys[1]).unwrap_err(),
+            CellError::Locked(keys[1])
+        );
+    }
+    *table.try_get_mut::<f32>(&keys[1]).unwrap() = 3.0;
+
+    assert_eq!(
+        deebs::block_on(floats.slices()).unwrap().values(),
+        &[1.0, 3.0]
+    );
+    drop(floats);
+
+    // Map columns have no slices
+    let ints = ReadColumn::<i32>::blocking(&table);
+    assert!(deebs::block_on(ints.slices()).is_none());
+}
+
+#[test]
+fn try_writes_are_tracked() {
+    let table = TestTable::default();
+    let key = deebs::block_on(table.insert_auto(1));
+    let tick = Tick::now();
+
+    *table.try_get_mut::<i32>(&key).unwrap() = 2;
+    assert_eq!(
+        deebs::block_on(table.changed::<i32>(tick).collect::<Vec<_>>()),
+        vec![key]
+    );
+}
+
+#[cfg(all(
+    debug_assertions,
+    any(deebs_backend = "async-std", deebs_backend = "tokio")
+))]
+#[test]
+#[should_panic(expected = "Blocking table access from within an executor task")]
+fn blocking_inside_a_task_panics() {
+    let table = TestTable::default();
+    deebs::runtime::run(async {
+        let key = table.insert_auto(1).await;
+        let _ = table.get_blocking::<i32>(&key);
+    });
+}
@@ -0,0 +1,169 @@
+//! Checks that columns track added, changed and removed keys, and that filters and views follow them.
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Added, Changed, Column, KeyAllocator, ReadCell, Row, Table, Tick, View,
+};
+use futures::StreamExt;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Position(f32);
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct AddedRow<'a> {
+    position: ReadCell<'a, Position>,
+    added: Added<Position>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct ChangedRow<'a> {
+    position: ReadCell<'a, Position>,
+    changed: Changed<Position>,
+}
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    key_allocator: KeyAllocator,
+
+    positions: Column<Position>,
+
+    changed_rows: View<ChangedRow<'a>>,
+}
+
+#[test]
+fn columns_record_changes_since_a_tick() {
+    run(async {
+        let table = TestTable::default();
+        let first = table.insert_auto(Position(0.0)).await;
+
+        let tick = Tick::now();
+        let second = table.insert_auto(Position(1.0)).await;
+        *table.get_mut::<Position>(&first).await.unwrap() = Position(2.0);
+        table.notify_observers::<Position>().await;
+
+        assert_eq!(
+            table.added::<Position>(tick).collect::<Vec<_>>().await,
+            vec![second]
+        );
+        assert_eq!(
+            table.changed::<Position>(tick).collect::<Vec<_>>().await,
+            vec![first]
+        );
+
+        let tick = Tick::now();
+        table.remove::<Position>(first).await;
+        assert_eq!(
+            table.removed::<Position>(tick).collect::<Vec<_>>().await,
+            vec![first]
+        );
+
+        // Removing a cell forgets that it was added or changed
+        assert!(table
+            .changed::<Position>(Tick::default())
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+
+        // Removals are kept until pruned
+        table.prune_changes::<Position>(Tick::now());
+        assert!(table
+            .removed::<Position>(Tick::default())
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+    });
+}
+
+#[test]
+fn filters_are_evaluated_against_the_callers_tick() {
+    run(async {
+        let table = TestTable::default();
+        let first = table.insert_auto(Position(0.0)).await;
+
+        let tick = Tick::now();
+        table.insert_auto(Position(1.0)).await;
+        *table.get_mut::<Position>(&first).await.unwrap() = Position(2.0);
+
+        let added = AddedRow::query_since(&table, tick)
+            .await
+            .map(|row| *row.position)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(added, vec![Position(1.0)]);
+
+        let changed = ChangedRow::query_since(&table, tick)
+            .await
+            .map(|row| *row.position)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(changed, vec![Position(2.0)]);
+
+        // Nothing has happened since now
+        assert_eq!(
+            AddedRow::query_since(&table, Tick::now())
+                .await
+                .collect::<Vec<_>>()
+                .await
+                .len(),
+            0
+        );
+
+        // The default tick sees every change that hasn't been pruned
+        assert_eq!(
+            AddedRow::query(&table)
+                .await
+                .collect::<Vec<_>>()
+                .await
+                .len(),
+            2
+        );
+    });
+}
+
+#[test]
+fn filtered_views_follow_cell_writes() {
+    run(async {
+        let table = TestTable::default();
+        let first = table.insert_auto(Position(0.0)).await;
+        let second = table.insert_auto(Position(1.0)).await;
+
+        table.changed_rows.advance(&table, Tick::now()).await;
+        assert!(table
+            .changed_rows
+            .keys()
+            .await
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+
+        // Writes made in place reach views once observers are notified
+        *table.get_mut::<Position>(&second).await.unwrap() = Position(2.0);
+        table.notify_observers::<Position>().await;
+        assert_eq!(
+            table.changed_rows.keys().await.collect::<Vec<_>>().await,
+            vec![second]
+        );
+
+        // Advancing the view drops the changes it has seen
+        table.changed_rows.advance(&table, Tick::now()).await;
+        assert!(table
+            .changed_rows
+            .keys()
+            .await
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+
+        *table.get_mut::<Position>(&first).await.unwrap() = Position(3.0);
+        table.notify_observers::<Position>().await;
+        assert_eq!(
+            table.changed_rows.keys().await.collect::<Vec<_>>().await,
+            vec![first]
+        );
+    });
+}
@@ -0,0 +1,127 @@
+//! Checks that queued commands are applied in order when flushed.
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Column, Commands, CommonKeys, KeyAllocator, ReadCell, Row, Table, View,
+};
+use futures::StreamExt;
+
+use deebs::ObserveEvent::*;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    key_allocator: KeyAllocator,
+
+    ints: Column<i32>,
+    floats: Column<f32>,
+
+    int_float_view: View<IntFloatRow<'a>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct IntFloatRow<'a> {
+    int: ReadCell<'a, i32>,
+    float: ReadCell<'a, f32>,
+}
+
+#[test]
+fn commands_queued_while_iterating_apply_at_flush() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table.insert_auto_multi(0..6).await;
+        table
+            .insert_multi(keys.iter().map(|key| (*key, 0.0f32)))
+            .await;
+
+        let receiver = table.observe_view_channel::<IntFloatRow, _>(OnRemove, 8);
+        let commands = Commands::default();
+
+        let mut common_keys = IntFloatRow::common_keys(&table).await;
+        while let Some(key) = common_keys.next().await {
+            let row = IntFloatRow::new(&table, &key).await;
+            if *row.int == 3 {
+                commands.despawn(key);
+            } else if *row.int % 2 == 0 {
+                commands.remove::<f32>(key);
+            }
+        }
+
+        assert!(receiver.is_empty());
+        assert_eq!(commands.flush(&table).await, 4);
+        assert!(commands.is_empty());
+
+        let remaining = IntFloatRow::query(&table)
+            .await
+            .map(|row| *row.int)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(remaining, vec![1, 5]);
+        assert!(table.get::<i32>(&keys[3]).await.is_err());
+        assert!(!table.is_alive(&keys[3]));
+        assert_eq!(receiver.len(), 4);
+    });
+}
+
+#[test]
+fn commands_apply_in_queued_order() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table.insert_auto_multi(0..2).await;
+
+        let commands = Commands::default();
+        commands.insert(keys[0], 1.0f32);
+        commands.remove::<f32>(keys[0]);
+        commands.remove::<f32>(keys[1]);
+        commands.insert(keys[1], 2.0f32);
+        assert_eq!(commands.len(), 4);
+
+        // Nothing is written until the queue is flushed
+        assert!(table.get::<f32>(&keys[1]).await.is_err());
+
+        assert_eq!(commands.flush(&table).await, 4);
+        assert!(table.get::<f32>(&keys[0]).await.is_err());
+        assert_eq!(*table.get::<f32>(&keys[1]).await.unwrap(), 2.0);
+        assert_eq!(
+            table.int_float_view.keys().await.collect::<Vec<_>>().await,
+            vec![keys[1]]
+        );
+    });
+}
+
+#[test]
+fn commands_skip_keys_freed_before_they_apply() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table.insert_auto_multi(0..2).await;
+
+        // A key despawned outside of the queue, and one despawned earlier in it
+        let freed = keys[0];
+        table.despawn(freed).await;
+
+        let commands = Commands::default();
+        commands.insert(freed, 1.0f32);
+        commands.despawn(keys[1]);
+        commands.insert(keys[1], 2.0f32);
+        commands.remove::<i32>(keys[1]);
+        commands.despawn(keys[1]);
+
+        // Reusing the freed key's slot leaves its commands stale rather than redirected
+        let reused = table.insert_auto(3).await;
+        assert_eq!(reused.index(), freed.index());
+
+        assert_eq!(commands.flush(&table).await, 1);
+        assert!(table.get::<f32>(&reused).await.is_err());
+        assert!(table.get::<f32>(&keys[1]).await.is_err());
+        assert!(!table.is_alive(&keys[1]));
+        assert!(table
+            .int_float_view
+            .keys()
+            .await
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+    });
+}
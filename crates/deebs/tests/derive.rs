@@ -0,0 +1,66 @@
+//! Checks that derives accept qualified paths and boxed views.
+
+use std::ops::Deref;
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Map, Row, Table},
+    runtime::run,
+    Map, ReadCell, Row, Table, ToStringMapper,
+};
+use futures::StreamExt;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable {
+    key_allocator: deebs::KeyAllocator,
+
+    ints: deebs::Column<i32>,
+    names: deebs::Column<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys, Map)]
+struct QualifiedRow<'a> {
+    int: deebs::ReadCell<'a, i32>,
+    name: Option<Box<deebs::WriteCell<'a, String>>>,
+    with_name: deebs::With<String>,
+}
+
+#[derive(Debug, Row, CommonKeys, Map)]
+struct BoxedRow<'a> {
+    int: Box<ReadCell<'a, i32>>,
+    name: std::option::Option<ReadCell<'a, String>>,
+}
+
+#[test]
+fn qualified_and_boxed_views_are_queried() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table.insert_auto_multi(vec![1, 2].into_iter()).await;
+        table.insert(keys[0], String::from("one")).await;
+
+        let rows = QualifiedRow::query(&table).await.collect::<Vec<_>>().await;
+        assert_eq!(rows.len(), 1);
+        for mut row in rows {
+            assert_eq!(*row.int, 1);
+            row.name.as_mut().unwrap().push('!');
+        }
+
+        let rows = BoxedRow::query(&table).await.collect::<Vec<_>>().await;
+        let rows = rows
+            .iter()
+            .map(|row| {
+                <BoxedRow as Map<ToStringMapper>>::map(row)
+                    .map(|cell| cell.unwrap_or_default())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                vec![String::from("1"), String::from("one!")],
+                vec![String::from("2"), String::new()],
+            ]
+        );
+    });
+}
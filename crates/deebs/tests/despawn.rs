@@ -0,0 +1,170 @@
+//! Checks that despawning removes every cell of a key, frees it, and reports how many cells were dropped.
+
+use borrow_derive::Borrow;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::{self, run},
+    BorrowColumn, CellError, Column, Key, KeyAllocator, ReadCell, ReadColumn, Table, View,
+};
+use futures::StreamExt;
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct IntFloatRow<'a> {
+    int: ReadCell<'a, i32>,
+    float: ReadCell<'a, f32>,
+}
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    key_allocator: KeyAllocator,
+
+    ints: Column<i32>,
+    #[dense]
+    floats: Column<f32>,
+    bools: Column<bool>,
+
+    int_float_view: View<IntFloatRow<'a>>,
+}
+
+async fn view_keys(table: &TestTable<'_>) -> Vec<Key> {
+    table.int_float_view.keys().await.collect().await
+}
+
+/// Let tasks on the current executor run, then give other threads a moment to.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        std::thread::sleep(Duration::from_millis(1));
+        Poll::Pending
+    }
+}
+
+/// Despawn a full key while its `T` column is read-locked,
+/// checking that none of its other cells go missing before the lock is released.
+async fn despawn_while_locked<T>()
+where
+    TestTable<'static>: BorrowColumn<T>,
+    T: Send + Sync + 'static,
+{
+    let table = Arc::new(TestTable::default());
+    let key = table.insert_auto::<i32>(1).await;
+    table.insert::<f32>(key, 1.0).await;
+    table.insert::<bool>(key, true).await;
+
+    let column = ReadColumn::<T>::new(&*table).await;
+    let despawn = runtime::spawn({
+        let table = table.clone();
+        async move { table.despawn(key).await }
+    });
+
+    for _ in 0..20 {
+        YieldNow(false).await;
+        assert_ne!(
+            table.try_get::<i32>(&key).err(),
+            Some(CellError::Missing(key))
+        );
+        assert_ne!(
+            table.try_get::<f32>(&key).err(),
+            Some(CellError::Missing(key))
+        );
+        assert_ne!(
+            table.try_get::<bool>(&key).err(),
+            Some(CellError::Missing(key))
+        );
+    }
+
+    drop(column);
+    assert_eq!(despawn.await, 3);
+    assert!(!table.is_alive(&key));
+}
+
+#[test]
+fn despawn_counts_the_cells_a_key_held() {
+    run(async {
+        let table = TestTable::default();
+
+        let full = table.insert_auto(1).await;
+        table.insert(full, 1.0f32).await;
+        table.insert(full, true).await;
+
+        let partial = table.insert_auto(2).await;
+        table.insert(partial, 2.0f32).await;
+
+        let empty = table.next_key();
+
+        assert_eq!(view_keys(&table).await, vec![full, partial]);
+
+        assert_eq!(table.despawn(full).await, 3);
+        assert!(!table.is_alive(&full));
+        assert!(table.get::<f32>(&full).await.is_err());
+        assert_eq!(view_keys(&table).await, vec![partial]);
+
+        // Keys holding only some columns drop only those cells
+        table.remove::<i32>(partial).await;
+        assert_eq!(table.despawn(partial).await, 1);
+        assert!(!table.is_alive(&partial));
+        assert!(view_keys(&table).await.is_empty());
+
+        // Keys without cells are still freed
+        assert!(table.is_alive(&empty));
+        assert_eq!(table.despawn(empty).await, 0);
+        assert!(!table.is_alive(&empty));
+    });
+}
+
+#[test]
+fn despawn_multi_sums_counts_and_skips_stale_keys() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table.insert_auto_multi(0..4).await;
+        table.insert(keys[0], 0.0f32).await;
+        table.insert(keys[1], 1.0f32).await;
+        table.insert(keys[1], true).await;
+
+        // Free the last key and reuse its slot, leaving a stale key behind
+        let stale = keys[3];
+        assert_eq!(table.despawn(stale).await, 1);
+        let reused = table.insert_auto(4).await;
+        assert_eq!(reused.index(), stale.index());
+
+        let dropped = table
+            .despawn_multi(vec![keys[0], keys[1], keys[1], stale].into_iter())
+            .await;
+        assert_eq!(dropped, 5);
+
+        assert!(view_keys(&table).await.is_empty());
+        assert!(!table.is_alive(&keys[0]));
+        assert!(!table.is_alive(&keys[1]));
+
+        // Neither the untouched key nor the one reusing the stale key's slot were affected
+        assert_eq!(*table.get::<i32>(&keys[2]).await.unwrap(), 2);
+        assert_eq!(*table.get::<i32>(&reused).await.unwrap(), 4);
+        assert!(table.is_alive(&reused));
+    });
+}
+
+#[test]
+fn despawn_removes_every_cell_at_once() {
+    runtime::run(async {
+        despawn_while_locked::<i32>().await;
+        despawn_while_locked::<f32>().await;
+        despawn_while_locked::<bool>().await;
+    });
+}
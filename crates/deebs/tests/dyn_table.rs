@@ -0,0 +1,139 @@
+//! Checks that the row derives, views and despawning work over columns added at runtime.
+
+use deebs::{
+    macros::{CommonKeys, Insert, Remove, Row},
+    runtime::run,
+    ColumnStorage, CommonKeys, DynTable, Insert, OrdIndex, ReadCell, ReadSingleton, ReadView,
+    Remove, Row, Table, WriteCell, WriteSingleton,
+};
+use futures::StreamExt;
+
+use deebs::ObserveEvent::*;
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys, Insert, Remove)]
+struct PositionRow<'a> {
+    position: WriteCell<'a, (f32, f32)>,
+    name: Option<ReadCell<'a, String>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct NamedRow<'a> {
+    name: ReadCell<'a, String>,
+}
+
+#[derive(Debug, Default)]
+struct Gravity(f32);
+
+#[test]
+fn row_derives_create_columns_on_first_use() {
+    run(async {
+        let table = DynTable::new();
+        assert!(!table.has_column::<(f32, f32)>());
+
+        let keys = PositionRow::insert_auto_multi(
+            &table,
+            vec![
+                ((0.0, 0.0), Some(String::from("origin"))),
+                ((1.0, 2.0), None),
+            ]
+            .into_iter(),
+        )
+        .await;
+        assert!(table.has_column::<(f32, f32)>());
+        assert!(table.has_column::<String>());
+
+        PositionRow::query(&table)
+            .await
+            .for_each(|mut row| {
+                row.position.1 += 1.0;
+                futures::future::ready(())
+            })
+            .await;
+        assert_eq!(
+            *table.get::<(f32, f32)>(&keys[1]).await.unwrap(),
+            (1.0, 3.0)
+        );
+
+        PositionRow::remove(&table, keys[0]).await;
+        assert!(table.get::<String>(&keys[0]).await.is_err());
+        assert_eq!(
+            PositionRow::common_keys(&table)
+                .await
+                .collect::<Vec<_>>()
+                .await,
+            vec![keys[1]]
+        );
+    });
+}
+
+#[test]
+fn registered_views_follow_inserts_and_despawns() {
+    run(async {
+        let table = DynTable::new();
+        let first = table.insert_auto(String::from("first")).await;
+
+        assert!(!table.has_view::<NamedRow<'static>>());
+        table.register_view::<NamedRow<'static>>().await;
+        let receiver = table.observe_view_channel::<NamedRow<'static>, _>(OnInsert | OnRemove, 8);
+
+        let second = table.insert_auto(String::from("second")).await;
+        table.insert(second, 2i32).await;
+        let view = ReadView::new::<_, NamedRow<'static>>(&table).await;
+        assert_eq!(
+            view.iter().copied().collect::<Vec<_>>(),
+            vec![first, second]
+        );
+        drop(view);
+
+        assert_eq!(table.despawn(second).await, 2);
+        assert!(!table.is_alive(&second));
+        assert_eq!(receiver.try_recv().unwrap(), (OnInsert, second));
+        assert_eq!(receiver.try_recv().unwrap(), (OnRemove, second));
+        assert_eq!(
+            table.keys::<String>().await.collect::<Vec<_>>().await,
+            vec![first]
+        );
+    });
+}
+
+#[test]
+fn singletons_are_defaulted_on_first_use() {
+    run(async {
+        let table = DynTable::new();
+        assert_eq!(ReadSingleton::<Gravity>::new(&table).await.0, 0.0);
+
+        WriteSingleton::<Gravity>::new(&table).await.0 = -9.8;
+        assert_eq!(ReadSingleton::<Gravity>::new(&table).await.0, -9.8);
+    });
+}
+
+#[test]
+fn registered_storage_and_index_are_applied() {
+    run(async {
+        let table = DynTable::new();
+        table.register_column::<i32>(ColumnStorage::Dense);
+        table.register_index::<i32, OrdIndex<i32>>();
+
+        // New columns are laid out as registered before they are first locked
+        assert_eq!(
+            table.column::<i32>().read().await.storage(),
+            ColumnStorage::Dense
+        );
+
+        let keys = table.insert_auto_multi(vec![3, 1, 3].into_iter()).await;
+        assert_eq!(
+            table.find(&3).await.collect::<Vec<_>>().await,
+            vec![keys[0], keys[2]]
+        );
+        assert_eq!(
+            table.range(..2).await.collect::<Vec<_>>().await,
+            vec![keys[1]]
+        );
+        assert_eq!(
+            table.column::<i32>().read().await.storage(),
+            ColumnStorage::Dense
+        );
+    });
+}
@@ -0,0 +1,98 @@
+//! Checks that events rotate out after two frames and that readers consume them independently.
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::Table, runtime::run, EventReader, Events, KeyAllocator, ReadSingleton, Singleton,
+    WriteSingleton,
+};
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable {
+    key_allocator: KeyAllocator,
+
+    events: Singleton<Events<i32>>,
+}
+
+#[test]
+fn events_are_dropped_after_two_updates() {
+    let mut events = Events::default();
+    events.extend(vec![1, 2]);
+    assert_eq!(events.current(), &[1, 2]);
+
+    events.update();
+    events.send(3);
+    assert_eq!(events.current(), &[3]);
+    assert_eq!(events.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    events.update();
+    assert!(events.current().is_empty());
+    assert_eq!(events.iter().copied().collect::<Vec<_>>(), vec![3]);
+    assert_eq!(events.event_count(), 3);
+
+    events.update();
+    assert!(events.is_empty());
+}
+
+#[test]
+fn readers_consume_events_independently() {
+    run(async {
+        let table = TestTable::default();
+        let mut fast = EventReader::<i32>::default();
+        let mut slow = EventReader::<i32>::default();
+
+        {
+            let mut events = WriteSingleton::<Events<i32>>::new(&table).await;
+            events.update();
+            events.extend(vec![1, 2]);
+        }
+
+        {
+            let events = ReadSingleton::<Events<i32>>::new(&table).await;
+            assert_eq!(fast.len(&events), 2);
+            assert_eq!(fast.read(&events).copied().collect::<Vec<_>>(), vec![1, 2]);
+            assert!(fast.is_empty(&events));
+            assert_eq!(fast.read(&events).count(), 0);
+        }
+
+        {
+            let mut events = WriteSingleton::<Events<i32>>::new(&table).await;
+            events.update();
+            events.send(3);
+        }
+
+        {
+            let events = ReadSingleton::<Events<i32>>::new(&table).await;
+            assert_eq!(fast.read(&events).copied().collect::<Vec<_>>(), vec![3]);
+            assert_eq!(
+                slow.read(&events).copied().collect::<Vec<_>>(),
+                vec![1, 2, 3]
+            );
+        }
+
+        {
+            let mut events = WriteSingleton::<Events<i32>>::new(&table).await;
+            events.update();
+            events.send(4);
+            events.update();
+            events.send(5);
+        }
+
+        // Readers that fall more than a frame behind skip the events that were rotated out
+        let events = ReadSingleton::<Events<i32>>::new(&table).await;
+        let mut late = events.reader();
+        assert_eq!(slow.read(&events).copied().collect::<Vec<_>>(), vec![4, 5]);
+        assert!(late.read(&events).next().is_none());
+    });
+}
+
+#[test]
+fn cleared_events_are_skipped_by_readers() {
+    let mut events = Events::default();
+    let mut reader = EventReader::default();
+    events.extend(vec![1, 2]);
+    events.clear();
+    events.send(3);
+
+    assert_eq!(reader.read(&events).copied().collect::<Vec<_>>(), vec![3]);
+    assert_eq!(events.event_count(), 3);
+}
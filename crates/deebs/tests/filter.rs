@@ -0,0 +1,238 @@
+//! Checks that the `With`, `Without` and `Or` filters restrict derived rows, alone and combined.
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Added, Column, Key, KeyAllocator, Or, ReadCell, Row, Table, Tick, View, With, Without,
+};
+use futures::StreamExt;
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct WithRow<'a> {
+    int: ReadCell<'a, i32>,
+    with_float: With<f32>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct WithoutRow<'a> {
+    int: ReadCell<'a, i32>,
+    without_bool: Without<bool>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct WithWithoutRow<'a> {
+    int: ReadCell<'a, i32>,
+    with_float: With<f32>,
+    without_bool: Without<bool>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct OrRow<'a> {
+    int: ReadCell<'a, i32>,
+    float_or_bool: Or<(With<f32>, With<bool>)>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct OrWithoutRow<'a> {
+    int: ReadCell<'a, i32>,
+    float_or_no_bool: Or<(With<f32>, Without<bool>)>,
+    without_char: Without<char>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct OrAddedRow<'a> {
+    int: ReadCell<'a, i32>,
+    added_or_bool: Or<(Added<i32>, With<bool>)>,
+}
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    key_allocator: KeyAllocator,
+
+    ints: Column<i32>,
+    floats: Column<f32>,
+    bools: Column<bool>,
+    chars: Column<char>,
+
+    with_without_view: View<WithWithoutRow<'a>>,
+    or_without_view: View<OrWithoutRow<'a>>,
+}
+
+/// Keys of a table holding every combination of float, bool and char cells alongside an int.
+struct Keys {
+    int: Key,
+    float: Key,
+    bool: Key,
+    float_bool: Key,
+    float_char: Key,
+    char: Key,
+}
+
+async fn populate(table: &TestTable<'_>) -> Keys {
+    let keys = table.insert_auto_multi(0..6).await;
+    let keys = Keys {
+        int: keys[0],
+        float: keys[1],
+        bool: keys[2],
+        float_bool: keys[3],
+        float_char: keys[4],
+        char: keys[5],
+    };
+
+    table.insert(keys.float, 1.0f32).await;
+    table.insert(keys.bool, true).await;
+    table.insert(keys.float_bool, 3.0f32).await;
+    table.insert(keys.float_bool, true).await;
+    table.insert(keys.float_char, 4.0f32).await;
+    table.insert(keys.float_char, 'a').await;
+    table.insert(keys.char, 'b').await;
+
+    // A float without an int never matches, whatever the filters
+    table.insert_auto(6.0f32).await;
+
+    keys
+}
+
+#[test]
+fn with_and_without_filter_rows() {
+    run(async {
+        let table = TestTable::default();
+        let keys = populate(&table).await;
+
+        let ints = WithRow::query(&table)
+            .await
+            .map(|row| *row.int)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(ints, vec![1, 3, 4]);
+
+        let ints = WithoutRow::query(&table)
+            .await
+            .map(|row| *row.int)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(ints, vec![0, 1, 4, 5]);
+
+        let ints = WithWithoutRow::query(&table)
+            .await
+            .map(|row| *row.int)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(ints, vec![1, 4]);
+
+        assert_eq!(
+            table
+                .with_without_view
+                .keys()
+                .await
+                .collect::<Vec<_>>()
+                .await,
+            vec![keys.float, keys.float_char]
+        );
+    });
+}
+
+#[test]
+fn or_passes_keys_matching_any_filter() {
+    run(async {
+        let table = TestTable::default();
+        let keys = populate(&table).await;
+
+        let ints = OrRow::query(&table)
+            .await
+            .map(|row| *row.int)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(ints, vec![1, 2, 3, 4]);
+
+        // (float || !bool) && !char
+        let ints = OrWithoutRow::query(&table)
+            .await
+            .map(|row| *row.int)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(ints, vec![0, 1, 3]);
+        assert_eq!(
+            table.or_without_view.keys().await.collect::<Vec<_>>().await,
+            vec![keys.int, keys.float, keys.float_bool]
+        );
+    });
+}
+
+#[test]
+fn filtered_views_follow_filter_columns() {
+    run(async {
+        let table = TestTable::default();
+        let keys = populate(&table).await;
+
+        // Adding an excluded cell drops the key, removing it restores the key
+        table.insert(keys.int, 'c').await;
+        table.insert(keys.float, true).await;
+        assert_eq!(
+            table.or_without_view.keys().await.collect::<Vec<_>>().await,
+            vec![keys.float, keys.float_bool]
+        );
+        assert_eq!(
+            table
+                .with_without_view
+                .keys()
+                .await
+                .collect::<Vec<_>>()
+                .await,
+            vec![keys.float_char]
+        );
+
+        table.remove::<char>(keys.int).await;
+        table.remove::<f32>(keys.float_char).await;
+        assert_eq!(
+            table.or_without_view.keys().await.collect::<Vec<_>>().await,
+            vec![keys.int, keys.float, keys.float_bool]
+        );
+        assert!(table
+            .with_without_view
+            .keys()
+            .await
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+
+        // Without its float, a key with a bool fails both sides of the Or
+        table.remove::<f32>(keys.float_bool).await;
+        assert_eq!(
+            table.or_without_view.keys().await.collect::<Vec<_>>().await,
+            vec![keys.int, keys.float]
+        );
+    });
+}
+
+#[test]
+fn or_combines_change_filters_with_presence_filters() {
+    run(async {
+        let table = TestTable::default();
+        let keys = populate(&table).await;
+
+        let tick = Tick::now();
+        let added = table.insert_auto(7).await;
+
+        let ints = OrAddedRow::query_since(&table, tick)
+            .await
+            .map(|row| *row.int)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(ints, vec![2, 3, 7]);
+
+        let ints = OrAddedRow::query_keys_since(&table, vec![keys.int, keys.bool, added], tick)
+            .await
+            .map(|row| *row.int)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(ints, vec![2, 7]);
+    });
+}
@@ -0,0 +1,168 @@
+//! Checks that tables forward borrows, views, storage and reflection to `#[flatten]` sub-tables.
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Column, ColumnStorage, KeyAllocator, ReadCell, ReadSingleton, Row, Table,
+};
+use futures::StreamExt;
+
+use plugin::{PluginColumns, Position};
+
+mod plugin {
+    use borrow_derive::Borrow;
+    use deebs::{
+        macros::{CommonKeys, Row, SubTable},
+        Column, ReadCell, Singleton, View,
+    };
+
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Position(pub i32);
+
+    #[derive(Debug, Default, Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Gravity(pub f32);
+
+    #[allow(dead_code)]
+    #[derive(Debug, Row, CommonKeys)]
+    pub struct NamedPositionRow<'a> {
+        pub name: ReadCell<'a, String>,
+        pub position: ReadCell<'a, Position>,
+    }
+
+    // Field types are spelled in the crates that flatten the bundle,
+    // so types from outside the prelude and `deebs` need absolute paths
+    #[derive(Debug, Default, Borrow, SubTable)]
+    pub struct PluginColumns<'a> {
+        pub gravity: Singleton<crate::plugin::Gravity>,
+
+        #[dense]
+        #[index]
+        pub positions: Column<crate::plugin::Position>,
+        pub parents: Column<deebs::Parent>,
+        pub children: Column<deebs::Children>,
+
+        pub named_positions: View<crate::plugin::NamedPositionRow<'a>>,
+    }
+}
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    key_allocator: KeyAllocator,
+
+    names: Column<String>,
+
+    #[flatten]
+    plugin: PluginColumns<'a>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct PositionRow<'a> {
+    position: ReadCell<'a, Position>,
+}
+
+#[test]
+fn flattened_columns_are_borrowed_through_the_table() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table
+            .insert_auto_multi(vec![Position(2), Position(1)].into_iter())
+            .await;
+        table.insert(keys[0], String::from("first")).await;
+
+        assert_eq!(
+            PositionRow::query(&table)
+                .await
+                .map(|row| *row.position)
+                .collect::<Vec<_>>()
+                .await,
+            vec![Position(2), Position(1)]
+        );
+        assert_eq!(ReadSingleton::<plugin::Gravity>::new(&table).await.0, 0.0);
+
+        // The flattened index and storage are applied to the table's column
+        assert_eq!(
+            table.range(..Position(2)).await.collect::<Vec<_>>().await,
+            vec![keys[1]]
+        );
+        assert_eq!(
+            deebs::Table::column_storage(&table, std::any::TypeId::of::<Position>()),
+            ColumnStorage::Dense
+        );
+    });
+}
+
+#[test]
+fn flattened_views_and_hierarchy_follow_the_table() {
+    run(async {
+        let table = TestTable::default();
+        let parent = table.insert_auto(Position(0)).await;
+        let child = table.insert_auto(Position(1)).await;
+        table.insert(parent, String::from("parent")).await;
+        table.insert(child, String::from("child")).await;
+        table.set_parent(child, parent).await.unwrap();
+
+        assert_eq!(
+            table
+                .plugin
+                .named_positions
+                .keys()
+                .await
+                .collect::<Vec<_>>()
+                .await,
+            vec![parent, child]
+        );
+
+        table.remove::<String>(child).await;
+        assert_eq!(
+            table
+                .plugin
+                .named_positions
+                .keys()
+                .await
+                .collect::<Vec<_>>()
+                .await,
+            vec![parent]
+        );
+
+        // Despawning the parent despawns the child through the flattened hierarchy columns
+        assert_eq!(table.despawn(parent).await, 5);
+        assert!(!table.is_alive(&child));
+        assert!(table
+            .plugin
+            .named_positions
+            .keys()
+            .await
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+    });
+}
+
+#[test]
+fn flattened_columns_are_registered() {
+    let registry = TestTable::column_registry();
+    let names = registry
+        .iter()
+        .map(|column| column.name())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            "names",
+            "plugin.positions",
+            "plugin.parents",
+            "plugin.children"
+        ]
+    );
+
+    // Flattened columns are named after the field holding their bundle
+    let positions = registry.get_by_field("plugin.positions").unwrap();
+    assert_eq!(positions.field, "positions");
+    assert_eq!(positions.flatten, Some("plugin"));
+    assert!(registry.get_by_field("positions").is_none());
+    assert!(registry.get_by_field("names").is_some());
+}
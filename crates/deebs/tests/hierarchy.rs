@@ -0,0 +1,172 @@
+//! Checks that parent and child columns stay consistent as entities are attached, moved and despawned.
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Children, Column, HierarchyError, Key, KeyAllocator, Parent, ReadCell, Table,
+};
+use futures::StreamExt;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Device;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable {
+    key_allocator: KeyAllocator,
+
+    names: Column<String>,
+    #[skip_serde]
+    devices: Column<Device>,
+    parents: Column<Parent>,
+    children: Column<Children>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct NameRow<'a> {
+    name: ReadCell<'a, String>,
+}
+
+async fn children(table: &TestTable, key: Key) -> Vec<Key> {
+    match table.get::<Children>(&key).await {
+        Ok(children) => children.to_vec(),
+        Err(_) => vec![],
+    }
+}
+
+async fn parent(table: &TestTable, key: Key) -> Option<Key> {
+    table
+        .get::<Parent>(&key)
+        .await
+        .ok()
+        .map(|parent| parent.key())
+}
+
+#[test]
+fn set_parent_keeps_both_columns_in_step() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table
+            .insert_auto_multi(vec!["a", "b", "c"].into_iter().map(String::from))
+            .await;
+        let (a, b, c) = (keys[0], keys[1], keys[2]);
+
+        table.set_parent(b, a).await.unwrap();
+        table.set_parent(c, a).await.unwrap();
+        assert_eq!(children(&table, a).await, vec![b, c]);
+        assert_eq!(parent(&table, b).await, Some(a));
+
+        // Moving a child detaches it from its previous parent
+        table.set_parent(c, b).await.unwrap();
+        assert_eq!(children(&table, a).await, vec![b]);
+        assert_eq!(children(&table, b).await, vec![c]);
+        assert_eq!(parent(&table, c).await, Some(b));
+
+        assert_eq!(
+            table.set_parent(a, c).await,
+            Err(HierarchyError::Cycle {
+                child: a,
+                parent: c
+            })
+        );
+        assert_eq!(
+            table.set_parent(a, a).await,
+            Err(HierarchyError::Cycle {
+                child: a,
+                parent: a
+            })
+        );
+
+        assert_eq!(table.remove_parent(c).await, Some(b));
+        assert_eq!(table.remove_parent(c).await, None);
+        assert!(table.get::<Children>(&b).await.is_err());
+        assert!(table.get::<Parent>(&c).await.is_err());
+
+        let stale = table.insert_auto("stale".to_string()).await;
+        table.despawn(stale).await;
+        assert_eq!(
+            table.set_parent(stale, a).await,
+            Err(HierarchyError::Stale(stale))
+        );
+    });
+}
+
+#[test]
+fn despawn_cascades_to_descendants() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table
+            .insert_auto_multi(
+                vec!["root", "a", "a1", "a2", "b"]
+                    .into_iter()
+                    .map(String::from),
+            )
+            .await;
+        let (root, a, a1, a2, b) = (keys[0], keys[1], keys[2], keys[3], keys[4]);
+
+        table.set_parent(a, root).await.unwrap();
+        table.set_parent(a1, a).await.unwrap();
+        table.set_parent(a2, a).await.unwrap();
+        table.set_parent(b, root).await.unwrap();
+
+        // Each of `a`, `a1` and `a2` drops a name and a parent cell, and `a` its children
+        assert_eq!(table.despawn(a).await, 7);
+
+        for key in [a, a1, a2].iter() {
+            assert!(table.is_stale(key));
+        }
+        assert_eq!(children(&table, root).await, vec![b]);
+        assert_eq!(parent(&table, b).await, Some(root));
+
+        table.despawn(root).await;
+        assert!(table.is_stale(&b));
+        assert_eq!(
+            table.keys::<String>().await.collect::<Vec<_>>().await,
+            vec![]
+        );
+        assert_eq!(
+            table.keys::<Parent>().await.collect::<Vec<_>>().await,
+            vec![]
+        );
+        assert_eq!(
+            table.keys::<Children>().await.collect::<Vec<_>>().await,
+            vec![]
+        );
+    });
+}
+
+#[test]
+fn queries_walk_the_hierarchy() {
+    run(async {
+        let table = TestTable::default();
+        let device = table.insert_auto(Device).await;
+        let keys = table
+            .insert_auto_multi(
+                vec!["window", "swap chain", "surface", "other"]
+                    .into_iter()
+                    .map(String::from),
+            )
+            .await;
+        let (window, swap_chain, surface, other) = (keys[0], keys[1], keys[2], keys[3]);
+
+        table.set_parent(window, device).await.unwrap();
+        table.set_parent(swap_chain, window).await.unwrap();
+        table.set_parent(surface, swap_chain).await.unwrap();
+        table.set_parent(other, device).await.unwrap();
+
+        let names = table
+            .query_descendants::<NameRow>(device)
+            .await
+            .map(|row| row.name.clone())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(names, vec!["window", "swap chain", "surface", "other"]);
+
+        let ancestors = table.ancestors(surface).await.collect::<Vec<_>>().await;
+        assert_eq!(ancestors, vec![swap_chain, window, device]);
+
+        assert_eq!(table.ancestor_with::<Device>(surface).await, Some(device));
+        assert_eq!(table.ancestor_with::<Device>(device).await, None);
+    });
+}
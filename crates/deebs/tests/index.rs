@@ -0,0 +1,244 @@
+//! Checks that indexed lookups agree with the cells they index.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::Table, runtime::run, Column, Key, KeyAllocator, ObserveEvent::OnRemove, Table, Tick,
+};
+use futures::StreamExt;
+use proptest::prelude::*;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable {
+    key_allocator: KeyAllocator,
+
+    #[index]
+    ints: Column<i32>,
+    #[index(hash)]
+    #[dense]
+    labels: Column<String>,
+    floats: Column<f32>,
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    Insert(usize, i32),
+    Remove(usize),
+    Write(usize, i32),
+    Commit(usize, i32),
+    Rollback(usize, i32),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let index = 0..8usize;
+    let value = -4..4i32;
+    prop_oneof![
+        (index.clone(), value.clone()).prop_map(|(index, value)| Op::Insert(index, value)),
+        index.clone().prop_map(Op::Remove),
+        (index.clone(), value.clone()).prop_map(|(index, value)| Op::Write(index, value)),
+        (index.clone(), value.clone()).prop_map(|(index, value)| Op::Commit(index, value)),
+        (index, value).prop_map(|(index, value)| Op::Rollback(index, value)),
+    ]
+}
+
+async fn apply(table: &TestTable, model: &mut BTreeMap<Key, i32>, op: Op) {
+    match op {
+        Op::Insert(index, value) => {
+            table.insert(Key::from(index), value).await;
+            model.insert(Key::from(index), value);
+        }
+        Op::Remove(index) => {
+            table.remove::<i32>(Key::from(index)).await;
+            model.remove(&Key::from(index));
+        }
+        Op::Write(index, value) => {
+            if let Ok(mut cell) = table.get_mut::<i32>(&Key::from(index)).await {
+                *cell = value;
+                model.insert(Key::from(index), value);
+            }
+        }
+        Op::Commit(index, value) => {
+            table
+                .transaction::<(i32,), _, _, ()>(|(ints,)| {
+                    ints.modify(&Key::from(index), |cell| *cell = value);
+                    Ok(())
+                })
+                .await
+                .unwrap();
+
+            if let Some(cell) = model.get_mut(&Key::from(index)) {
+                *cell = value;
+            }
+        }
+        Op::Rollback(index, value) => {
+            table
+                .transaction::<(i32,), _, (), ()>(|(ints,)| {
+                    ints.insert(Key::from(index), value);
+                    Err(())
+                })
+                .await
+                .unwrap_err();
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn index_matches_cells(ops in prop::collection::vec(op(), 0..32)) {
+        run(async {
+            let table = TestTable::default();
+            let mut model = BTreeMap::new();
+            for op in ops {
+                apply(&table, &mut model, op).await;
+            }
+
+            for value in -4..4 {
+                let found = table.find(&value).await.collect::<BTreeSet<_>>().await;
+                let expected = model
+                    .iter()
+                    .filter(|(_, cell)| **cell == value)
+                    .map(|(key, _)| *key)
+                    .collect::<BTreeSet<_>>();
+                assert_eq!(found, expected);
+            }
+
+            let found = table.range(-2..2).await.collect::<BTreeSet<_>>().await;
+            let expected = model
+                .iter()
+                .filter(|(_, cell)| (-2..2).contains(*cell))
+                .map(|(key, _)| *key)
+                .collect::<BTreeSet<_>>();
+            assert_eq!(found, expected);
+        });
+    }
+}
+
+#[test]
+fn hash_index_finds_labels() {
+    run(async {
+        let table = TestTable::default();
+        let window = table.insert_auto("Egui Debugger".to_string()).await;
+        let other = table.insert_auto("Other".to_string()).await;
+
+        let find = |label: &'static str| {
+            let table = &table;
+            async move {
+                table
+                    .find(&label.to_string())
+                    .await
+                    .collect::<Vec<_>>()
+                    .await
+            }
+        };
+
+        assert_eq!(find("Egui Debugger").await, vec![window]);
+
+        table.get_mut::<String>(&other).await.unwrap().push('!');
+        assert_eq!(find("Other").await, vec![]);
+        assert_eq!(find("Other!").await, vec![other]);
+
+        table.despawn(window).await;
+        assert_eq!(find("Egui Debugger").await, vec![]);
+
+        // Hash indexes can't answer ranges, so the column is scanned instead
+        let range = table
+            .range("O".to_string().."P".to_string())
+            .await
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(range, vec![other]);
+    });
+}
+
+#[test]
+fn unindexed_columns_are_scanned() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table
+            .insert_auto_multi(vec![1.0f32, 2.0, 3.0].into_iter())
+            .await;
+
+        let found = table.find(&2.0f32).await.collect::<Vec<_>>().await;
+        assert_eq!(found, vec![keys[1]]);
+
+        let range = table.range(1.5f32..).await.collect::<Vec<_>>().await;
+        assert_eq!(range, keys[1..].to_vec());
+    });
+}
+
+#[test]
+fn inverted_ranges_are_empty() {
+    run(async {
+        let table = TestTable::default();
+        table.insert_auto(1).await;
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let range = table.range(2..1).await.collect::<Vec<_>>().await;
+        assert!(range.is_empty());
+
+        let range = table.range(1..1).await.collect::<Vec<_>>().await;
+        assert!(range.is_empty());
+    });
+}
+
+#[test]
+fn index_sees_cell_writes_on_release() {
+    run(async {
+        let table = TestTable::default();
+        let key = table.insert_auto(1).await;
+
+        // The index answers without waiting on the held cell, and only sees its write once released
+        let mut cell = table.get_mut::<i32>(&key).await.unwrap();
+        *cell = 2;
+
+        let found = async_std::future::timeout(Duration::from_secs(1), async {
+            table.find(&1).await.collect::<Vec<_>>().await
+        })
+        .await
+        .expect("Indexed lookup waited on a cell lock.");
+        assert_eq!(found, vec![key]);
+
+        drop(cell);
+        assert_eq!(table.find(&1).await.collect::<Vec<_>>().await, vec![]);
+        assert_eq!(table.find(&2).await.collect::<Vec<_>>().await, vec![key]);
+    });
+}
+
+#[test]
+fn dense_inserts_evict_other_generations_as_removals() {
+    run(async {
+        let table = TestTable::default();
+        let old = Key::new(0, 0);
+        let new = Key::new(0, 1);
+        table.insert(old, String::from("old")).await;
+
+        let tick = Tick::now();
+        let removals = table.observe_channel::<String, _>(OnRemove, 4);
+
+        // Both generations share a dense slot, so the old cell is removed like any other
+        table.insert(new, String::from("new")).await;
+        assert_eq!(
+            table.removed::<String>(tick).collect::<Vec<_>>().await,
+            vec![old]
+        );
+        assert_eq!(removals.try_recv().unwrap(), (OnRemove, old));
+        assert!(table
+            .find(&String::from("old"))
+            .await
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+        assert_eq!(
+            table
+                .find(&String::from("new"))
+                .await
+                .collect::<Vec<_>>()
+                .await,
+            vec![new]
+        );
+    });
+}
@@ -0,0 +1,275 @@
+//! Checks that replaying a journal reconstructs the table that wrote it.
+#![cfg(feature = "journal")]
+
+use std::{
+    fs::OpenOptions,
+    io::{Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use borrow_derive::Borrow;
+use deebs::{
+    journal::Journal,
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Column, Key, KeyAllocator, ReadCell, Table, View,
+};
+use futures::StreamExt;
+
+use bundles::{Bytes, Words};
+
+mod bundles {
+    use borrow_derive::Borrow;
+    use deebs::{macros::SubTable, Column};
+
+    #[derive(Debug, Default, Borrow, SubTable)]
+    pub struct Bytes {
+        pub values: Column<u8>,
+    }
+
+    #[derive(Debug, Default, Borrow, SubTable)]
+    pub struct Words {
+        pub values: Column<u16>,
+    }
+}
+
+#[derive(Debug)]
+struct Opaque;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    key_allocator: KeyAllocator,
+
+    ints: Column<i32>,
+    #[dense]
+    strings: Column<String>,
+    #[skip_serde]
+    opaques: Column<Opaque>,
+
+    int_string_view: View<IntStringRow<'a>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct IntStringRow<'a> {
+    int: ReadCell<'a, i32>,
+    string: ReadCell<'a, String>,
+}
+
+/// Flattens two bundles whose columns share a field name.
+#[derive(Debug, Default, Borrow, Table)]
+struct FlattenedTable {
+    key_allocator: KeyAllocator,
+
+    #[flatten]
+    bytes: Bytes,
+    #[flatten]
+    words: Words,
+}
+
+/// A journal path unique to one test, removed along with its side files on drop.
+struct TempJournal(PathBuf);
+
+impl TempJournal {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "deebs-journal-{}-{}.journal",
+            name,
+            std::process::id()
+        ));
+        let journal = TempJournal(path);
+        journal.clean();
+        journal
+    }
+
+    fn clean(&self) {
+        for extension in ["journal", "pending", "compacted"] {
+            std::fs::remove_file(self.0.with_extension(extension)).ok();
+        }
+    }
+}
+
+impl Drop for TempJournal {
+    fn drop(&mut self) {
+        self.clean();
+    }
+}
+
+async fn state(table: &TestTable<'_>) -> (Vec<(Key, i32)>, Vec<(Key, String)>, Vec<Key>) {
+    let mut ints = vec![];
+    for key in table.keys::<i32>().await.collect::<Vec<_>>().await {
+        ints.push((key, *table.get::<i32>(&key).await.unwrap()));
+    }
+
+    let mut strings = vec![];
+    for key in table.keys::<String>().await.collect::<Vec<_>>().await {
+        strings.push((key, table.get::<String>(&key).await.unwrap().clone()));
+    }
+
+    let view = table
+        .int_string_view
+        .keys
+        .read()
+        .await
+        .iter()
+        .copied()
+        .collect();
+
+    (ints, strings, view)
+}
+
+async fn write(table: &TestTable<'_>) -> Vec<Key> {
+    let keys = table.insert_auto_multi(0..4).await;
+    table
+        .insert_multi(keys.iter().map(|key| (*key, key.to_string())))
+        .await;
+
+    *table.get_mut::<i32>(&keys[0]).await.unwrap() = 10;
+    table.get_mut::<String>(&keys[1]).await.unwrap().push('!');
+    table.remove::<String>(keys[2]).await;
+    table.insert(keys[3], Opaque).await;
+
+    table
+        .transaction::<(i32,), _, _, ()>(|(ints,)| {
+            ints.insert(keys[1], 11);
+            ints.remove(&keys[3]);
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+    keys
+}
+
+#[test]
+fn replay_reconstructs_table() {
+    run(async {
+        let path = TempJournal::new("replay");
+
+        let table = TestTable::default();
+        table.attach_journal(Arc::new(Journal::open(&path.0).unwrap()));
+
+        let keys = write(&table).await;
+        table.despawn(keys[0]).await;
+        let key = table.next_key();
+        table.insert(key, 20).await;
+
+        let replayed = TestTable::default();
+        replayed
+            .replay(&Journal::open(&path.0).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(state(&replayed).await, state(&table).await);
+        assert!(replayed.is_stale(&keys[0]));
+        assert!(replayed.is_alive(&key));
+        assert!(replayed.get::<Opaque>(&keys[3]).await.is_err());
+        assert_ne!(replayed.next_key(), key);
+    });
+}
+
+#[test]
+fn damaged_tail_is_ignored() {
+    run(async {
+        let path = TempJournal::new("damaged");
+
+        let table = TestTable::default();
+        let journal = Arc::new(Journal::open(&path.0).unwrap());
+        table.attach_journal(journal.clone());
+
+        let key = table.insert_auto(1).await;
+        let expected = state(&table).await;
+        table.insert(key, 2).await;
+        drop(journal);
+
+        // Flip a byte in the final record's payload, as a torn write might
+        let mut file = OpenOptions::new().write(true).open(&path.0).unwrap();
+        file.seek(SeekFrom::End(-2)).unwrap();
+        file.write_all(b"?").unwrap();
+        drop(file);
+
+        let replayed = TestTable::default();
+        let journal = Arc::new(Journal::open(&path.0).unwrap());
+        assert_eq!(replayed.replay(&journal).await.unwrap(), 2);
+        assert_eq!(state(&replayed).await, expected);
+
+        // Records appended after the damaged tail is truncated are readable
+        replayed.attach_journal(journal);
+        replayed.insert(key, 3).await;
+
+        // A record cut short is dropped too
+        let len = std::fs::metadata(&path.0).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path.0)
+            .unwrap()
+            .set_len(len + 5)
+            .unwrap();
+
+        let again = TestTable::default();
+        assert_eq!(
+            again
+                .replay(&Journal::open(&path.0).unwrap())
+                .await
+                .unwrap(),
+            3
+        );
+        assert_eq!(*again.get::<i32>(&key).await.unwrap(), 3);
+    });
+}
+
+#[test]
+fn compaction_keeps_state() {
+    run(async {
+        let path = TempJournal::new("compaction");
+
+        let table = TestTable::default();
+        let journal = Arc::new(Journal::open(&path.0).unwrap());
+        table.attach_journal(journal.clone());
+
+        for _ in 0..4 {
+            write(&table).await;
+        }
+
+        let before = std::fs::metadata(&path.0).unwrap().len();
+        table.compact_journal(&journal).await.unwrap();
+        assert!(std::fs::metadata(&path.0).unwrap().len() < before);
+
+        let key = table.insert_auto(30).await;
+
+        let replayed = TestTable::default();
+        let records = replayed
+            .replay(&Journal::open(&path.0).unwrap())
+            .await
+            .unwrap();
+
+        // The snapshot, then the allocation and insert made after compacting
+        assert_eq!(records, 3);
+        assert_eq!(state(&replayed).await, state(&table).await);
+        assert!(replayed.is_alive(&key));
+        assert!(journal.take_error().is_none());
+    });
+}
+
+#[test]
+fn flattened_columns_are_journaled_apart() {
+    run(async {
+        let path = TempJournal::new("flattened");
+
+        let table = FlattenedTable::default();
+        table.attach_journal(Arc::new(Journal::open(&path.0).unwrap()));
+
+        let key = table.insert_auto(1u8).await;
+        table.insert(key, 300u16).await;
+
+        let replayed = FlattenedTable::default();
+        replayed
+            .replay(&Journal::open(&path.0).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(*replayed.get::<u8>(&key).await.unwrap(), 1);
+        assert_eq!(*replayed.get::<u16>(&key).await.unwrap(), 300);
+    });
+}
@@ -0,0 +1,100 @@
+//! Checks that keys whose slot has been reused are rejected rather than aliasing the new occupant.
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::Table, runtime::run, CellError, Column, Key, KeyAllocator, Table, WriteColumn,
+};
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable {
+    key_allocator: KeyAllocator,
+
+    ints: Column<i32>,
+    #[dense]
+    floats: Column<f32>,
+}
+
+#[test]
+fn freed_slots_are_reused_with_a_new_generation() {
+    let keys = KeyAllocator::default();
+    let old = keys.alloc();
+    assert!(keys.is_alive(&old));
+
+    assert!(keys.free(&old));
+    assert!(!keys.free(&old));
+    assert!(keys.is_stale(&old));
+
+    let new = keys.alloc();
+    assert_eq!(new.index(), old.index());
+    assert_ne!(new.generation(), old.generation());
+    assert!(keys.is_alive(&new));
+    assert!(keys.is_stale(&old));
+
+    // Freeing through a stale key leaves the new occupant alone
+    assert!(!keys.free(&old));
+    assert!(keys.is_alive(&new));
+
+    // Keys this allocator never handed out are neither alive nor stale
+    let foreign = Key::new(new.index() + 1, 0);
+    assert!(!keys.is_alive(&foreign));
+    assert!(!keys.is_stale(&foreign));
+}
+
+#[test]
+fn stale_keys_do_not_reach_reused_slots() {
+    run(async {
+        let table = TestTable::default();
+        let old = table.insert_auto(1).await;
+        table.despawn(old).await;
+
+        let new = table.insert_auto(2).await;
+        assert_eq!(new.index(), old.index());
+
+        assert_eq!(
+            table.get::<i32>(&old).await.unwrap_err(),
+            CellError::Stale(old)
+        );
+        assert_eq!(
+            table.get_mut::<i32>(&old).await.unwrap_err(),
+            CellError::Stale(old)
+        );
+        assert_eq!(
+            table.try_get::<i32>(&old).unwrap_err(),
+            CellError::Stale(old)
+        );
+
+        // Despawning through the stale key leaves the new row in place
+        assert_eq!(table.despawn(old).await, 0);
+        assert!(table.is_alive(&new));
+        assert_eq!(*table.get::<i32>(&new).await.unwrap(), 2);
+    });
+}
+
+#[test]
+fn stale_keys_cannot_write_into_reused_slots() {
+    run(async {
+        let table = TestTable::default();
+        let old = table.insert_auto(1.0f32).await;
+        table.despawn(old).await;
+
+        let new = table.insert_auto(2.0f32).await;
+        assert_eq!(new.index(), old.index());
+
+        // Neither the dense nor the map column lets the stale key alias the new row
+        table.insert(old, 99.0f32).await;
+        table.insert(old, 99).await;
+        assert_eq!(*table.get::<f32>(&new).await.unwrap(), 2.0);
+        assert!(table.get::<i32>(&new).await.is_err());
+        assert_eq!(
+            table.get::<f32>(&old).await.unwrap_err(),
+            CellError::Stale(old)
+        );
+
+        let mut floats = WriteColumn::<f32>::new(&table).await;
+        assert_eq!(
+            floats.try_insert(old, 99.0).unwrap_err(),
+            CellError::Stale(old)
+        );
+        assert_eq!(floats.try_insert(new, 3.0), Ok(Some(2.0)));
+    });
+}
@@ -0,0 +1,80 @@
+//! Checks that the debug-mode lock-order checker catches inversions
+//! and that derived rows lock in canonical order.
+#![cfg(all(debug_assertions, not(deebs_backend = "smol")))]
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Column, KeyAllocator, ReadCell, ReadColumn, Row, Table, WriteCell,
+};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct First(u8);
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Second(u8);
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Third(u8);
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Fourth(u8);
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct FirstSecondRow<'a> {
+    first: WriteCell<'a, First>,
+    second: ReadCell<'a, Second>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct SecondFirstRow<'a> {
+    second: WriteCell<'a, Second>,
+    first: ReadCell<'a, First>,
+}
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable {
+    key_allocator: KeyAllocator,
+
+    firsts: Column<First>,
+    seconds: Column<Second>,
+    thirds: Column<Third>,
+    fourths: Column<Fourth>,
+}
+
+#[test]
+fn rows_declared_in_either_order_lock_in_the_same_order() {
+    run(async {
+        let table = TestTable::default();
+        let key = table.insert_auto(First(0)).await;
+        table.insert(key, Second(0)).await;
+
+        drop(FirstSecondRow::new(&table, &key).await);
+        drop(SecondFirstRow::new(&table, &key).await);
+    });
+}
+
+// Each test below uses its own column types, as observed lock orders are shared by the process
+
+#[test]
+#[should_panic(expected = "Lock order inversion")]
+fn nested_locks_in_opposite_orders_panic() {
+    run(async {
+        let table = TestTable::default();
+
+        {
+            let _third = ReadColumn::<Third>::new(&table).await;
+            let _fourth = ReadColumn::<Fourth>::new(&table).await;
+        }
+
+        let _fourth = ReadColumn::<Fourth>::new(&table).await;
+        let _third = ReadColumn::<Third>::new(&table).await;
+    });
+}
@@ -0,0 +1,162 @@
+//! Checks that column and view observers receive the events they subscribed to.
+
+use std::sync::{Arc, Mutex};
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Column, Key, ReadCell, Row, Table, View, WriteCell,
+};
+
+use deebs::ObserveEvent::*;
+use futures::StreamExt;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    ints: Column<i32>,
+    floats: Column<f32>,
+
+    int_float_view: View<IntFloatRow<'a>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct IntFloatRow<'a> {
+    int: ReadCell<'a, i32>,
+    float: ReadCell<'a, f32>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct ScaleRow<'a> {
+    int: WriteCell<'a, i32>,
+    float: ReadCell<'a, f32>,
+}
+
+#[test]
+fn column_callback_sees_released_column() {
+    run(async {
+        let table = Arc::new(TestTable::default());
+        let seen = Arc::new(Mutex::new(vec![]));
+
+        {
+            let weak = Arc::downgrade(&table);
+            let seen = seen.clone();
+            table.observe::<i32, _, _, _>(OnInsert | OnRemove, move |event, key| {
+                let table = weak.upgrade().unwrap();
+                let seen = seen.clone();
+                async move {
+                    // The write guard has been released, so the column can be read here.
+                    let value = table.get::<i32>(&key).await.ok().map(|cell| *cell);
+                    seen.lock().unwrap().push((event, key, value));
+                }
+            });
+        }
+
+        let key = Key::from(0);
+        table.insert(key, 1i32).await;
+        table.insert(key, 2i32).await;
+        table.remove::<i32>(key).await;
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(OnInsert, key, Some(1)), (OnRemove, key, None)]
+        );
+    });
+}
+
+#[test]
+fn bounded_channel_drops_overflow() {
+    run(async {
+        let table = TestTable::default();
+        let receiver = table.observe_channel::<i32, _>(OnInsert, 2);
+
+        table
+            .insert_multi((0..4).map(|index| (Key::from(index), index as i32)))
+            .await;
+
+        assert_eq!(receiver.len(), 2);
+        assert_eq!(receiver.recv().await, Ok((OnInsert, Key::from(0))));
+        assert_eq!(receiver.recv().await, Ok((OnInsert, Key::from(1))));
+        assert!(receiver.is_empty());
+    });
+}
+
+#[test]
+fn view_channel_tracks_membership() {
+    run(async {
+        let table = TestTable::default();
+        let receiver = table.observe_view_channel::<IntFloatRow, _>(OnInsert | OnRemove, 8);
+
+        let key = Key::from(0);
+        table.insert(key, 1i32).await;
+        assert!(receiver.is_empty());
+
+        table.insert(key, 1.0f32).await;
+        assert_eq!(receiver.recv().await, Ok((OnInsert, key)));
+
+        table.despawn(key).await;
+        assert_eq!(receiver.recv().await, Ok((OnRemove, key)));
+        assert!(receiver.is_empty());
+    });
+}
+
+#[test]
+fn write_cells_notify_on_release() {
+    run(async {
+        let table = TestTable::default();
+        let receiver = table.observe_channel::<i32, _>(OnChange, 8);
+
+        let key = Key::from(0);
+        table.insert(key, 1i32).await;
+
+        let mut cell = table.get_mut::<i32>(&key).await.unwrap();
+        *cell = 2;
+        assert!(receiver.is_empty());
+        cell.release().await;
+        assert_eq!(receiver.try_recv(), Ok((OnChange, key)));
+
+        // Dropped cells leave their events pending until the column is notified
+        *table.get_mut::<i32>(&key).await.unwrap() = 3;
+        assert!(receiver.is_empty());
+        table.notify_observers::<i32>().await;
+        assert_eq!(receiver.try_recv(), Ok((OnChange, key)));
+    });
+}
+
+#[test]
+fn rows_notify_on_release() {
+    run(async {
+        let table = Arc::new(TestTable::default());
+        let receiver = table.observe_channel::<i32, _>(OnChange, 8);
+        let floats = table.observe_channel::<f32, _>(OnChange, 8);
+
+        let key = Key::from(0);
+        table.insert(key, 1i32).await;
+        table.insert(key, 1.0f32).await;
+
+        let mut row = ScaleRow::new(&*table, &key).await;
+        *row.int *= 2;
+        row.release().await;
+        assert_eq!(receiver.try_recv(), Ok((OnChange, key)));
+
+        // Queried rows share the stream's column guards, so their columns are notified afterwards
+        ScaleRow::query(&*table)
+            .await
+            .for_each(|mut row| {
+                *row.int *= 2;
+                futures::future::ready(())
+            })
+            .await;
+        assert!(receiver.is_empty());
+        ScaleRow::notify_observers(&*table).await;
+        assert_eq!(receiver.try_recv(), Ok((OnChange, key)));
+
+        ScaleRow::par_for_each(table.clone(), 2, |mut row| *row.int *= 2).await;
+        assert_eq!(receiver.try_recv(), Ok((OnChange, key)));
+        assert_eq!(*table.get::<i32>(&key).await.unwrap(), 8);
+        assert!(receiver.is_empty());
+        assert!(floats.is_empty());
+    });
+}
@@ -0,0 +1,91 @@
+//! Checks that prefabs spawn every cell under fresh keys.
+#![cfg(feature = "prefab")]
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    prefab::{spawn, ComponentRegistry, Prefab, PrefabError},
+    runtime::run,
+    Column, KeyAllocator, ReadCell, ReadView, Table, View,
+};
+use futures::StreamExt;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    keys: KeyAllocator,
+
+    ints: Column<i32>,
+    floats: Column<f32>,
+    strings: Column<String>,
+
+    int_float_view: View<IntFloatRow<'a>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct IntFloatRow<'a> {
+    int: ReadCell<'a, i32>,
+    float: ReadCell<'a, f32>,
+}
+
+fn registry() -> ComponentRegistry<TestTable<'static>> {
+    let mut registry = ComponentRegistry::new();
+    registry
+        .register::<i32>("int")
+        .register::<f32>("float")
+        .register::<String>("label");
+    registry
+}
+
+#[test]
+fn spawn_prefab() {
+    run(async {
+        let table = TestTable::default();
+        let prefab = Prefab::from_json(
+            r#"{
+                "a": { "int": 1, "float": 2.0, "label": "A" },
+                "b": { "int": 3 }
+            }"#,
+        )
+        .unwrap();
+
+        let keys = spawn(&table, &registry(), &prefab).await.unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_ne!(keys["a"], keys["b"]);
+
+        assert_eq!(*table.get::<i32>(&keys["a"]).await.unwrap(), 1);
+        assert_eq!(*table.get::<f32>(&keys["a"]).await.unwrap(), 2.0);
+        assert_eq!(*table.get::<String>(&keys["a"]).await.unwrap(), "A");
+        assert_eq!(*table.get::<i32>(&keys["b"]).await.unwrap(), 3);
+        assert!(table.get::<f32>(&keys["b"]).await.is_err());
+
+        let view = ReadView::new::<_, IntFloatRow>(&table).await;
+        assert_eq!(view.iter().copied().collect::<Vec<_>>(), vec![keys["a"]]);
+    });
+}
+
+#[test]
+fn failed_spawn_leaves_table_untouched() {
+    run(async {
+        let table = TestTable::default();
+
+        let unknown = Prefab::from_json(r#"{ "a": { "int": 1, "char": "c" } }"#).unwrap();
+        assert!(matches!(
+            spawn(&table, &registry(), &unknown).await,
+            Err(PrefabError::UnknownComponent { .. })
+        ));
+
+        let invalid = Prefab::from_json(r#"{ "a": { "int": 1 }, "b": { "float": "x" } }"#).unwrap();
+        assert!(matches!(
+            spawn(&table, &registry(), &invalid).await,
+            Err(PrefabError::InvalidValue { .. })
+        ));
+
+        assert!(table
+            .keys::<i32>()
+            .await
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+    });
+}
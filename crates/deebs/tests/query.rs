@@ -0,0 +1,79 @@
+//! Checks that derived rows are queried in batches whose rows can be held at once.
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Column, KeyAllocator, ReadCell, Row, Table, WriteCell,
+};
+use futures::StreamExt;
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct IntFloatRow<'a> {
+    int: WriteCell<'a, i32>,
+    float: ReadCell<'a, f32>,
+    name: Option<ReadCell<'a, String>>,
+}
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable {
+    key_allocator: KeyAllocator,
+
+    ints: Column<i32>,
+    #[dense]
+    floats: Column<f32>,
+    names: Column<String>,
+}
+
+#[test]
+fn query_yields_rows_that_can_be_held_together() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table.insert_auto_multi(0..4).await;
+        for key in &keys[..3] {
+            table.insert(*key, key.index() as f32).await;
+        }
+        table.insert(keys[1], String::from("one")).await;
+
+        // Every row is held at once, each writing its own cell
+        let mut rows = IntFloatRow::query(&table).await.collect::<Vec<_>>().await;
+        assert_eq!(rows.len(), 3);
+        for row in rows.iter_mut() {
+            *row.int += *row.float as i32 + 10;
+        }
+        let names = rows
+            .iter()
+            .map(|row| row.name.as_ref().map(|name| name.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![None, Some(String::from("one")), None]);
+        drop(rows);
+
+        let ints = table.keys::<i32>().await.collect::<Vec<_>>().await;
+        let mut values = vec![];
+        for key in ints {
+            values.push(*table.get::<i32>(&key).await.unwrap());
+        }
+        assert_eq!(values, vec![10, 12, 14, 3]);
+    });
+}
+
+#[test]
+fn query_keys_skips_missing_and_repeated_keys() {
+    run(async {
+        let table = TestTable::default();
+        let keys = table.insert_auto_multi(0..3).await;
+        table.insert(keys[0], 0.0f32).await;
+        table.insert(keys[2], 2.0f32).await;
+
+        let stale = table.insert_auto(3).await;
+        table.despawn(stale).await;
+
+        let ints = IntFloatRow::query_keys(&table, vec![keys[2], keys[1], stale, keys[2], keys[0]])
+            .await
+            .map(|row| *row.int)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(ints, vec![2, 0]);
+    });
+}
@@ -0,0 +1,113 @@
+//! Checks that the `Table` derive describes every column for reflection.
+
+use std::any::TypeId;
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::Table, reflect::ColumnRegistry, runtime::run, CellError, Column, Key, Table,
+    WriteColumn,
+};
+
+/// Implements neither `Debug` nor `Display`.
+struct Opaque;
+
+#[derive(Default, Borrow, Table)]
+struct TestTable {
+    ints: Column<i32>,
+    #[dense]
+    floats: Column<f32>,
+    #[skip_serde]
+    opaques: Column<Opaque>,
+}
+
+#[test]
+fn registry_lists_columns_in_field_order() {
+    let registry: ColumnRegistry<TestTable> = TestTable::column_registry();
+
+    let fields = registry
+        .iter()
+        .map(|column| column.field)
+        .collect::<Vec<_>>();
+    assert_eq!(fields, vec!["ints", "floats", "opaques"]);
+
+    let floats = registry.get(TypeId::of::<f32>()).unwrap();
+    assert_eq!(floats.field, "floats");
+    assert_eq!(floats.type_name, "f32");
+
+    let opaques = registry.get_by_field("opaques").unwrap();
+    assert!(opaques.debug.is_none());
+    assert!(opaques.display.is_none());
+}
+
+#[test]
+fn registry_renders_cells() {
+    run(async {
+        let table = TestTable::default();
+        let registry = TestTable::column_registry();
+
+        let key = Key::from(0);
+        table.insert(key, 1i32).await;
+        table.insert(key, 2.5f32).await;
+        table.insert(Key::from(1), Opaque).await;
+
+        let columns = registry
+            .columns_of(&table, &key)
+            .map(|column| column.field)
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec!["ints", "floats"]);
+
+        let ints = registry.get_by_field("ints").unwrap();
+        assert_eq!((ints.keys)(&table), Some(vec![key]));
+        assert_eq!((ints.debug.unwrap())(&table, &key), Ok("1".to_string()));
+
+        let floats = registry.get_by_field("floats").unwrap();
+        assert_eq!(
+            (floats.display.unwrap())(&table, &key),
+            Ok("2.5".to_string())
+        );
+        assert_eq!(
+            (floats.display.unwrap())(&table, &Key::from(1)),
+            Err(CellError::Missing(Key::from(1)))
+        );
+
+        let column = WriteColumn::<i32>::new(&table).await;
+        assert_eq!(
+            (ints.debug.unwrap())(&table, &key),
+            Err(CellError::Locked(key))
+        );
+        drop(column);
+    });
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn registry_edits_cells() {
+    run(async {
+        let table = TestTable::default();
+        let registry = TestTable::column_registry();
+
+        let key = Key::from(0);
+        table.insert(key, 1i32).await;
+
+        let ints = registry.get_by_field("ints").unwrap();
+        assert_eq!(
+            (ints.serialize.unwrap())(&table, &key).unwrap().unwrap(),
+            serde_json::json!(1)
+        );
+
+        (ints.deserialize.unwrap())(&table, &key, serde_json::json!(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(*table.get::<i32>(&key).await.unwrap(), 5);
+
+        assert!(
+            (ints.deserialize.unwrap())(&table, &key, serde_json::json!("x"))
+                .unwrap()
+                .is_err()
+        );
+
+        let opaques = registry.get_by_field("opaques").unwrap();
+        assert!(opaques.serialize.is_none());
+        assert!(opaques.deserialize.is_none());
+    });
+}
@@ -0,0 +1,102 @@
+//! Checks that tasks spawned through the selected runtime backend run to completion.
+
+use std::sync::{
+    atomic::{AtomicI32, Ordering},
+    Arc,
+};
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime, Column, KeyAllocator, ReadCell, Table, WriteCell,
+};
+use futures::StreamExt;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable {
+    key_allocator: KeyAllocator,
+
+    ints: Column<i32>,
+}
+
+#[derive(Debug, Row, CommonKeys)]
+struct IntRow<'a> {
+    int: ReadCell<'a, i32>,
+}
+
+#[derive(Debug, Row, CommonKeys)]
+struct IntMutRow<'a> {
+    int: WriteCell<'a, i32>,
+}
+
+#[test]
+fn spawned_tasks_return_their_output() {
+    runtime::run(async {
+        let table = Arc::new(TestTable::default());
+        let key = table.insert_auto(1).await;
+
+        let handle = runtime::spawn({
+            let table = table.clone();
+            async move { *table.get::<i32>(&key).await.unwrap() + 1 }
+        });
+        assert_eq!(handle.await, 2);
+    });
+}
+
+#[test]
+fn par_for_each_visits_every_row() {
+    runtime::run(async {
+        let table = Arc::new(TestTable::default());
+        table.insert_auto_multi(1..=100).await;
+
+        let sum = Arc::new(AtomicI32::new(0));
+        IntRow::par_for_each(table, 4, {
+            let sum = sum.clone();
+            move |row| {
+                sum.fetch_add(*row.int, Ordering::Relaxed);
+            }
+        })
+        .await;
+
+        assert_eq!(sum.load(Ordering::Relaxed), 5050);
+    });
+}
+
+#[test]
+fn par_for_each_writes_every_row_once() {
+    runtime::run(async {
+        let table = Arc::new(TestTable::default());
+        let keys = table.insert_auto_multi(1..=100).await;
+
+        // More tasks than rows leaves the extra tasks without a share
+        for tasks in [0, 3, 200] {
+            IntMutRow::par_for_each(table.clone(), tasks, |mut row| *row.int *= 2).await;
+        }
+
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(*table.get::<i32>(key).await.unwrap(), (i as i32 + 1) * 8);
+        }
+        assert_eq!(
+            table
+                .changed::<i32>(deebs::Tick::default())
+                .collect::<Vec<_>>()
+                .await,
+            keys
+        );
+    });
+}
+
+#[cfg(deebs_backend = "tokio")]
+#[test]
+fn spawn_blocking_threads_may_block() {
+    runtime::run(async {
+        let table = Arc::new(TestTable::default());
+        let key = table.insert_auto(1).await;
+
+        // Blocking threads carry a runtime handle and a task ID, but aren't polled by the scheduler
+        let value = tokio::task::spawn_blocking(move || *table.get_blocking::<i32>(&key).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(value, 1);
+    });
+}
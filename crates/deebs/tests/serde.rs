@@ -0,0 +1,71 @@
+//! Checks that tables survive a serde round trip.
+#![cfg(feature = "serde")]
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Column, KeyAllocator, ReadCell, ReadSingleton, ReadView, Singleton, Table, View,
+};
+
+/// Stands in for a GPU or window handle that can't be serialized.
+#[derive(Debug, Default)]
+struct Handle;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    keys: KeyAllocator,
+
+    frame: Singleton<u64>,
+
+    ints: Column<i32>,
+    #[dense]
+    floats: Column<f32>,
+    #[skip_serde]
+    handles: Column<Handle>,
+
+    int_float_view: View<IntFloatRow<'a>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct IntFloatRow<'a> {
+    int: ReadCell<'a, i32>,
+    float: ReadCell<'a, f32>,
+}
+
+#[test]
+fn table_round_trip() {
+    run(async {
+        let table = TestTable::default();
+        *deebs::WriteSingleton::<u64>::new(&table).await = 7;
+
+        let both = table.insert_auto(1i32).await;
+        table.insert(both, 1.0f32).await;
+        table.insert(both, Handle).await;
+
+        let int_only = table.insert_auto(2i32).await;
+        let freed = table.insert_auto(3i32).await;
+        table.despawn(freed).await;
+
+        let json = serde_json::to_string(&table).unwrap();
+        let loaded = TestTable::load(&mut serde_json::Deserializer::from_str(&json))
+            .await
+            .unwrap();
+
+        assert_eq!(*ReadSingleton::<u64>::new(&loaded).await, 7);
+        assert_eq!(*loaded.get::<i32>(&both).await.unwrap(), 1);
+        assert_eq!(*loaded.get::<f32>(&both).await.unwrap(), 1.0);
+        assert_eq!(*loaded.get::<i32>(&int_only).await.unwrap(), 2);
+        assert!(loaded.get::<Handle>(&both).await.is_err());
+
+        let view = ReadView::new::<_, IntFloatRow>(&loaded).await;
+        assert_eq!(view.iter().copied().collect::<Vec<_>>(), vec![both]);
+        drop(view);
+
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+
+        assert!(loaded.is_stale(&freed));
+        assert_ne!(loaded.next_key(), int_only);
+    });
+}
@@ -0,0 +1,190 @@
+//! Checks that restoring a snapshot returns columns, views and keys to their captured state.
+
+use std::{any::TypeId, collections::BTreeSet};
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Column, CommonKeys, Key, KeyAllocator, ReadCell, Table, View,
+};
+use futures::StreamExt;
+use proptest::prelude::*;
+
+#[derive(Debug)]
+struct Opaque;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    key_allocator: KeyAllocator,
+
+    ints: Column<i32>,
+    #[dense]
+    floats: Column<f32>,
+    #[skip_serde]
+    opaques: Column<Opaque>,
+
+    int_float_view: View<IntFloatRow<'a>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct IntFloatRow<'a> {
+    int: ReadCell<'a, i32>,
+    float: ReadCell<'a, f32>,
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    InsertInt(usize, i32),
+    InsertFloat(usize),
+    RemoveInt(usize),
+    RemoveFloat(usize),
+    WriteInt(usize, i32),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let index = 0..8usize;
+    prop_oneof![
+        (index.clone(), any::<i32>()).prop_map(|(index, value)| Op::InsertInt(index, value)),
+        index.clone().prop_map(Op::InsertFloat),
+        index.clone().prop_map(Op::RemoveInt),
+        index.clone().prop_map(Op::RemoveFloat),
+        (index, any::<i32>()).prop_map(|(index, value)| Op::WriteInt(index, value)),
+    ]
+}
+
+async fn apply(table: &TestTable<'_>, op: Op) {
+    match op {
+        Op::InsertInt(index, value) => table.insert(Key::from(index), value).await,
+        Op::InsertFloat(index) => table.insert(Key::from(index), index as f32).await,
+        Op::RemoveInt(index) => table.remove::<i32>(Key::from(index)).await,
+        Op::RemoveFloat(index) => table.remove::<f32>(Key::from(index)).await,
+        Op::WriteInt(index, value) => {
+            if let Ok(mut cell) = table.get_mut::<i32>(&Key::from(index)).await {
+                *cell = value;
+            }
+        }
+    }
+}
+
+async fn cells<T>(table: &TestTable<'_>) -> Vec<(Key, T)>
+where
+    T: Copy + Send + Sync + 'static,
+    for<'a> TestTable<'a>: deebs::BorrowColumn<T>,
+{
+    let keys = table.keys::<T>().await.collect::<Vec<_>>().await;
+    let mut cells = vec![];
+    for key in keys {
+        cells.push((key, *table.get::<T>(&key).await.unwrap()));
+    }
+    cells
+}
+
+proptest! {
+    #[test]
+    fn restore_matches_captured_state(
+        before in prop::collection::vec(op(), 0..32),
+        after in prop::collection::vec(op(), 0..32),
+    ) {
+        run(async {
+            let table = TestTable::default();
+            for op in before {
+                apply(&table, op).await;
+            }
+
+            let ints = cells::<i32>(&table).await;
+            let floats = cells::<f32>(&table).await;
+            let snapshot = table.snapshot(|_| true).await;
+
+            for op in after {
+                apply(&table, op).await;
+            }
+
+            table.restore(snapshot).await;
+
+            assert_eq!(cells::<i32>(&table).await, ints);
+            assert_eq!(cells::<f32>(&table).await, floats);
+
+            let incremental = table.int_float_view.keys.read().await.clone();
+            let full = IntFloatRow::common_keys(&table)
+                .await
+                .collect::<BTreeSet<_>>()
+                .await;
+            assert_eq!(incremental, full);
+        });
+    }
+}
+
+#[test]
+fn unchanged_columns_are_shared() {
+    run(async {
+        let table = TestTable::default();
+        table.insert(Key::from(0), 1i32).await;
+        table.insert(Key::from(0), 1.0f32).await;
+
+        let first = table.snapshot(|_| true).await;
+        table.insert(Key::from(1), 2.0f32).await;
+        let second = table.snapshot(|_| true).await;
+
+        let ints = |snapshot: &deebs::Snapshot| snapshot.cells::<i32>().unwrap().as_ptr();
+        let floats = |snapshot: &deebs::Snapshot| snapshot.cells::<f32>().unwrap().as_ptr();
+
+        assert_eq!(ints(&first), ints(&second));
+        assert_ne!(floats(&first), floats(&second));
+
+        // A restored column matches its snapshot, so the next snapshot shares it too
+        table.restore(first.clone()).await;
+        let third = table.snapshot(|_| true).await;
+        assert_eq!(floats(&first), floats(&third));
+
+        // Writing through a cell guard invalidates the shared copy
+        *table.get_mut::<i32>(&Key::from(0)).await.unwrap() = 2;
+        let fourth = table.snapshot(|_| true).await;
+        assert_ne!(ints(&third), ints(&fourth));
+        assert_eq!(fourth.cells::<i32>().unwrap(), &[(Key::from(0), 2)]);
+    });
+}
+
+#[test]
+fn filter_selects_clone_columns() {
+    run(async {
+        let table = TestTable::default();
+        table.insert(Key::from(0), 1i32).await;
+        table.insert(Key::from(0), 1.0f32).await;
+        table.insert(Key::from(0), Opaque).await;
+
+        let snapshot = table
+            .snapshot(|column| column.type_id != TypeId::of::<f32>())
+            .await;
+
+        assert!(snapshot.contains(TypeId::of::<i32>()));
+        assert!(!snapshot.contains(TypeId::of::<f32>()));
+        assert!(!snapshot.contains(TypeId::of::<Opaque>()));
+
+        table.remove::<i32>(Key::from(0)).await;
+        table.remove::<f32>(Key::from(0)).await;
+        table.restore(snapshot).await;
+
+        assert_eq!(*table.get::<i32>(&Key::from(0)).await.unwrap(), 1);
+        assert!(table.get::<f32>(&Key::from(0)).await.is_err());
+        assert!(table.get::<Opaque>(&Key::from(0)).await.is_ok());
+    });
+}
+
+#[test]
+fn restore_revives_despawned_keys() {
+    run(async {
+        let table = TestTable::default();
+        let key = table.insert_auto(1i32).await;
+        let snapshot = table.snapshot(|_| true).await;
+
+        table.despawn(key).await;
+        assert!(table.is_stale(&key));
+
+        table.restore(snapshot).await;
+        assert!(table.is_alive(&key));
+        assert_eq!(*table.get::<i32>(&key).await.unwrap(), 1);
+        assert_ne!(table.next_key(), key);
+    });
+}
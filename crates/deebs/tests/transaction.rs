@@ -0,0 +1,123 @@
+//! Checks that transactions commit atomically and roll back on error or panic.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Column, KeyAllocator, ReadCell, Table, Tick, View,
+};
+use futures::StreamExt;
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct IntFloatRow<'a> {
+    int: ReadCell<'a, i32>,
+    float: ReadCell<'a, f32>,
+}
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    key_allocator: KeyAllocator,
+
+    ints: Column<i32>,
+    floats: Column<f32>,
+
+    int_float_view: View<IntFloatRow<'a>>,
+}
+
+async fn view_keys(table: &TestTable<'_>) -> Vec<deebs::Key> {
+    table.int_float_view.keys().await.collect().await
+}
+
+#[test]
+fn committed_transactions_apply_every_write() {
+    run(async {
+        let table = TestTable::default();
+        let key = table.next_key();
+
+        let tick = Tick::now();
+        let result = table
+            .transaction::<(i32, f32), _, _, ()>(|(ints, floats)| {
+                ints.insert(key, 1);
+                floats.insert(key, 2.0);
+                Ok("done")
+            })
+            .await;
+        assert_eq!(result, Ok("done"));
+
+        assert_eq!(*table.get::<i32>(&key).await.unwrap(), 1);
+        assert_eq!(*table.get::<f32>(&key).await.unwrap(), 2.0);
+        assert_eq!(view_keys(&table).await, vec![key]);
+        assert_eq!(
+            table.added::<i32>(tick).collect::<Vec<_>>().await,
+            vec![key]
+        );
+    });
+}
+
+#[test]
+fn failed_transactions_roll_back() {
+    run(async {
+        let table = TestTable::default();
+        let key = table.insert_auto(1).await;
+        table.insert(key, 2.0f32).await;
+        let other = table.next_key();
+
+        let tick = Tick::now();
+        let result = table
+            .transaction::<(i32, f32), _, (), _>(|(ints, floats)| {
+                ints.modify(&key, |int| *int = 10);
+                ints.insert(other, 3);
+                floats.remove(&key);
+                Err("failed")
+            })
+            .await;
+        assert_eq!(result, Err("failed"));
+
+        assert_eq!(*table.get::<i32>(&key).await.unwrap(), 1);
+        assert_eq!(*table.get::<f32>(&key).await.unwrap(), 2.0);
+        assert!(table.get::<i32>(&other).await.is_err());
+        assert_eq!(view_keys(&table).await, vec![key]);
+
+        // Nothing is recorded for rolled back writes
+        assert!(table
+            .changed::<i32>(tick)
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+        assert!(table
+            .added::<i32>(tick)
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+    });
+}
+
+#[test]
+fn panicking_transactions_roll_back() {
+    let table = TestTable::default();
+    let key = run(table.insert_auto(1));
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        run(
+            table.transaction::<(i32, f32), _, (), ()>(|(ints, floats)| {
+                ints.insert(key, 10);
+                floats.insert(key, 2.0);
+                panic!("transaction panicked");
+            }),
+        )
+    }));
+    assert!(result.is_err());
+
+    run(async {
+        assert_eq!(*table.get::<i32>(&key).await.unwrap(), 1);
+        assert!(table.get::<f32>(&key).await.is_err());
+        assert!(view_keys(&table).await.is_empty());
+
+        // The columns were released, so the table is still usable
+        table.insert(key, 2.0f32).await;
+        assert_eq!(view_keys(&table).await, vec![key]);
+    });
+}
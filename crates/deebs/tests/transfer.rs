@@ -0,0 +1,294 @@
+//! Checks that rows move and copy between tables, rewriting the keys they hold.
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Children, Column, Key, KeyAllocator, KeyMap, MapKeys, Parent, ReadCell, Table, View,
+};
+use futures::StreamExt;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Position(f32);
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct PositionRow<'a> {
+    position: ReadCell<'a, Position>,
+    name: Option<ReadCell<'a, String>>,
+}
+
+/// Rows holding types without `Clone` can still be moved
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Handle(u32);
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct HandleRow<'a> {
+    handle: ReadCell<'a, Handle>,
+}
+
+/// Refers to another entity, which is rewritten on transfer
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Target(Option<Key>);
+
+impl MapKeys for Target {
+    fn held_keys(&self) -> Vec<Key> {
+        self.0.held_keys()
+    }
+
+    fn map_keys(&mut self, keys: &KeyMap) -> bool {
+        self.0.map_keys(keys)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct TargetRow<'a> {
+    target: ReadCell<'a, Target>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct HierarchyRow<'a> {
+    parent: Option<ReadCell<'a, Parent>>,
+    children: Option<ReadCell<'a, Children>>,
+}
+
+#[derive(Debug, Default, Borrow, Table)]
+struct World<'a> {
+    key_allocator: KeyAllocator,
+
+    positions: Column<Position>,
+    names: Column<String>,
+    parents: Column<Parent>,
+    children: Column<Children>,
+    handles: Column<Handle>,
+    targets: Column<Target>,
+
+    position_rows: View<PositionRow<'a>>,
+}
+
+#[test]
+fn transfer_moves_cells_and_views() {
+    run(async {
+        let src = World::default();
+        let dst = World::default();
+        dst.insert_auto(Position(-1.0)).await;
+
+        let key = src.insert_auto(Position(1.0)).await;
+        src.insert(key, String::from("moved")).await;
+        src.insert(key, Handle(7)).await;
+
+        let mut keys = KeyMap::new();
+        let dst_key = PositionRow::transfer(&src, &dst, key, &mut keys)
+            .await
+            .unwrap();
+        assert_eq!(
+            HandleRow::transfer(&src, &dst, key, &mut keys).await,
+            Some(dst_key)
+        );
+        assert_ne!(dst_key, key);
+        assert_eq!(keys.get(&key), Some(dst_key));
+
+        assert!(src.get::<Position>(&key).await.is_err());
+        assert!(src.get::<String>(&key).await.is_err());
+        assert!(src
+            .position_rows
+            .keys()
+            .await
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+
+        assert_eq!(*dst.get::<Position>(&dst_key).await.unwrap(), Position(1.0));
+        assert_eq!(*dst.get::<String>(&dst_key).await.unwrap(), "moved");
+        assert_eq!(*dst.get::<Handle>(&dst_key).await.unwrap(), Handle(7));
+        assert_eq!(
+            dst.position_rows
+                .keys()
+                .await
+                .collect::<Vec<_>>()
+                .await
+                .len(),
+            2
+        );
+    });
+}
+
+#[test]
+fn rows_of_one_entity_share_a_destination_key() {
+    run(async {
+        let src = World::default();
+        let dst = World::default();
+
+        let parent = src.insert_auto(Position(0.0)).await;
+        let child = src.insert_auto(Position(1.0)).await;
+        src.set_parent(child, parent).await.unwrap();
+
+        let mut keys = KeyMap::new();
+        for key in [parent, child].iter().copied() {
+            let position_key = PositionRow::transfer(&src, &dst, key, &mut keys).await;
+            let hierarchy_key = HierarchyRow::transfer(&src, &dst, key, &mut keys).await;
+            assert_eq!(position_key, hierarchy_key);
+        }
+        assert_eq!(keys.len(), 2);
+
+        // Moved hierarchy cells still hold source keys until they are mapped
+        let dst_parent = keys.get(&parent).unwrap();
+        let dst_child = keys.get(&child).unwrap();
+        assert_eq!(dst.get::<Parent>(&dst_child).await.unwrap().key(), parent);
+
+        assert!(HierarchyRow::map_keys(&dst, &keys).await.is_empty());
+        assert_eq!(
+            dst.get::<Parent>(&dst_child).await.unwrap().key(),
+            dst_parent
+        );
+        assert_eq!(
+            &**dst.get::<Children>(&dst_parent).await.unwrap(),
+            &[dst_child][..]
+        );
+    });
+}
+
+#[test]
+fn transfer_clone_copies_cells() {
+    run(async {
+        let src = World::default();
+        let dst = World::default();
+
+        let parent = src.insert_auto(Position(0.0)).await;
+        let child = src.insert_auto(Position(1.0)).await;
+        src.set_parent(child, parent).await.unwrap();
+
+        let mut keys = KeyMap::new();
+        for key in [parent, child].iter().copied() {
+            PositionRow::transfer_clone(&src, &dst, key, &mut keys).await;
+            HierarchyRow::transfer_clone(&src, &dst, key, &mut keys).await;
+        }
+        assert!(HierarchyRow::map_keys(&dst, &keys).await.is_empty());
+
+        // The source keeps its cells and hierarchy
+        assert_eq!(*src.get::<Position>(&child).await.unwrap(), Position(1.0));
+        assert_eq!(src.get::<Parent>(&child).await.unwrap().key(), parent);
+
+        let dst_parent = keys.get(&parent).unwrap();
+        let dst_child = keys.get(&child).unwrap();
+        assert_eq!(
+            *dst.get::<Position>(&dst_child).await.unwrap(),
+            Position(1.0)
+        );
+        assert_eq!(
+            dst.get::<Parent>(&dst_child).await.unwrap().key(),
+            dst_parent
+        );
+
+        // Despawning in the destination follows the mapped hierarchy
+        // and leaves the source untouched
+        dst.despawn(dst_parent).await;
+        assert!(!dst.is_alive(&dst_child));
+        assert!(src.is_alive(&child));
+
+        // Mapping back through the inverse recovers the source keys
+        let inverse = keys.inverse();
+        assert_eq!(inverse.get(&dst_child), Some(child));
+    });
+}
+
+#[test]
+fn transfer_skips_stale_and_empty_keys() {
+    run(async {
+        let src = World::default();
+        let dst = World::default();
+
+        let empty = src.next_key();
+        let stale = src.insert_auto(Position(0.0)).await;
+        src.despawn(stale).await;
+
+        let mut keys = KeyMap::new();
+        assert_eq!(
+            PositionRow::transfer(&src, &dst, empty, &mut keys).await,
+            None
+        );
+        assert_eq!(
+            PositionRow::transfer_clone(&src, &dst, stale, &mut keys).await,
+            None
+        );
+        assert!(keys.is_empty());
+
+        // Nothing was allocated in the destination
+        let next = dst.next_key();
+        assert_eq!((next.index(), next.generation()), (0, 0));
+    });
+}
+
+#[test]
+fn map_keys_drops_references_outside_the_transfer() {
+    run(async {
+        let src = World::default();
+        let dst = World::default();
+
+        let parent = src.insert_auto(Position(0.0)).await;
+        let child = src.insert_auto(Position(1.0)).await;
+        let grandchild = src.insert_auto(Position(2.0)).await;
+        src.set_parent(child, parent).await.unwrap();
+        src.set_parent(grandchild, child).await.unwrap();
+
+        // Only the child and grandchild move, leaving the child's parent behind
+        let mut keys = KeyMap::new();
+        for key in [child, grandchild].iter().copied() {
+            PositionRow::transfer(&src, &dst, key, &mut keys).await;
+            HierarchyRow::transfer(&src, &dst, key, &mut keys).await;
+        }
+        let dst_child = keys.get(&child).unwrap();
+        let dst_grandchild = keys.get(&grandchild).unwrap();
+
+        assert_eq!(HierarchyRow::map_keys(&dst, &keys).await, vec![parent]);
+
+        // The dangling parent is removed rather than aliasing a destination key
+        assert!(dst.get::<Parent>(&dst_child).await.is_err());
+        assert_eq!(
+            dst.get::<Parent>(&dst_grandchild).await.unwrap().key(),
+            dst_child
+        );
+    });
+}
+
+#[test]
+fn map_keys_skips_cells_without_keys_to_rewrite() {
+    run(async {
+        let src = World::default();
+        let dst = World::default();
+
+        // Offset the destination's keys so that every held key needs rewriting
+        dst.insert_auto(Position(0.0)).await;
+
+        let target = src.insert_auto(Target(None)).await;
+        let follower = src.insert_auto(Target(Some(target))).await;
+
+        let mut keys = KeyMap::new();
+        for key in [target, follower].iter().copied() {
+            TargetRow::transfer(&src, &dst, key, &mut keys).await;
+        }
+        let dst_target = keys.get(&target).unwrap();
+        let dst_follower = keys.get(&follower).unwrap();
+
+        let tick = dst.targets.changes().latest();
+        assert!(TargetRow::map_keys(&dst, &keys).await.is_empty());
+        assert_eq!(
+            *dst.get::<Target>(&dst_follower).await.unwrap(),
+            Target(Some(dst_target))
+        );
+        assert_eq!(
+            dst.targets
+                .changes()
+                .changed_since(tick)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![dst_follower]
+        );
+    });
+}
@@ -0,0 +1,7 @@
+//! Checks that derives report malformed input as spanned compile errors.
+
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+use deebs::{macros::Row, ReadCell};
+
+#[derive(Row)]
+struct IntRow<'a> {
+    int: ReadCell<i32>,
+    float: ReadCell<'a, f32>,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use deebs::{macros::Row, ReadCell};
+
+#[derive(Row)]
+struct IntRow<T: 'static> {
+    int: ReadCell<'static, T>,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use deebs::{macros::Row, ReadCell};
+
+#[derive(Row)]
+struct IntRow {
+    int: ReadCell<'static, i32>,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use deebs::{macros::Remove, With};
+
+#[derive(Remove)]
+struct IntRow<'a> {
+    with_int: With<&'a i32>,
+}
+
+fn main() {}
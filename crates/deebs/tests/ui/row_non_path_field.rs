@@ -0,0 +1,8 @@
+use deebs::macros::CommonKeys;
+
+#[derive(CommonKeys)]
+struct IntRow<'a> {
+    int: &'a i32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use deebs::{macros::Map, ReadCell};
+
+#[derive(Map)]
+struct IntRow<'a> {
+    int: ReadCell<'a, i32>,
+    float: Option<ReadCell<'a, f32>, f32>,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use deebs::{macros::Insert, ReadCell};
+
+#[derive(Insert)]
+struct IntRow<'a>(ReadCell<'a, i32>);
+
+fn main() {}
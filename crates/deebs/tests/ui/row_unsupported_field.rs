@@ -0,0 +1,9 @@
+use deebs::{macros::Row, ReadCell};
+
+#[derive(Row)]
+struct IntRow<'a> {
+    int: ReadCell<'a, i32>,
+    count: usize,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use deebs::macros::Table;
+
+#[derive(Table)]
+struct TestTable {
+    ints: deebs::Column,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use deebs::{macros::Table, Column, Singleton};
+
+#[derive(Table)]
+struct TestTable {
+    ints: Column<i32>,
+    #[dense]
+    count: Singleton<usize>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use deebs::{macros::Table, Column};
+
+#[derive(Table)]
+struct TestTable {
+    #[index(btree)]
+    ints: Column<i32>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use deebs::{macros::Table, Column};
+
+#[derive(Table)]
+struct TestTable {
+    #[index = "hash"]
+    ints: Column<i32>,
+}
+
+fn main() {}
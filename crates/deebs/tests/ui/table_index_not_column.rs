@@ -0,0 +1,10 @@
+use deebs::{macros::Table, Column, KeyAllocator};
+
+#[derive(Table)]
+struct TestTable {
+    #[index]
+    key_allocator: KeyAllocator,
+    ints: Column<i32>,
+}
+
+fn main() {}
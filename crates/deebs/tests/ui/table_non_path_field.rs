@@ -0,0 +1,8 @@
+use deebs::{macros::Table, Column};
+
+#[derive(Table)]
+struct TestTable {
+    ints: (Column<i32>, Column<f32>),
+}
+
+fn main() {}
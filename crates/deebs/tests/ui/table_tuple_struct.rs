@@ -0,0 +1,6 @@
+use deebs::{macros::Table, Column};
+
+#[derive(Table)]
+struct TestTable(Column<i32>);
+
+fn main() {}
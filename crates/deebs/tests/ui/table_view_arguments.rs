@@ -0,0 +1,9 @@
+use deebs::{macros::Table, Column, View};
+
+#[derive(Table)]
+struct TestTable {
+    ints: Column<i32>,
+    view: View<'static>,
+}
+
+fn main() {}
@@ -0,0 +1,120 @@
+//! Checks that incrementally-maintained views match a full recomputation.
+
+use std::collections::BTreeSet;
+
+use borrow_derive::Borrow;
+use deebs::{
+    macros::{CommonKeys, Row, Table},
+    runtime::run,
+    Column, CommonKeys, Key, ReadCell, Table, View, With, Without,
+};
+use futures::StreamExt;
+use proptest::prelude::*;
+
+#[derive(Debug, Default, Borrow, Table)]
+struct TestTable<'a> {
+    ints: Column<i32>,
+    #[dense]
+    floats: Column<f32>,
+    bools: Column<bool>,
+
+    int_float_view: View<IntFloatRow<'a>>,
+    optional_view: View<OptionalRow<'a>>,
+    filtered_view: View<FilteredRow<'a>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct IntFloatRow<'a> {
+    int: ReadCell<'a, i32>,
+    float: ReadCell<'a, f32>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct OptionalRow<'a> {
+    int: Option<ReadCell<'a, i32>>,
+    float: Option<ReadCell<'a, f32>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Row, CommonKeys)]
+struct FilteredRow<'a> {
+    int: ReadCell<'a, i32>,
+    with_float: With<f32>,
+    without_bool: Without<bool>,
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    InsertInt(usize),
+    InsertFloat(usize),
+    InsertBool(usize),
+    RemoveInt(usize),
+    RemoveFloat(usize),
+    RemoveBool(usize),
+    Despawn(usize),
+    Transaction(usize, usize),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let index = 0..8usize;
+    prop_oneof![
+        index.clone().prop_map(Op::InsertInt),
+        index.clone().prop_map(Op::InsertFloat),
+        index.clone().prop_map(Op::InsertBool),
+        index.clone().prop_map(Op::RemoveInt),
+        index.clone().prop_map(Op::RemoveFloat),
+        index.clone().prop_map(Op::RemoveBool),
+        index.clone().prop_map(Op::Despawn),
+        (index.clone(), index).prop_map(|(lhs, rhs)| Op::Transaction(lhs, rhs)),
+    ]
+}
+
+async fn apply(table: &TestTable<'_>, op: Op) {
+    match op {
+        Op::InsertInt(index) => table.insert(Key::from(index), index as i32).await,
+        Op::InsertFloat(index) => table.insert(Key::from(index), index as f32).await,
+        Op::InsertBool(index) => table.insert(Key::from(index), true).await,
+        Op::RemoveInt(index) => table.remove::<i32>(Key::from(index)).await,
+        Op::RemoveFloat(index) => table.remove::<f32>(Key::from(index)).await,
+        Op::RemoveBool(index) => table.remove::<bool>(Key::from(index)).await,
+        Op::Despawn(index) => {
+            table.despawn(Key::from(index)).await;
+        }
+        Op::Transaction(insert, remove) => {
+            table
+                .transaction::<(i32, f32), _, _, ()>(|(ints, floats)| {
+                    ints.insert(Key::from(insert), insert as i32);
+                    floats.remove(&Key::from(remove));
+                    Ok(())
+                })
+                .await
+                .unwrap();
+        }
+    }
+}
+
+async fn assert_view<'a, R>(table: &'a TestTable<'a>, view: &View<R>)
+where
+    R: CommonKeys<TestTable<'a>>,
+{
+    let incremental = view.keys.read().await.clone();
+    let full = R::common_keys(table).await.collect::<BTreeSet<_>>().await;
+    assert_eq!(incremental, full);
+}
+
+proptest! {
+    #[test]
+    fn incremental_view_matches_full_recompute(ops in prop::collection::vec(op(), 0..64)) {
+        run(async {
+            let table = TestTable::default();
+            for op in ops {
+                apply(&table, op).await;
+                assert_view(&table, &table.int_float_view).await;
+                assert_view(&table, &table.optional_view).await;
+                assert_view(&table, &table.filtered_view).await;
+            }
+        });
+    }
+}
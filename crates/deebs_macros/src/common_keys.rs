@@ -1,34 +1,49 @@
 use quote::quote;
 use syn::ItemStruct;
 
-use crate::RowInput;
+use crate::{acquire_in_lock_order, RowInput};
 
-pub fn impl_common_keys(input: ItemStruct) -> proc_macro::TokenStream {
+pub fn impl_common_keys(input: ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
     let RowInput {
         ident,
         generics,
         where_predicates,
-        all_view_names: _,
         concrete_view_names: _,
         concrete_view_names_plural,
-        concrete_view_tys,
+        concrete_view_tys: _,
         concrete_view_inner_tys,
+        concrete_view_boxed: _,
         option_view_names: _,
         option_view_names_plural,
-        option_view_tys,
+        option_view_tys: _,
         option_view_inner_tys,
-    } = RowInput::new(input);
-
-    assert!(
-        !concrete_view_tys.is_empty() || !option_view_tys.is_empty(),
-        "Row struct must have at least one view member."
-    );
+        option_view_boxed: _,
+        filter_view_names: _,
+        filter_view_names_plural,
+        filter_view_tys,
+    } = RowInput::new(input)?;
 
     let generic_lt = &generics[0];
     let generic_types = &generics[1..];
 
     let _insert_ty = quote! { (#(#concrete_view_inner_tys,)* #(Option<#option_view_inner_tys>,)*) };
 
+    let acquire = acquire_in_lock_order(
+        &concrete_view_names_plural
+            .iter()
+            .chain(option_view_names_plural.iter())
+            .collect::<Vec<_>>(),
+        &concrete_view_inner_tys
+            .iter()
+            .chain(option_view_inner_tys.iter())
+            .collect::<Vec<_>>(),
+        &concrete_view_inner_tys
+            .iter()
+            .chain(option_view_inner_tys.iter())
+            .map(|inner_ty| quote!(deebs::ReadColumn::<#inner_ty>::new(table)))
+            .collect::<Vec<_>>(),
+    );
+
     let tokens = quote! {
         #[async_trait::async_trait]
         impl<#(#generics,)* Table> deebs::CommonKeys<Table> for #ident<#(#generics,)*>
@@ -37,13 +52,26 @@ pub fn impl_common_keys(input: ItemStruct) -> proc_macro::TokenStream {
                 #generic_types: Send + Sync + #generic_lt,
             )*
             Table: #(deebs::BorrowColumn<#concrete_view_inner_tys> +)* #(deebs::BorrowColumn<#option_view_inner_tys> +)* Send + Sync,
+            #(
+                #filter_view_tys: deebs::Filter<Table>,
+            )*
             #(
                 #where_predicates,
             )*
         {
-            async fn common_keys(table: &Table) -> async_std::stream::FromIter<std::collections::btree_set::IntoIter<deebs::Key>> {
-                let (#(#concrete_view_names_plural,)*) = futures::join!(#(deebs::ReadColumn::<#concrete_view_inner_tys>::new(table),)*);
-                let (#(#option_view_names_plural,)*) = futures::join!(#(deebs::ReadColumn::<#option_view_inner_tys>::new(table),)*);
+            async fn common_keys(table: &Table) -> deebs::runtime::FromIter<std::collections::btree_set::IntoIter<deebs::Key>> {
+                <#ident<#(#generics),*> as deebs::CommonKeys<Table>>::common_keys_since(table, deebs::Tick::default()).await
+            }
+
+            async fn common_keys_since(table: &Table, tick: deebs::Tick) -> deebs::runtime::FromIter<std::collections::btree_set::IntoIter<deebs::Key>> {
+                #(
+                    let #filter_view_names_plural = <#filter_view_tys as deebs::Filter<Table>>::filter_keys(table, tick).await;
+                )*
+
+                #acquire
+
+                let (#(#concrete_view_names_plural,)*) = (#(#concrete_view_names_plural.unwrap(),)*);
+                let (#(#option_view_names_plural,)*) = (#(#option_view_names_plural.unwrap(),)*);
 
                 let mut keys: std::collections::BTreeSet<deebs::Key> = Default::default();
 
@@ -63,16 +91,60 @@ pub fn impl_common_keys(input: ItemStruct) -> proc_macro::TokenStream {
                     #(
                         let contains = contains & #concrete_view_names_plural.contains_key(key);
                     )*
+                    #(
+                        let contains = contains & #filter_view_names_plural.contains(key);
+                    )*
 
                     if contains {
                         keys.insert(*key);
                     }
                 }
 
-                async_std::stream::from_iter(keys.into_iter())
+                deebs::runtime::from_iter(keys.into_iter())
+            }
+
+            async fn common_keys_in(table: &Table, keys_in: &[deebs::Key]) -> std::collections::BTreeSet<deebs::Key> {
+                <#ident<#(#generics),*> as deebs::CommonKeys<Table>>::common_keys_in_since(table, keys_in, deebs::Tick::default()).await
+            }
+
+            async fn common_keys_in_since(table: &Table, keys_in: &[deebs::Key], tick: deebs::Tick) -> std::collections::BTreeSet<deebs::Key> {
+                #(
+                    let #filter_view_names_plural = <#filter_view_tys as deebs::Filter<Table>>::filter_keys(table, tick).await;
+                )*
+
+                #acquire
+
+                let (#(#concrete_view_names_plural,)*) = (#(#concrete_view_names_plural.unwrap(),)*);
+                let (#(#option_view_names_plural,)*) = (#(#option_view_names_plural.unwrap(),)*);
+
+                let mut keys: std::collections::BTreeSet<deebs::Key> = Default::default();
+
+                for key in keys_in {
+                    let contains = true;
+                    #(
+                        let contains = contains & #concrete_view_names_plural.contains_key(key);
+                    )*
+                    #(
+                        let contains = contains & #filter_view_names_plural.contains(key);
+                    )*
+
+                    let any = false;
+                    #(
+                        let any = any | #concrete_view_names_plural.contains_key(key);
+                    )*
+                    #(
+                        let any = any | #option_view_names_plural.contains_key(key);
+                    )*
+
+                    if contains & any {
+                        keys.insert(*key);
+                    }
+                }
+
+                keys
             }
         }
     };
 
-    tokens.into()
+    Ok(tokens)
 }
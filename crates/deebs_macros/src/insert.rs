@@ -3,31 +3,31 @@ use syn::ItemStruct;
 
 use crate::RowInput;
 
-pub fn impl_insert(input: ItemStruct) -> proc_macro::TokenStream {
+pub fn impl_insert(input: ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
     let RowInput {
         ident,
         generics,
         where_predicates,
-        all_view_names: _,
         concrete_view_names,
         concrete_view_names_plural,
-        concrete_view_tys,
+        concrete_view_tys: _,
         concrete_view_inner_tys,
+        concrete_view_boxed: _,
         option_view_names,
         option_view_names_plural,
-        option_view_tys,
+        option_view_tys: _,
         option_view_inner_tys,
-    } = RowInput::new(input);
-
-    assert!(
-        !concrete_view_tys.is_empty() || !option_view_tys.is_empty(),
-        "Row struct must have at least one view member."
-    );
+        option_view_boxed: _,
+        filter_view_names: _,
+        filter_view_names_plural: _,
+        filter_view_tys: _,
+    } = RowInput::new(input)?;
 
     let generic_lt = &generics[0];
     let generic_types = &generics[1..];
 
     let insert_ty = quote! { (#(#concrete_view_inner_tys,)* #(Option<#option_view_inner_tys>,)*) };
+    let transaction_ty = quote! { (#(#concrete_view_inner_tys,)* #(#option_view_inner_tys,)*) };
 
     let tokens = quote! {
         #[async_trait::async_trait]
@@ -44,48 +44,26 @@ pub fn impl_insert(input: ItemStruct) -> proc_macro::TokenStream {
             type Insert = #insert_ty;
 
             async fn insert(table: &Table, key: deebs::Key, (#(#concrete_view_names,)* #(#option_view_names,)*): #insert_ty) where Table: deebs::Table {
-                {
-                    futures::join!(
-                        #(
-                            table.insert::<#concrete_view_inner_tys>(key, #concrete_view_names),
-                        )*
-                    );
+                deebs::Table::transaction::<#transaction_ty, _, _, _>(table, move |(#(#concrete_view_names_plural,)* #(#option_view_names_plural,)*)| {
+                    #(
+                        #concrete_view_names_plural.insert(key, #concrete_view_names);
+                    )*
 
-                    futures::join!(
-                        #(
-                            async move {
-                                if let Some(#option_view_names) = #option_view_names {
-                                    table.insert::<#option_view_inner_tys>(key, #option_view_names.into()).await;
-                                }
-                            },
-                        )*
-                    );
-                }
+                    #(
+                        if let Some(#option_view_names) = #option_view_names {
+                            #option_view_names_plural.insert(key, #option_view_names);
+                        }
+                    )*
 
-                deebs::Table::update_views(table, &<#ident<#(#generics),*> as deebs::Row<Table>>::inner_types()).await;
+                    Ok::<_, std::convert::Infallible>(())
+                })
+                .await
+                .unwrap_or_else(|never| match never {})
             }
 
-            async fn insert_auto(table: &Table, (#(#concrete_view_names,)* #(#option_view_names,)*): #insert_ty) -> deebs::Key where Table: deebs::Table + std::borrow::Borrow<std::sync::atomic::AtomicUsize> {
+            async fn insert_auto(table: &Table, (#(#concrete_view_names,)* #(#option_view_names,)*): #insert_ty) -> deebs::Key where Table: deebs::Table {
                 let key = table.next_key();
-                {
-                    futures::join!(
-                        #(
-                            table.insert::<#concrete_view_inner_tys>(key, #concrete_view_names),
-                        )*
-                    );
-
-                    futures::join!(
-                        #(
-                            async move {
-                                if let Some(#option_view_names) = #option_view_names {
-                                    table.insert::<#option_view_inner_tys>(key, #option_view_names.into()).await;
-                                }
-                            },
-                        )*
-                    );
-                }
-
-                deebs::Table::update_views(table, &<#ident<#(#generics),*> as deebs::Row<Table>>::inner_types()).await;
+                <#ident<#(#generics),*> as deebs::Insert<Table>>::insert(table, key, (#(#concrete_view_names,)* #(#option_view_names,)*)).await;
                 key
             }
 
@@ -94,79 +72,44 @@ pub fn impl_insert(input: ItemStruct) -> proc_macro::TokenStream {
                     Table: deebs::Table,
                     RowIterator: Iterator<Item = (deebs::Key, #insert_ty)> + Send
             {
-                {
+                deebs::Table::transaction::<#transaction_ty, _, _, _>(table, move |(#(#concrete_view_names_plural,)* #(#option_view_names_plural,)*)| {
                     let (lower, upper) = rows.size_hint();
                     let length = upper.unwrap_or(lower);
 
                     #(
-                        let mut #concrete_view_names_plural = deebs::WriteColumn::<#concrete_view_inner_tys>::new(std::ops::Deref::deref(&table)).await;
                         #concrete_view_names_plural.reserve(length);
                     )*
 
-                    #(
-                        let mut #option_view_names_plural = deebs::WriteColumn::<#option_view_inner_tys>::new(std::ops::Deref::deref(&table)).await;
-                    )*
-
-                    let mut rows = async_std::stream::from_iter(rows);
-                    while let Some((key, (#(#concrete_view_names,)* #(#option_view_names,)*))) = futures::StreamExt::next(&mut rows).await {
+                    for (key, (#(#concrete_view_names,)* #(#option_view_names,)*)) in rows {
                         #(
-                            #concrete_view_names_plural.insert(key, #concrete_view_names.into());
+                            #concrete_view_names_plural.insert(key, #concrete_view_names);
                         )*
 
                         #(
                             if let Some(#option_view_names) = #option_view_names {
-                                #option_view_names_plural.insert(key, #option_view_names.into());
+                                #option_view_names_plural.insert(key, #option_view_names);
                             }
                         )*
                     }
-                }
 
-                deebs::Table::update_views(table, &<#ident<#(#generics),*> as deebs::Row<Table>>::inner_types()).await;
+                    Ok::<_, std::convert::Infallible>(())
+                })
+                .await
+                .unwrap_or_else(|never| match never {})
             }
 
             async fn insert_auto_multi<RowIterator>(table: &Table, rows: RowIterator) -> Vec<deebs::Key>
                 where
-                    Table: deebs::Table + std::borrow::Borrow<std::sync::atomic::AtomicUsize>,
+                    Table: deebs::Table,
                     RowIterator: Iterator<Item = #insert_ty> + Send
             {
-                let mut keys = vec![];
-
-                {
-                    let (lower, upper) = rows.size_hint();
-                    let length = upper.unwrap_or(lower);
-
-                    #(
-                        let mut #concrete_view_names_plural = deebs::WriteColumn::<#concrete_view_inner_tys>::new(std::ops::Deref::deref(&table)).await;
-                        #concrete_view_names_plural.reserve(length);
-                    )*
-
-                    #(
-                        let mut #option_view_names_plural = deebs::WriteColumn::<#option_view_inner_tys>::new(std::ops::Deref::deref(&table)).await;
-                    )*
-
-                    let mut rows = async_std::stream::from_iter(rows);
-                    while let Some((#(#concrete_view_names,)* #(#option_view_names,)*)) = futures::StreamExt::next(&mut rows).await {
-                        let key = table.next_key();
-
-                        #(
-                            #concrete_view_names_plural.insert(key, #concrete_view_names.into());
-                        )*
-
-                        #(
-                            if let Some(#option_view_names) = #option_view_names {
-                                #option_view_names_plural.insert(key, #option_view_names.into());
-                            }
-                        )*
-
-                        keys.push(key)
-                    }
-                }
-
-                deebs::Table::update_views(table, &<#ident<#(#generics),*> as deebs::Row<Table>>::inner_types()).await;
+                let rows = rows.map(|row| (table.next_key(), row)).collect::<Vec<_>>();
+                let keys = rows.iter().map(|(key, _)| *key).collect();
+                <#ident<#(#generics),*> as deebs::Insert<Table>>::insert_multi(table, rows.into_iter()).await;
                 keys
             }
         }
     };
 
-    tokens.into()
+    Ok(tokens)
 }
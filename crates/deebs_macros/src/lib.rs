@@ -1,11 +1,16 @@
-use proc_macro2::Span;
-use syn::{GenericArgument, GenericParam, Ident, ItemStruct, PathArguments, Type, WherePredicate};
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::quote;
+use syn::{
+    GenericArgument, GenericParam, Ident, ItemStruct, PathArguments, PathSegment, Type,
+    WherePredicate,
+};
 
 mod common_keys;
 mod insert;
 mod map;
 mod remove;
 mod row;
+mod sub_table;
 mod table;
 mod widget;
 mod widgets;
@@ -14,28 +19,44 @@ pub(crate) struct RowInput {
     ident: Ident,
     generics: Vec<GenericParam>,
     where_predicates: Vec<WherePredicate>,
-    all_view_names: Vec<Ident>,
 
     concrete_view_names: Vec<Ident>,
     concrete_view_names_plural: Vec<Ident>,
     concrete_view_tys: Vec<Ident>,
     concrete_view_inner_tys: Vec<Type>,
+    concrete_view_boxed: Vec<bool>,
 
     option_view_names: Vec<Ident>,
     option_view_names_plural: Vec<Ident>,
     option_view_tys: Vec<Ident>,
     option_view_inner_tys: Vec<Type>,
+    option_view_boxed: Vec<bool>,
+
+    filter_view_names: Vec<Ident>,
+    filter_view_names_plural: Vec<Ident>,
+    filter_view_tys: Vec<Type>,
 }
 
 impl RowInput {
-    pub fn new(input: ItemStruct) -> Self {
+    pub fn new(input: ItemStruct) -> syn::Result<Self> {
         let ident = input.ident;
         let generics = input.generics;
 
-        if let syn::GenericParam::Lifetime(_) = generics.params.first().unwrap() {
-        } else {
-            panic!("First generic param must be a lifetime.")
-        };
+        match generics.params.first() {
+            Some(GenericParam::Lifetime(_)) => (),
+            Some(param) => {
+                return Err(syn::Error::new_spanned(
+                    param,
+                    "First generic param must be a lifetime.",
+                ))
+            }
+            None => {
+                return Err(syn::Error::new_spanned(
+                    &ident,
+                    "Row struct must have a lifetime parameter.",
+                ))
+            }
+        }
 
         let generics_vec = generics.params.into_iter().collect::<Vec<_>>();
         let where_predicates_vec = if let Some(where_clause) = generics.where_clause {
@@ -44,118 +65,204 @@ impl RowInput {
             vec![]
         };
 
-        let mut all_view_names: Vec<Ident> = vec![];
-
         let mut concrete_view_names: Vec<Ident> = vec![];
         let mut concrete_view_names_plural: Vec<Ident> = vec![];
         let mut concrete_view_tys: Vec<Ident> = vec![];
         let mut concrete_view_inner_tys: Vec<Type> = vec![];
+        let mut concrete_view_boxed: Vec<bool> = vec![];
 
         let mut option_view_names: Vec<Ident> = vec![];
         let mut option_view_names_plural: Vec<Ident> = vec![];
         let mut option_view_tys: Vec<Ident> = vec![];
         let mut option_view_inner_tys: Vec<Type> = vec![];
+        let mut option_view_boxed: Vec<bool> = vec![];
+
+        let mut filter_view_names: Vec<Ident> = vec![];
+        let mut filter_view_names_plural: Vec<Ident> = vec![];
+        let mut filter_view_tys: Vec<Type> = vec![];
 
         for field in input.fields {
-            let field_ident = field.ident.expect("All fields must have an ident");
+            let field_ident = match &field.ident {
+                Some(field_ident) => field_ident.clone(),
+                None => {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        "Row struct fields must be named.",
+                    ))
+                }
+            };
 
-            all_view_names.push(field_ident.clone());
+            if parse_filter_type(&field.ty).is_some() {
+                filter_view_names.push(field_ident.clone());
+                filter_view_names_plural.push(Ident::new(
+                    &(field_ident.to_string() + "_keys"),
+                    Span::call_site(),
+                ));
+                filter_view_tys.push(field.ty);
+                continue;
+            }
 
-            if let Ok(inner_type) = parse_option_type(&field.ty) {
+            if let Some(inner_type) = parse_option_type(&field.ty)? {
                 option_view_names.push(field_ident.clone());
                 option_view_names_plural.push(Ident::new(
                     &(field_ident.to_string() + "_collection"),
                     Span::call_site(),
                 ));
-                let (type_ident, ty) = parse_cell_view_type(&inner_type).expect("Unexpected type.");
+                let (boxed, inner_type) = parse_box_type(inner_type)?;
+                let (type_ident, ty) = parse_cell_view_type(inner_type)?;
                 option_view_tys.push(type_ident);
                 option_view_inner_tys.push(ty);
+                option_view_boxed.push(boxed);
             } else {
                 concrete_view_names.push(field_ident.clone());
                 concrete_view_names_plural.push(Ident::new(
                     &(field_ident.to_string() + "_collection"),
                     Span::call_site(),
                 ));
-                let (ident, ty) = parse_cell_view_type(&field.ty).expect("Unexpected type.");
+                let (boxed, inner_type) = parse_box_type(&field.ty)?;
+                let (ident, ty) = parse_cell_view_type(inner_type)?;
                 concrete_view_tys.push(ident);
                 concrete_view_inner_tys.push(ty);
+                concrete_view_boxed.push(boxed);
             }
         }
 
-        RowInput {
+        if concrete_view_tys.is_empty() && option_view_tys.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &ident,
+                "Row struct must have at least one view member.",
+            ));
+        }
+
+        Ok(RowInput {
             ident,
             generics: generics_vec,
             where_predicates: where_predicates_vec,
-            all_view_names,
             concrete_view_names,
             concrete_view_names_plural,
             concrete_view_tys,
             concrete_view_inner_tys,
+            concrete_view_boxed,
             option_view_names,
             option_view_names_plural,
             option_view_tys,
             option_view_inner_tys,
-        }
+            option_view_boxed,
+            filter_view_names,
+            filter_view_names_plural,
+            filter_view_tys,
+        })
     }
 }
 
-pub(crate) fn parse_cell_view_type(ty: &Type) -> Result<(Ident, Type), &str> {
-    if let Type::Path(path) = ty {
-        let first = path
-            .path
-            .segments
-            .first()
-            .expect("Path must have a first segment");
-
-        assert!(first.ident == "ReadCell" || first.ident == "WriteCell");
-        let view_ty = first.ident.clone();
+/// Generate statements that bind each of `names` to the awaited output of the matching
+/// `acquires` future, awaiting them one at a time in canonical `deebs::lock_order`.
+///
+/// Each name is bound to an `Option` that is guaranteed to be `Some` afterward.
+pub(crate) fn acquire_in_lock_order(
+    names: &[&Ident],
+    inner_tys: &[&Type],
+    acquires: &[TokenStream],
+) -> TokenStream {
+    let indices = (0..names.len()).map(Literal::usize_unsuffixed);
 
-        if let PathArguments::AngleBracketed(args) = &first.arguments {
-            let lt = &args.args[0];
+    quote! {
+        #(
+            let mut #names = None;
+        )*
 
-            if let GenericArgument::Lifetime(_) = lt {
-            } else {
-                return Err("First generic argument is not a lifetime.");
+        for i in deebs::lock_order(&[#(std::any::TypeId::of::<#inner_tys>(),)*]) {
+            match i {
+                #(
+                    #indices => #names = Some(#acquires.await),
+                )*
+                _ => unreachable!(),
             }
-
-            if let GenericArgument::Type(ty) = &args.args[1] {
-                let view_inner_ty = ty.clone();
-                Ok((view_ty, view_inner_ty))
-            } else {
-                Err("Second generic argument is not a type.")
-            }
-        } else {
-            Err("Path arguments must be angle-bracketed.")
         }
-    } else {
-        Err("All fields must be paths.")
     }
 }
 
-pub(crate) fn parse_option_type(ty: &Type) -> Result<Type, &str> {
+/// Return the final segment of `ty` if it is a path, such that `deebs::ReadCell<'a, T>`
+/// and `ReadCell<'a, T>` are matched alike.
+pub(crate) fn last_segment(ty: &Type) -> Option<&PathSegment> {
     if let Type::Path(path) = ty {
-        let first = path
-            .path
-            .segments
-            .first()
-            .expect("Path must have a first segment");
-
-        if first.ident == "Option" {
-            if let PathArguments::AngleBracketed(args) = &first.arguments {
-                let arg = &args.args[0];
-                if let GenericArgument::Type(ty) = arg {
-                    Ok(ty.clone())
-                } else {
-                    Err("Unrecognized Option type.")
-                }
-            } else {
-                Err("Option arguments must be angle-bracketed.")
-            }
-        } else {
-            Err("Unrecognized type.")
+        if path.qself.is_none() {
+            return path.path.segments.last();
         }
+    }
+
+    None
+}
+
+/// Return the angle-bracketed arguments of `segment`, or an error spanned on it.
+pub(crate) fn angle_bracketed_args(segment: &PathSegment) -> syn::Result<Vec<&GenericArgument>> {
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        Ok(args.args.iter().collect())
     } else {
-        Err("All fields must be paths.")
+        Err(syn::Error::new_spanned(
+            segment,
+            format!("Expected `{}<...>`.", segment.ident),
+        ))
+    }
+}
+
+/// Parse `ReadCell<'a, T>` or `WriteCell<'a, T>` into the view ident and `T`.
+pub(crate) fn parse_cell_view_type(ty: &Type) -> syn::Result<(Ident, Type)> {
+    let segment = last_segment(ty)
+        .filter(|segment| segment.ident == "ReadCell" || segment.ident == "WriteCell")
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                ty,
+                "Row fields must be a ReadCell or WriteCell, an Option of either, or a filter.",
+            )
+        })?;
+
+    match angle_bracketed_args(segment)?.as_slice() {
+        [GenericArgument::Lifetime(_), GenericArgument::Type(inner_ty)] => {
+            Ok((segment.ident.clone(), inner_ty.clone()))
+        }
+        _ => Err(syn::Error::new_spanned(
+            &segment.arguments,
+            format!("Expected `{}<'a, T>`.", segment.ident),
+        )),
+    }
+}
+
+/// Parse a filter such as `With<T>` into its ident.
+pub(crate) fn parse_filter_type(ty: &Type) -> Option<Ident> {
+    let segment = last_segment(ty)?;
+
+    match segment.ident.to_string().as_str() {
+        "Added" | "Changed" | "With" | "Without" | "Or" => Some(segment.ident.clone()),
+        _ => None,
+    }
+}
+
+/// Parse `Option<T>` into `T`, returning `None` for any other type.
+pub(crate) fn parse_option_type(ty: &Type) -> syn::Result<Option<&Type>> {
+    parse_wrapper_type(ty, "Option")
+}
+
+/// Unwrap `Box<T>` into `T`, returning whether `ty` was boxed.
+pub(crate) fn parse_box_type(ty: &Type) -> syn::Result<(bool, &Type)> {
+    Ok(match parse_wrapper_type(ty, "Box")? {
+        Some(inner_ty) => (true, inner_ty),
+        None => (false, ty),
+    })
+}
+
+fn parse_wrapper_type<'a>(ty: &'a Type, wrapper: &str) -> syn::Result<Option<&'a Type>> {
+    let segment = match last_segment(ty) {
+        Some(segment) if segment.ident == wrapper => segment,
+        _ => return Ok(None),
+    };
+
+    match angle_bracketed_args(segment)?.as_slice() {
+        [GenericArgument::Type(inner_ty)] => Ok(Some(inner_ty)),
+        _ => Err(syn::Error::new_spanned(
+            &segment.arguments,
+            format!("Expected `{}<T>`.", wrapper),
+        )),
     }
 }
 
@@ -163,46 +270,70 @@ pub(crate) fn parse_option_type(ty: &Type) -> Result<Type, &str> {
 pub fn derive_row(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input);
     row::impl_row(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
 #[proc_macro_derive(Insert)]
 pub fn derive_insert(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input);
     insert::impl_insert(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
 #[proc_macro_derive(Remove)]
 pub fn derive_remove(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input);
     remove::impl_remove(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
 #[proc_macro_derive(CommonKeys)]
 pub fn derive_common_keys(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input);
     common_keys::impl_common_keys(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
 #[proc_macro_derive(Map)]
 pub fn derive_map(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input);
     map::impl_map(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
 #[proc_macro_derive(Widget)]
 pub fn derive_widget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input);
     widget::impl_widget(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
 #[proc_macro_derive(Widgets)]
 pub fn derive_widgets(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input);
     widgets::impl_widgets(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
-#[proc_macro_derive(Table)]
+#[proc_macro_derive(Table, attributes(dense, flatten, index, skip_serde, reflect_egui))]
 pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input);
     table::impl_table(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(SubTable, attributes(dense, index, skip_serde))]
+pub fn derive_sub_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input);
+    sub_table::impl_sub_table(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
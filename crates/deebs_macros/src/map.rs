@@ -3,29 +3,44 @@ use syn::ItemStruct;
 
 use crate::RowInput;
 
-pub fn impl_map(input: ItemStruct) -> proc_macro::TokenStream {
+pub fn impl_map(input: ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
     let RowInput {
         ident,
         generics,
         where_predicates,
-        all_view_names: _,
         concrete_view_names,
         concrete_view_names_plural: _,
-        concrete_view_tys,
+        concrete_view_tys: _,
         concrete_view_inner_tys,
+        concrete_view_boxed,
         option_view_names,
         option_view_names_plural: _,
-        option_view_tys,
+        option_view_tys: _,
         option_view_inner_tys,
-    } = RowInput::new(input);
-
-    assert!(
-        !concrete_view_tys.is_empty() || !option_view_tys.is_empty(),
-        "Row struct must have at least one view member."
-    );
+        option_view_boxed,
+        filter_view_names: _,
+        filter_view_names_plural: _,
+        filter_view_tys: _,
+    } = RowInput::new(input)?;
 
     let _generic_lt = &generics[0];
 
+    // Boxed views deref through the box before the cell
+    let concrete_view_derefs = concrete_view_boxed.iter().map(|boxed| {
+        if *boxed {
+            quote!(.deref().deref())
+        } else {
+            quote!(.deref())
+        }
+    });
+    let option_view_derefs = option_view_boxed.iter().map(|boxed| {
+        if *boxed {
+            quote!(.as_deref().map(std::ops::Deref::deref))
+        } else {
+            quote!(.as_deref())
+        }
+    });
+
     let ty_count = concrete_view_inner_tys.len() + option_view_inner_tys.len();
 
     let tokens = quote! {
@@ -48,19 +63,20 @@ pub fn impl_map(input: ItemStruct) -> proc_macro::TokenStream {
                 let #ident {
                     #(#concrete_view_names,)*
                     #(#option_view_names,)*
+                    ..
                 } = self;
 
                 std::array::IntoIter::new([
                     #(
-                        Some(M::map(#concrete_view_names.deref())),
+                        Some(M::map(#concrete_view_names #concrete_view_derefs)),
                     )*
                     #(
-                        #option_view_names.as_deref().map(|#option_view_names| M::map(#option_view_names)),
+                        #option_view_names #option_view_derefs.map(|#option_view_names| M::map(#option_view_names)),
                     )*
                 ])
             }
         }
     };
 
-    tokens.into()
+    Ok(tokens)
 }
@@ -3,31 +3,31 @@ use syn::ItemStruct;
 
 use crate::RowInput;
 
-pub fn impl_remove(input: ItemStruct) -> proc_macro::TokenStream {
+pub fn impl_remove(input: ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
     let RowInput {
         ident,
         generics,
         where_predicates,
-        all_view_names: _,
         concrete_view_names: _,
-        concrete_view_names_plural: _,
-        concrete_view_tys,
+        concrete_view_names_plural,
+        concrete_view_tys: _,
         concrete_view_inner_tys,
+        concrete_view_boxed: _,
         option_view_names: _,
-        option_view_names_plural: _,
-        option_view_tys,
+        option_view_names_plural,
+        option_view_tys: _,
         option_view_inner_tys,
-    } = RowInput::new(input);
-
-    assert!(
-        !concrete_view_tys.is_empty() || !option_view_tys.is_empty(),
-        "Row struct must have at least one view member."
-    );
+        option_view_boxed: _,
+        filter_view_names: _,
+        filter_view_names_plural: _,
+        filter_view_tys: _,
+    } = RowInput::new(input)?;
 
     let generic_lt = &generics[0];
     let generic_types = &generics[1..];
 
     let _insert_ty = quote! { (#(#concrete_view_inner_tys,)* #(Option<#option_view_inner_tys>,)*) };
+    let transaction_ty = quote! { (#(#concrete_view_inner_tys,)* #(#option_view_inner_tys,)*) };
 
     let tokens = quote! {
         #[async_trait::async_trait]
@@ -42,39 +42,40 @@ pub fn impl_remove(input: ItemStruct) -> proc_macro::TokenStream {
             )*
         {
             async fn remove(table: &Table, key: deebs::Key) where Table: deebs::Table {
-                {
-                    futures::join!(
-                        #(
-                            table.remove::<#concrete_view_inner_tys>(key),
-                        )*
-                        #(
-                            table.remove::<#option_view_inner_tys>(key),
-                        )*
-                    );
-                }
+                deebs::Table::transaction::<#transaction_ty, _, _, _>(table, move |(#(#concrete_view_names_plural,)* #(#option_view_names_plural,)*)| {
+                    #(
+                        #concrete_view_names_plural.remove(&key);
+                    )*
+                    #(
+                        #option_view_names_plural.remove(&key);
+                    )*
 
-                deebs::Table::update_views(table, &<#ident<#(#generics),*> as deebs::Row<Table>>::inner_types()).await;
+                    Ok::<_, std::convert::Infallible>(())
+                })
+                .await
+                .unwrap_or_else(|never| match never {})
             }
 
             async fn remove_multi<S>(table: &Table, keys: S) where Table: deebs::Table, S: futures::Stream<Item = deebs::Key> + Send {
-                {
-                    futures::pin_mut!(keys);
-                    while let Some(key) = futures::StreamExt::next(&mut keys).await {
-                        futures::join!(
-                            #(
-                                table.remove::<#concrete_view_inner_tys>(key),
-                            )*
-                            #(
-                                table.remove::<#option_view_inner_tys>(key),
-                            )*
-                        );
+                let keys = futures::StreamExt::collect::<Vec<_>>(keys).await;
+
+                deebs::Table::transaction::<#transaction_ty, _, _, _>(table, move |(#(#concrete_view_names_plural,)* #(#option_view_names_plural,)*)| {
+                    for key in keys {
+                        #(
+                            #concrete_view_names_plural.remove(&key);
+                        )*
+                        #(
+                            #option_view_names_plural.remove(&key);
+                        )*
                     }
-                }
 
-                deebs::Table::update_views(table, &<#ident<#(#generics),*> as deebs::Row<Table>>::inner_types()).await;
+                    Ok::<_, std::convert::Infallible>(())
+                })
+                .await
+                .unwrap_or_else(|never| match never {})
             }
         }
     };
 
-    tokens.into()
+    Ok(tokens)
 }
@@ -1,34 +1,213 @@
 use quote::quote;
 use syn::ItemStruct;
 
-use crate::RowInput;
+use crate::{acquire_in_lock_order, RowInput};
 
-pub fn impl_row(input: ItemStruct) -> proc_macro::TokenStream {
+pub fn impl_row(input: ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
     let RowInput {
         ident,
         generics,
         where_predicates,
-        all_view_names,
         concrete_view_names,
         concrete_view_names_plural: _,
         concrete_view_tys,
         concrete_view_inner_tys,
+        concrete_view_boxed,
         option_view_names,
         option_view_names_plural: _,
         option_view_tys,
         option_view_inner_tys,
-    } = RowInput::new(input);
-
-    assert!(
-        !concrete_view_tys.is_empty() || !option_view_tys.is_empty(),
-        "Row struct must have at least one view member."
-    );
+        option_view_boxed,
+        filter_view_names,
+        filter_view_names_plural,
+        filter_view_tys,
+    } = RowInput::new(input)?;
 
     let generic_lt = &generics[0];
     let generic_types = &generics[1..];
 
+    // Boxed views are boxed as the row is assembled
+    let view_fields = concrete_view_names
+        .iter()
+        .zip(concrete_view_boxed.iter())
+        .map(|(name, boxed)| {
+            if *boxed {
+                quote!(#name: Box::new(#name))
+            } else {
+                quote!(#name)
+            }
+        })
+        .chain(
+            option_view_names
+                .iter()
+                .zip(option_view_boxed.iter())
+                .map(|(name, boxed)| {
+                    if *boxed {
+                        quote!(#name: #name.map(Box::new))
+                    } else {
+                        quote!(#name)
+                    }
+                }),
+        )
+        .collect::<Vec<_>>();
+
+    // Write cells notify their table's observers once the row is released
+    let is_write = |view_ty: &syn::Ident| view_ty == "WriteCell";
+    let write_tys = concrete_view_tys
+        .iter()
+        .zip(concrete_view_inner_tys.iter())
+        .chain(option_view_tys.iter().zip(option_view_inner_tys.iter()))
+        .filter(|(view_ty, _)| is_write(view_ty))
+        .map(|(_, inner_ty)| inner_ty)
+        .collect::<Vec<_>>();
+
+    let mut release_names = vec![];
+    let mut releases = vec![];
+    let mut read_names = vec![];
+    for (name, view_ty, boxed, optional) in concrete_view_names
+        .iter()
+        .zip(concrete_view_tys.iter())
+        .zip(concrete_view_boxed.iter())
+        .map(|((name, view_ty), boxed)| (name, view_ty, *boxed, false))
+        .chain(
+            option_view_names
+                .iter()
+                .zip(option_view_tys.iter())
+                .zip(option_view_boxed.iter())
+                .map(|((name, view_ty), boxed)| (name, view_ty, *boxed, true)),
+        )
+    {
+        if !is_write(view_ty) {
+            read_names.push(name);
+            continue;
+        }
+
+        let cell = if boxed { quote!(*cell) } else { quote!(cell) };
+        let release = quote!(|cell| deebs::WriteCell::release(#cell));
+        release_names.push(name);
+        releases.push(if optional {
+            quote!(#name.map(#release))
+        } else {
+            quote!(Some(#name).map(#release))
+        });
+    }
+
     let _insert_ty = quote! { (#(#concrete_view_inner_tys,)* #(Option<#option_view_inner_tys>,)*) };
 
+    let acquire = acquire_in_lock_order(
+        &concrete_view_names
+            .iter()
+            .chain(option_view_names.iter())
+            .collect::<Vec<_>>(),
+        &concrete_view_inner_tys
+            .iter()
+            .chain(option_view_inner_tys.iter())
+            .collect::<Vec<_>>(),
+        &concrete_view_tys
+            .iter()
+            .zip(concrete_view_inner_tys.iter())
+            .chain(option_view_tys.iter().zip(option_view_inner_tys.iter()))
+            .map(|(view_ty, inner_ty)| quote!(deebs::#view_ty::<#inner_ty>::new(table, key)))
+            .collect::<Vec<_>>(),
+    );
+
+    let acquire_columns = acquire_in_lock_order(
+        &concrete_view_names
+            .iter()
+            .chain(option_view_names.iter())
+            .collect::<Vec<_>>(),
+        &concrete_view_inner_tys
+            .iter()
+            .chain(option_view_inner_tys.iter())
+            .collect::<Vec<_>>(),
+        &concrete_view_inner_tys
+            .iter()
+            .chain(option_view_inner_tys.iter())
+            .map(|inner_ty| quote!(deebs::ReadColumn::<#inner_ty>::new(table)))
+            .collect::<Vec<_>>(),
+    );
+
+    let query = |candidates: proc_macro2::TokenStream| {
+        quote! {
+            #(
+                let #filter_view_names_plural = <#filter_view_tys as deebs::Filter<Table>>::filter_keys(table, tick).await;
+            )*
+
+            #acquire_columns
+
+            let (#(#concrete_view_names,)*) = (#(std::sync::Arc::new(#concrete_view_names.unwrap()),)*);
+            let (#(#option_view_names,)*) = (#(std::sync::Arc::new(#option_view_names.unwrap()),)*);
+
+            let mut visited: std::collections::BTreeSet<deebs::Key> = Default::default();
+            let mut keys = vec![];
+            for key in #candidates {
+                if !visited.insert(key) {
+                    continue;
+                }
+
+                let contains = true;
+                #(
+                    let contains = contains & #concrete_view_names.contains_key(&key);
+                )*
+                #(
+                    let contains = contains & #filter_view_names_plural.contains(&key);
+                )*
+
+                if contains {
+                    keys.push(key);
+                }
+            }
+
+            let rows = futures::StreamExt::then(futures::stream::iter(keys), move |key| {
+                #(
+                    let #concrete_view_names = #concrete_view_names.clone();
+                )*
+                #(
+                    let #option_view_names = #option_view_names.clone();
+                )*
+
+                async move {
+                    #(
+                        let #concrete_view_names = deebs::#concrete_view_tys::from_column(#concrete_view_names, &key)
+                            .await
+                            .unwrap_or_else(|e| panic!("{} ({})", e, stringify!(#concrete_view_names)));
+                    )*
+                    #(
+                        let #option_view_names = deebs::#option_view_tys::from_column(#option_view_names, &key)
+                            .await
+                            .ok();
+                    )*
+
+                    #ident { #(#view_fields,)* #(#filter_view_names: Default::default(),)* }
+                }
+            });
+
+            futures::StreamExt::boxed(rows)
+        }
+    };
+
+    let query_all = query(quote! {
+        std::iter::empty()
+        #(
+            .chain(#concrete_view_names.keys().copied())
+        )*
+        #(
+            .chain(#option_view_names.keys().copied())
+        )*
+        .collect::<std::collections::BTreeSet<_>>()
+    });
+    let query_keys = query(quote!(keys_in));
+
+    // Every cell present at a key is transferred, whether or not its field is optional
+    let transfer_names = concrete_view_names
+        .iter()
+        .chain(option_view_names.iter())
+        .collect::<Vec<_>>();
+    let transfer_tys = concrete_view_inner_tys
+        .iter()
+        .chain(option_view_inner_tys.iter())
+        .collect::<Vec<_>>();
+
     let tokens = quote! {
         #[async_trait::async_trait]
         impl<#(#generics,)* Table> deebs::Row<#generic_lt, Table> for #ident<#(#generics,)*>
@@ -36,7 +215,13 @@ pub fn impl_row(input: ItemStruct) -> proc_macro::TokenStream {
             #(
                 #generic_types: Send + Sync + #generic_lt,
             )*
-            Table: #(deebs::BorrowColumn<#concrete_view_inner_tys> +)* #(deebs::BorrowColumn<#option_view_inner_tys> +)* Send + Sync,
+            Table: deebs::Table + #(deebs::BorrowColumn<#concrete_view_inner_tys> +)* #(deebs::BorrowColumn<#option_view_inner_tys> +)* Send + Sync,
+            #(
+                #write_tys: Send + Sync + 'static,
+            )*
+            #(
+                #filter_view_tys: deebs::Filter<Table>,
+            )*
             #(
                 #where_predicates,
             )*
@@ -44,19 +229,307 @@ pub fn impl_row(input: ItemStruct) -> proc_macro::TokenStream {
             const HEADER: &'static [&'static str] = &[#(stringify!(#concrete_view_inner_tys),)* #(stringify!(#option_view_inner_tys),)*];
 
             fn inner_types() -> Vec<std::any::TypeId> {
-                vec![#(std::any::TypeId::of::<#concrete_view_inner_tys>(),)* #(std::any::TypeId::of::<#option_view_inner_tys>(),)*]
+                let mut types = vec![#(std::any::TypeId::of::<#concrete_view_inner_tys>(),)* #(std::any::TypeId::of::<#option_view_inner_tys>(),)*];
+                #(
+                    types.extend(<#filter_view_tys as deebs::Filter<Table>>::inner_types());
+                )*
+                types
             }
 
             async fn new(table: &#generic_lt Table, key: &deebs::Key) -> Self {
-                let (#(#concrete_view_names,)*) = futures::join!(#(deebs::#concrete_view_tys::<#concrete_view_inner_tys>::new(table, key),)*);
-                let (#(#option_view_names,)*) = futures::join!(#(deebs::#option_view_tys::<#option_view_inner_tys>::new(table, key),)*);
+                #acquire
+
+                let (#(#concrete_view_names,)*) = (#(#concrete_view_names.unwrap().unwrap_or_else(|e| panic!("{} ({})", e, stringify!(#concrete_view_names))),)*);
+                let (#(#option_view_names,)*) = (#(
+                    match #option_view_names.unwrap() {
+                        Ok(cell) => Some(cell),
+                        Err(deebs::CellError::Missing(_)) => None,
+                        Err(e) => panic!("{} ({})", e, stringify!(#option_view_names)),
+                    },
+                )*);
+
+                #ident { #(#view_fields,)* #(#filter_view_names: Default::default(),)* }
+            }
+
+            async fn query(table: &#generic_lt Table) -> futures::stream::BoxStream<#generic_lt, Self> {
+                <Self as deebs::Row<#generic_lt, Table>>::query_since(table, deebs::Tick::default()).await
+            }
+
+            async fn query_since(table: &#generic_lt Table, tick: deebs::Tick) -> futures::stream::BoxStream<#generic_lt, Self> {
+                #query_all
+            }
+
+            async fn query_keys(table: &#generic_lt Table, keys_in: Vec<deebs::Key>) -> futures::stream::BoxStream<#generic_lt, Self> {
+                <Self as deebs::Row<#generic_lt, Table>>::query_keys_since(table, keys_in, deebs::Tick::default()).await
+            }
+
+            async fn query_keys_since(table: &#generic_lt Table, keys_in: Vec<deebs::Key>, tick: deebs::Tick) -> futures::stream::BoxStream<#generic_lt, Self> {
+                #query_keys
+            }
+        }
+
+        impl<#(#generics,)*> #ident<#(#generics,)*>
+        where
+            #(
+                #where_predicates,
+            )*
+        {
+            /// Release this row's cells, then update views and observers for the writes
+            /// of its [`deebs::WriteCell`]s as by [`deebs::WriteCell::release`].
+            ///
+            /// Every cell is released before any observer runs,
+            /// so observers may lock any of the row's columns.
+            /// Cells of rows streamed by [`deebs::Row::query`] are only dropped;
+            /// see [`Self::notify_observers`].
+            pub async fn release(self) {
+                let #ident { #(#concrete_view_names,)* #(#option_view_names,)* .. } = self;
+                #(
+                    let #release_names = #releases;
+                )*
+                #(
+                    drop(#read_names);
+                )*
+                #(
+                    if let Some(released) = #release_names {
+                        released.await;
+                    }
+                )*
+            }
+
+            /// Split this row's common keys across `tasks` [`deebs::runtime::spawn`]ed tasks,
+            /// [`deebs::Row::query_keys`] each share, and call `f` with every row.
+            ///
+            /// The observers of the columns this row writes are notified once every task is done.
+            pub async fn par_for_each<Table, Each>(table: std::sync::Arc<Table>, tasks: usize, f: Each)
+            where
+                Table: deebs::Table #(+ deebs::BorrowColumn<#write_tys>)* + Send + Sync + 'static,
+                #(
+                    #write_tys: Send + Sync + 'static,
+                )*
+                for<'r> #ident<'r, #(#generic_types,)*>: deebs::Row<'r, Table> + deebs::CommonKeys<Table> + Send,
+                Each: for<'r> Fn(#ident<'r, #(#generic_types,)*>) + Send + Sync + 'static,
+            {
+                let keys = futures::StreamExt::collect::<Vec<_>>(
+                    <#ident<#(#generic_types,)*> as deebs::CommonKeys<Table>>::common_keys(&*table).await,
+                )
+                .await;
+
+                let mut shares = vec![vec![]; tasks.max(1)];
+                let share_count = shares.len();
+                for (i, key) in keys.into_iter().enumerate() {
+                    shares[i % share_count].push(key);
+                }
+
+                let f = std::sync::Arc::new(f);
+
+                let handles = shares
+                    .into_iter()
+                    .filter(|share| !share.is_empty())
+                    .map(|share| {
+                        let table = table.clone();
+                        let f = f.clone();
+                        deebs::runtime::spawn(async move {
+                            let rows = <#ident<#(#generic_types,)*> as deebs::Row<Table>>::query_keys(&*table, share).await;
+                            futures::StreamExt::for_each(rows, |row| {
+                                f(row);
+                                futures::future::ready(())
+                            })
+                            .await;
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                futures::future::join_all(handles).await;
+                Self::notify_observers(&*table).await;
+            }
+
+            /// Update views and observers for the writes of this row's [`deebs::WriteCell`]s
+            /// that were released without [`Self::release`].
+            ///
+            /// Rows streamed by [`deebs::Row::query`] share their column guards with the stream,
+            /// so call this once the stream and its rows are dropped.
+            pub async fn notify_observers<Table>(table: &Table)
+            where
+                Table: deebs::Table #(+ deebs::BorrowColumn<#write_tys>)* + Send + Sync,
+                #(
+                    #write_tys: Send + Sync + 'static,
+                )*
+            {
+                #(
+                    deebs::Table::notify_observers::<#write_tys>(table).await;
+                )*
+            }
+
+            /// Move this row's cells at `key` from `src` to `dst`,
+            /// returning the key they were given in `dst`.
+            ///
+            /// `key` is given a new key in `dst` the first time it is transferred through `keys`,
+            /// so that transferring several rows of one entity keeps them together.
+            /// Nothing is allocated in `dst` if `key` isn't alive in `src` or has none of this row's cells.
+            ///
+            /// `key` stays alive in `src`, and the moved cells still hold `src` keys
+            /// until they are rewritten with [`Self::map_keys`].
+            pub async fn transfer<Src, Dst>(
+                src: &Src,
+                dst: &Dst,
+                key: deebs::Key,
+                keys: &mut deebs::KeyMap,
+            ) -> Option<deebs::Key>
+            where
+                Src: deebs::Table #(+ deebs::BorrowColumn<#transfer_tys>)* + Send + Sync,
+                Dst: deebs::Table #(+ deebs::BorrowColumn<#transfer_tys>)* + Send + Sync,
+                #(
+                    #transfer_tys: Send + Sync + 'static,
+                )*
+            {
+                if deebs::Table::key_allocator(src).is_some() && !deebs::Table::is_alive(src, &key) {
+                    return None;
+                }
+
+                // Each cell is taken out of `src` before `dst` is locked,
+                // so that transferring within a single table can't deadlock
+                #(
+                    let #transfer_names = deebs::WriteColumn::<#transfer_tys>::new(src).await.remove(&key);
+                )*
+
+                let mut type_ids = vec![];
+                #(
+                    if #transfer_names.is_some() {
+                        type_ids.push(std::any::TypeId::of::<#transfer_tys>());
+                    }
+                )*
+                if type_ids.is_empty() {
+                    return None;
+                }
+
+                let dst_key = keys.get_or_insert(key, dst);
+                #(
+                    if let Some(cell) = #transfer_names {
+                        deebs::WriteColumn::<#transfer_tys>::new(dst).await.insert(dst_key, cell);
+                    }
+                )*
+
+                deebs::Table::update_views(src, &type_ids, &[key]).await;
+                deebs::Table::update_views(dst, &type_ids, &[dst_key]).await;
+                #(
+                    deebs::Table::notify_observers::<#transfer_tys>(src).await;
+                    deebs::Table::notify_observers::<#transfer_tys>(dst).await;
+                )*
+
+                Some(dst_key)
+            }
+
+            /// As [`Self::transfer`], but copy the cells instead of removing them from `src`.
+            ///
+            /// The `Clone` bounds are higher-ranked so that rows holding types without `Clone`
+            /// still derive, and only fail to compile if this is called.
+            pub async fn transfer_clone<Src, Dst>(
+                src: &Src,
+                dst: &Dst,
+                key: deebs::Key,
+                keys: &mut deebs::KeyMap,
+            ) -> Option<deebs::Key>
+            where
+                Src: deebs::Table #(+ deebs::BorrowColumn<#transfer_tys>)* + Send + Sync,
+                Dst: deebs::Table #(+ deebs::BorrowColumn<#transfer_tys>)* + Send + Sync,
+                #(
+                    #transfer_tys: Send + Sync + 'static,
+                    for<'__clone> #transfer_tys: Clone,
+                )*
+            {
+                if deebs::Table::key_allocator(src).is_some() && !deebs::Table::is_alive(src, &key) {
+                    return None;
+                }
+
+                #(
+                    let #transfer_names = deebs::Table::get::<#transfer_tys>(src, &key)
+                        .await
+                        .ok()
+                        .map(|cell| <#transfer_tys as Clone>::clone(&*cell));
+                )*
+
+                let mut type_ids = vec![];
+                #(
+                    if #transfer_names.is_some() {
+                        type_ids.push(std::any::TypeId::of::<#transfer_tys>());
+                    }
+                )*
+                if type_ids.is_empty() {
+                    return None;
+                }
+
+                let dst_key = keys.get_or_insert(key, dst);
+                #(
+                    if let Some(cell) = #transfer_names {
+                        deebs::WriteColumn::<#transfer_tys>::new(dst).await.insert(dst_key, cell);
+                    }
+                )*
+
+                deebs::Table::update_views(dst, &type_ids, &[dst_key]).await;
+                #(
+                    deebs::Table::notify_observers::<#transfer_tys>(dst).await;
+                )*
+
+                Some(dst_key)
+            }
+
+            /// Rewrite the keys held by this row's [`deebs::MapKeys`] cells
+            /// at every key transferred into `table` through `keys`,
+            /// returning the held keys that weren't transferred.
+            ///
+            /// References to keys that weren't transferred are dropped rather than left to alias
+            /// unrelated entities in `table`, and cells that can't stand without them are removed.
+            /// Cells whose keys are unchanged aren't written.
+            pub async fn map_keys<Tbl>(table: &Tbl, keys: &deebs::KeyMap) -> Vec<deebs::Key>
+            where
+                Tbl: deebs::Table #(+ deebs::BorrowColumn<#transfer_tys>)* + Send + Sync,
+                #(
+                    #transfer_tys: Send + Sync + 'static,
+                )*
+            {
+                use deebs::reflect::{ProbeMapKeys as _, ProbeNoMapKeys as _};
+
+                let mut unmapped = std::collections::BTreeSet::new();
+                #(
+                    if let Some((held_keys, map_keys)) = (&deebs::reflect::Probe::<Tbl, #transfer_tys>::new()).map_keys_fns() {
+                        let mut changed = false;
+                        let mut removed = vec![];
+                        for key in keys.values() {
+                            let held = match deebs::Table::get::<#transfer_tys>(table, key).await {
+                                Ok(cell) => held_keys(&*cell),
+                                Err(_) => continue,
+                            };
+
+                            unmapped.extend(held.iter().copied().filter(|held| keys.get(held).is_none()));
+                            if !keys.remaps(&held) {
+                                continue;
+                            }
+
+                            let keep = match deebs::Table::get_mut::<#transfer_tys>(table, key).await {
+                                Ok(mut cell) => map_keys(&mut *cell, keys),
+                                Err(_) => continue,
+                            };
+                            changed = true;
+
+                            if !keep {
+                                deebs::WriteColumn::<#transfer_tys>::new(table).await.remove(key);
+                                removed.push(*key);
+                            }
+                        }
 
-                let (#(#concrete_view_names,)*) = (#(#concrete_view_names.unwrap_or_else(|| panic!("{:?} has no {} cell.", key, stringify!(#concrete_view_names))),)*);
+                        if !removed.is_empty() {
+                            deebs::Table::update_views(table, &[std::any::TypeId::of::<#transfer_tys>()], &removed).await;
+                        }
+                        if changed {
+                            deebs::Table::notify_observers::<#transfer_tys>(table).await;
+                        }
+                    }
+                )*
 
-                #ident { #(#all_view_names,)* }
+                unmapped.into_iter().collect()
             }
         }
     };
 
-    tokens.into()
+    Ok(tokens)
 }
@@ -0,0 +1,305 @@
+use proc_macro2::{Delimiter, Group, Punct, Spacing, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{GenericArgument, GenericParam, Ident, ItemStruct, Type};
+
+use crate::{
+    angle_bracketed_args, last_segment,
+    table::{column_infos, serde_impls, TableFields},
+};
+
+/// Return the lifetime of a view's row type, such as `'a` in `View<WindowRow<'a>>`.
+fn row_lifetime(ty: &Type) -> TokenStream {
+    last_segment(ty)
+        .and_then(|segment| angle_bracketed_args(segment).ok())
+        .and_then(|args| {
+            args.into_iter().find_map(|arg| match arg {
+                GenericArgument::Lifetime(lifetime) => Some(lifetime.to_token_stream()),
+                _ => None,
+            })
+        })
+        .unwrap_or_else(|| quote!('static))
+}
+
+/// Respell `tokens` so that they resolve inside the companion macro wherever it is invoked:
+/// `crate` becomes `$crate` and each of the struct's `lifetimes` becomes a macro variable.
+fn respell(tokens: TokenStream, lifetimes: &[Ident]) -> TokenStream {
+    let mut respelled = TokenStream::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => {
+                let mut inner = Group::new(group.delimiter(), respell(group.stream(), lifetimes));
+                inner.set_span(group.span());
+                respelled.extend(Some(TokenTree::Group(inner)));
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '\'' => match tokens.peek() {
+                Some(TokenTree::Ident(ident)) if lifetimes.contains(ident) => {
+                    respelled.extend(Some(TokenTree::Punct(Punct::new('$', Spacing::Alone))));
+                }
+                _ => respelled.extend(Some(TokenTree::Punct(punct))),
+            },
+            TokenTree::Ident(ident) if ident == "crate" => {
+                respelled.extend(Some(TokenTree::Punct(Punct::new('$', Spacing::Alone))));
+                respelled.extend(Some(TokenTree::Ident(ident)));
+            }
+            token => respelled.extend(Some(token)),
+        }
+    }
+    respelled
+}
+
+/// Return the type of a column, singleton or view field as the companion macro spells it,
+/// qualifying a bare `Column`, `Singleton` or `View` with `deebs::`.
+fn borrow_type(ty: &Type, lifetimes: &[Ident]) -> TokenStream {
+    let qualified = match ty {
+        Type::Path(path) if path.qself.is_none() && path.path.segments.len() == 1 => {
+            quote!(deebs::#ty)
+        }
+        _ => quote!(#ty),
+    };
+    respell(qualified, lifetimes)
+}
+
+pub fn impl_sub_table(input: ItemStruct) -> syn::Result<TokenStream> {
+    let ident = input.ident;
+    let vis = input.vis;
+    let generics = input.generics;
+
+    let mut lifetimes: Vec<Ident> = vec![];
+    for param in generics.params.iter() {
+        match param {
+            GenericParam::Lifetime(lifetime) => lifetimes.push(lifetime.lifetime.ident.clone()),
+            param => {
+                return Err(syn::Error::new_spanned(
+                    param,
+                    "SubTable structs may only have lifetime parameters.",
+                ))
+            }
+        }
+    }
+
+    let TableFields {
+        field_idents,
+        key_allocator_ident,
+        column_idents,
+        column_inner_tys,
+        column_dense,
+        index_column_inner_tys,
+        index_tys,
+        has_parent_column,
+        has_children_column,
+        view_idents,
+        view_inner_tys,
+        flatten_idents,
+        borrow_tys,
+        serde_idents,
+        serde_tys,
+        serde_column_idents,
+        serde_column_dense,
+        ..
+    } = TableFields::new(input.fields)?;
+
+    if let Some(key_allocator_ident) = key_allocator_ident {
+        return Err(syn::Error::new_spanned(
+            key_allocator_ident,
+            "SubTable structs can't hold a KeyAllocator, as keys belong to the table they are flattened into.",
+        ));
+    }
+
+    if let Some(flatten_ident) = flatten_idents.first() {
+        return Err(syn::Error::new_spanned(
+            flatten_ident,
+            "#[flatten] can only be applied to the fields of a Table.",
+        ));
+    }
+
+    let params = &generics.params;
+    let lifetime_params = params.iter();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let view_lifetimes = view_inner_tys.iter().map(row_lifetime).collect::<Vec<_>>();
+
+    let despawn_hierarchy = if has_parent_column && has_children_column {
+        quote!(deebs::despawn_hierarchy(table, keys).await)
+    } else {
+        quote!(keys)
+    };
+
+    let storage = |dense: &bool| {
+        if *dense {
+            quote!(deebs::ColumnStorage::Dense)
+        } else {
+            quote!(deebs::ColumnStorage::Map)
+        }
+    };
+    let column_storages = column_dense.iter().map(storage);
+    let serde_column_storages = serde_column_dense.iter().map(storage);
+
+    let column_infos = column_infos(&quote!(__Tbl), &column_idents, &column_inner_tys);
+
+    let sub_table = quote! {
+        #[async_trait::async_trait]
+        impl<#(#lifetime_params,)* __Tbl> deebs::SubTable<__Tbl> for #ident #ty_generics
+        where
+            __Tbl: deebs::Table<Key = deebs::Key> + #(deebs::BorrowColumn<#column_inner_tys> +)* Send + Sync,
+            #(
+                #view_inner_tys: deebs::CommonKeys<__Tbl> + deebs::Row<#view_lifetimes, __Tbl>,
+            )*
+        {
+            async fn despawn_hierarchy(&self, table: &__Tbl, keys: Vec<deebs::Key>) -> Vec<deebs::Key> {
+                #despawn_hierarchy
+            }
+
+            fn despawn_columns() -> Vec<(std::any::TypeId, deebs::LockDespawnFn<__Tbl>)> {
+                vec![
+                    #(
+                        (
+                            std::any::TypeId::of::<#column_inner_tys>(),
+                            deebs::lock_despawn::<__Tbl, #column_inner_tys>,
+                        ),
+                    )*
+                ]
+            }
+
+            #[allow(unused_variables)]
+            async fn notify_all_observers(&self, table: &__Tbl) {
+                #(
+                    deebs::Table::notify_observers::<#column_inner_tys>(table).await;
+                )*
+            }
+
+            fn column_storage(&self, type_id: std::any::TypeId) -> Option<deebs::ColumnStorage> {
+                #(
+                    if type_id == std::any::TypeId::of::<#column_inner_tys>() {
+                        return Some(#column_storages);
+                    }
+                )*
+
+                None
+            }
+
+            fn column_index<T>(&self) -> Option<Box<dyn deebs::CellIndex<T>>>
+            where
+                T: 'static,
+            {
+                #(
+                    if std::any::TypeId::of::<T>() == std::any::TypeId::of::<#index_column_inner_tys>() {
+                        let index: Box<dyn deebs::CellIndex<#index_column_inner_tys>> =
+                            Box::new(#index_tys::default());
+                        return deebs::downcast_index(index);
+                    }
+                )*
+
+                None
+            }
+
+            #[allow(unused_variables)]
+            async fn update_views(&self, table: &__Tbl, type_ids: &[std::any::TypeId], keys: &[deebs::Key]) {
+                #(
+                    if <#view_inner_tys as deebs::Row<#view_lifetimes, __Tbl>>::inner_types()
+                        .iter()
+                        .any(|ty| type_ids.contains(ty))
+                    {
+                        self.#view_idents.update_keys(table, keys).await;
+                    }
+                )*
+            }
+
+            #[allow(unused_variables)]
+            async fn rebuild_views(&self, table: &__Tbl) {
+                #(
+                    self.#view_idents.update(table).await;
+                )*
+            }
+
+            fn column_infos() -> Vec<deebs::reflect::ColumnInfo<__Tbl>> {
+                #column_infos
+            }
+        }
+    };
+
+    // The `Borrow` derive of a table flattening this struct invokes the companion macro,
+    // which re-implements `Borrow` for each of this struct's columns, singletons and views
+    // on the table by forwarding to the flattened field.
+    // The macro shares this struct's name and path, so that it is imported alongside it.
+    let macro_ident = format_ident!("__deebs_sub_table_{}", ident);
+    let check_ident = format_ident!("__deebs_sub_table_check_{}", ident);
+    let borrow_tys = borrow_tys
+        .iter()
+        .map(|ty| borrow_type(ty, &lifetimes))
+        .collect::<Vec<_>>();
+    let lifetime_vars = lifetimes.iter().map(|lifetime| {
+        let dollar = Punct::new('$', Spacing::Alone);
+        quote!(#dollar #lifetime:lifetime)
+    });
+    let lifetime_args = lifetimes
+        .iter()
+        .map(|lifetime| syn::Lifetime {
+            apostrophe: Span::call_site(),
+            ident: lifetime.clone(),
+        })
+        .collect::<Vec<_>>();
+    let brackets = |tokens: TokenStream| Group::new(Delimiter::Bracket, tokens);
+    let check_args = [
+        brackets(quote!(<#(#lifetime_args,)*>)),
+        brackets(quote!(Check<#(#lifetime_args,)*>)),
+        brackets(quote!()),
+        brackets(quote!(0)),
+        brackets(quote!(#(#lifetime_args),*)),
+    ];
+
+    let companion = quote! {
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #macro_ident {
+            (
+                [$($__impl_generics:tt)*]
+                [$($__self_ty:tt)*]
+                [$($__where_clause:tt)*]
+                [$__field:tt]
+                [#(#lifetime_vars),*]
+            ) => {
+                #(
+                    impl $($__impl_generics)* std::borrow::Borrow<#borrow_tys> for $($__self_ty)* $($__where_clause)* {
+                        fn borrow(&self) -> &#borrow_tys {
+                            std::borrow::Borrow::<#borrow_tys>::borrow(&self.$__field)
+                        }
+                    }
+                )*
+            };
+        }
+
+        #[doc(hidden)]
+        #vis use #macro_ident as #ident;
+
+        // Expand the companion macro away from this module's imports,
+        // so that field types it can't spell are reported here instead of where it is invoked.
+        #[doc(hidden)]
+        #[allow(dead_code, non_snake_case)]
+        mod #check_ident {
+            struct Check<#params>(super::#ident #ty_generics);
+
+            #macro_ident! { #(#check_args)* }
+        }
+    };
+
+    let serde = serde_impls(
+        &ident,
+        &generics,
+        &field_idents,
+        &serde_idents,
+        &serde_tys,
+        quote! {
+            #(
+                table.#serde_column_idents.get_mut().set_storage(#serde_column_storages);
+            )*
+        },
+    );
+
+    Ok(quote! {
+        #sub_table
+
+        #companion
+
+        #serde
+    })
+}
@@ -1,56 +1,591 @@
 use quote::quote;
-use syn::{GenericArgument, Ident, ItemStruct, PathArguments, Type};
+use syn::{
+    Attribute, Field, Fields, GenericArgument, GenericParam, Generics, Ident, ItemStruct, Meta,
+    NestedMeta, PathSegment, Type,
+};
 
-pub fn impl_table(input: ItemStruct) -> proc_macro::TokenStream {
+use crate::{angle_bracketed_args, last_segment};
+
+/// The kind of [`deebs::CellIndex`] requested by an `#[index]` attribute.
+enum IndexKind {
+    /// `#[index]`, backed by `deebs::OrdIndex`.
+    Ord,
+    /// `#[index(hash)]`, backed by `deebs::HashIndex`.
+    Hash,
+}
+
+fn parse_index(attr: &Attribute) -> syn::Result<IndexKind> {
+    match attr.parse_meta()? {
+        Meta::Path(_) => Ok(IndexKind::Ord),
+        Meta::List(list) => match list.nested.iter().collect::<Vec<_>>().as_slice() {
+            [NestedMeta::Meta(Meta::Path(path))] if path.is_ident("hash") => Ok(IndexKind::Hash),
+            _ => Err(syn::Error::new_spanned(
+                list,
+                "Index attribute must be #[index] or #[index(hash)].",
+            )),
+        },
+        meta @ Meta::NameValue(_) => Err(syn::Error::new_spanned(
+            meta,
+            "Index attribute must be #[index] or #[index(hash)].",
+        )),
+    }
+}
+
+/// Parse the single type argument of `Column<T>` or `View<T>`.
+pub(crate) fn parse_type_argument(segment: &PathSegment) -> syn::Result<Type> {
+    match angle_bracketed_args(segment)?.as_slice() {
+        [GenericArgument::Type(ty)] => Ok(ty.clone()),
+        _ => Err(syn::Error::new_spanned(
+            &segment.arguments,
+            format!("Expected `{}<T>`.", segment.ident),
+        )),
+    }
+}
+
+/// The fields of a `Table` or `SubTable` struct, classified by type.
+pub(crate) struct TableFields {
+    pub field_idents: Vec<Ident>,
+    pub key_allocator_ident: Option<Ident>,
+    pub column_idents: Vec<Ident>,
+    pub column_inner_tys: Vec<Type>,
+    pub column_dense: Vec<bool>,
+    pub dense_column_inner_tys: Vec<Type>,
+    pub index_column_inner_tys: Vec<Type>,
+    pub index_tys: Vec<proc_macro2::TokenStream>,
+    pub has_parent_column: bool,
+    pub has_children_column: bool,
+    pub view_idents: Vec<Ident>,
+    pub view_inner_tys: Vec<Type>,
+    pub flatten_idents: Vec<Ident>,
+    pub flatten_tys: Vec<Type>,
+    /// The type of every column, singleton and view field.
+    pub borrow_tys: Vec<Type>,
+    pub serde_idents: Vec<Ident>,
+    pub serde_tys: Vec<Type>,
+    pub serde_column_idents: Vec<Ident>,
+    pub serde_column_inner_tys: Vec<Type>,
+    pub serde_column_dense: Vec<bool>,
+}
+
+impl TableFields {
+    pub fn new(fields: Fields) -> syn::Result<Self> {
+        let mut table_fields = TableFields {
+            field_idents: vec![],
+            key_allocator_ident: None,
+            column_idents: vec![],
+            column_inner_tys: vec![],
+            column_dense: vec![],
+            dense_column_inner_tys: vec![],
+            index_column_inner_tys: vec![],
+            index_tys: vec![],
+            has_parent_column: false,
+            has_children_column: false,
+            view_idents: vec![],
+            view_inner_tys: vec![],
+            flatten_idents: vec![],
+            flatten_tys: vec![],
+            borrow_tys: vec![],
+            serde_idents: vec![],
+            serde_tys: vec![],
+            serde_column_idents: vec![],
+            serde_column_inner_tys: vec![],
+            serde_column_dense: vec![],
+        };
+
+        for field in fields {
+            table_fields.push(field)?;
+        }
+
+        Ok(table_fields)
+    }
+
+    fn push(&mut self, field: Field) -> syn::Result<()> {
+        let dense = field.attrs.iter().find(|attr| attr.path.is_ident("dense"));
+        let index_attr = field.attrs.iter().find(|attr| attr.path.is_ident("index"));
+        let index = index_attr.map(parse_index).transpose()?;
+        let flatten = field.attrs.iter().any(|attr| attr.path.is_ident("flatten"));
+        let skip_serde = field
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("skip_serde"));
+
+        let field_ident = match &field.ident {
+            Some(field_ident) => field_ident.clone(),
+            None => {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "Table struct fields must be named.",
+                ))
+            }
+        };
+        self.field_idents.push(field_ident.clone());
+
+        let segment = last_segment(&field.ty).ok_or_else(|| {
+            syn::Error::new_spanned(&field.ty, "Table struct fields must be paths.")
+        })?;
+
+        if flatten || segment.ident != "Column" {
+            if let Some(attr) = dense {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "#[dense] can only be applied to a Column.",
+                ));
+            }
+            if let Some(attr) = index_attr {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "#[index] can only be applied to a Column.",
+                ));
+            }
+        }
+
+        if flatten {
+            self.flatten_idents.push(field_ident.clone());
+            self.flatten_tys.push(field.ty.clone());
+            if !skip_serde {
+                self.serde_idents.push(field_ident);
+                self.serde_tys.push(field.ty);
+            }
+        } else if segment.ident == "KeyAllocator" {
+            self.key_allocator_ident = Some(field_ident.clone());
+            if !skip_serde {
+                self.serde_idents.push(field_ident);
+                self.serde_tys.push(field.ty);
+            }
+        } else if segment.ident == "Singleton" {
+            self.borrow_tys.push(field.ty.clone());
+            if !skip_serde {
+                self.serde_idents.push(field_ident);
+                self.serde_tys.push(field.ty);
+            }
+        } else if segment.ident == "Column" {
+            let ty = parse_type_argument(segment)?;
+            self.borrow_tys.push(field.ty.clone());
+            self.column_idents.push(field_ident.clone());
+            self.column_inner_tys.push(ty.clone());
+            self.column_dense.push(dense.is_some());
+            if let Some(inner) = last_segment(&ty) {
+                self.has_parent_column |= inner.ident == "Parent";
+                self.has_children_column |= inner.ident == "Children";
+            }
+            if dense.is_some() {
+                self.dense_column_inner_tys.push(ty.clone());
+            }
+            if let Some(index) = &index {
+                self.index_column_inner_tys.push(ty.clone());
+                self.index_tys.push(match index {
+                    IndexKind::Ord => quote!(deebs::OrdIndex::<#ty>),
+                    IndexKind::Hash => quote!(deebs::HashIndex::<#ty>),
+                });
+            }
+            if !skip_serde {
+                self.serde_idents.push(field_ident.clone());
+                self.serde_tys.push(field.ty.clone());
+                self.serde_column_idents.push(field_ident);
+                self.serde_column_inner_tys.push(ty);
+                self.serde_column_dense.push(dense.is_some());
+            }
+        } else if segment.ident == "View" {
+            self.borrow_tys.push(field.ty.clone());
+            self.view_inner_tys.push(parse_type_argument(segment)?);
+            self.view_idents.push(field_ident);
+        }
+
+        Ok(())
+    }
+}
+
+pub fn impl_table(input: ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
     let ident = input.ident;
     let generics = input.generics;
+    let reflect_egui = input
+        .attrs
+        .iter()
+        .any(|attr| attr.path.is_ident("reflect_egui"));
+
+    let TableFields {
+        field_idents,
+        key_allocator_ident,
+        column_idents,
+        column_inner_tys,
+        dense_column_inner_tys,
+        index_column_inner_tys,
+        index_tys,
+        has_parent_column,
+        has_children_column,
+        view_idents,
+        view_inner_tys,
+        flatten_idents,
+        flatten_tys,
+        serde_idents,
+        serde_tys,
+        serde_column_idents,
+        serde_column_inner_tys,
+        ..
+    } = TableFields::new(input.fields)?;
+
+    let key_allocator = if let Some(key_allocator_ident) = key_allocator_ident {
+        quote!(Some(&self.#key_allocator_ident))
+    } else {
+        quote!(None)
+    };
+
+    // Tables holding both hierarchy columns despawn each key's descendants along with it
+    let despawn_hierarchy = if has_parent_column && has_children_column {
+        quote!(let keys = deebs::despawn_hierarchy(self, keys).await;)
+    } else {
+        quote!()
+    };
+
+    // Columns, singletons, flattened fields and the key allocator are serialized unless marked
+    // `#[skip_serde]`. Every other field is defaulted on deserialization,
+    // so views are rebuilt afterwards by `Table::load`.
+    let serde = serde_impls(
+        &ident,
+        &generics,
+        &field_idents,
+        &serde_idents,
+        &serde_tys,
+        quote! {
+            #(
+                let storage = deebs::Table::column_storage(
+                    &table,
+                    std::any::TypeId::of::<#serde_column_inner_tys>(),
+                );
+                table.#serde_column_idents.get_mut().set_storage(storage);
+            )*
+        },
+    );
+
+    let column_infos = column_infos(&quote!(Self), &column_idents, &column_inner_tys);
+
+    // Widgets reference the `egui` and `antigen_egui` crates of the deriving crate,
+    // so they are only probed for tables marked `#[reflect_egui]`.
+    // Widget vtables are stored as registry extensions, which must be `'static`,
+    // so they are implemented for the table with every lifetime set to `'static`.
+    let egui = if reflect_egui {
+        let impl_params = generics
+            .params
+            .iter()
+            .filter(|param| !matches!(param, GenericParam::Lifetime(_)));
 
-    let mut view_inner_tys: Vec<Type> = vec![];
-    let mut view_idents: Vec<Ident> = vec![];
-
-    for field in input.fields {
-        if let Type::Path(path) = field.ty {
-            let first = path
-                .path
-                .segments
-                .first()
-                .expect("Path must have a first segment");
-
-            if first.ident == "View" {
-                if let PathArguments::AngleBracketed(args) = &first.arguments {
-                    if let GenericArgument::Type(ty) = &args.args[0] {
-                        let view_inner_ty = ty.clone();
-                        view_inner_tys.push(view_inner_ty);
-                        view_idents.push(field.ident.expect("Field must have an ident."));
-                    } else {
-                        panic!("First generic argument is not a type.")
-                    }
-                } else {
-                    panic!("Path arguments must be angle-bracketed.")
+        let static_args = generics.params.iter().map(|param| match param {
+            GenericParam::Lifetime(_) => quote!('static),
+            GenericParam::Type(ty) => {
+                let ident = &ty.ident;
+                quote!(#ident)
+            }
+            GenericParam::Const(konst) => {
+                let ident = &konst.ident;
+                quote!(#ident)
+            }
+        });
+
+        quote! {
+            impl<#(#impl_params,)*> antigen_egui::ReflectWidgets for #ident<#(#static_args,)*> {
+                fn cell_widgets() -> Vec<(std::any::TypeId, antigen_egui::CellWidget<Self>)> {
+                    use antigen_egui::{
+                        ProbeEditWidget as _, ProbeNoEditWidget as _, ProbeNoViewWidget as _,
+                        ProbeViewWidget as _,
+                    };
+
+                    vec![
+                        #(
+                            {
+                                let probe = deebs::reflect::Probe::<Self, #column_inner_tys>::new();
+                                (
+                                    std::any::TypeId::of::<#column_inner_tys>(),
+                                    antigen_egui::CellWidget {
+                                        view: (&probe).view_widget_fn(),
+                                        edit: (&probe).edit_widget_fn(),
+                                    },
+                                )
+                            },
+                        )*
+                    ]
                 }
             }
-        } else {
-            panic!("All fields must be paths.")
         }
-    }
+    } else {
+        quote!()
+    };
 
     let tokens = quote! {
         #[async_trait::async_trait]
         impl #generics deebs::Table for #ident #generics {
             type Key = deebs::Key;
-            
-            async fn update_views(&self, type_ids: &[std::any::TypeId]) {
+
+            fn key_allocator(&self) -> Option<&deebs::KeyAllocator> {
+                #key_allocator
+            }
+
+            async fn despawn_cells(&self, keys: Vec<deebs::Key>) -> deebs::DespawnedCells {
+                #despawn_hierarchy
+                #(
+                    let keys = deebs::SubTable::<Self>::despawn_hierarchy(&self.#flatten_idents, self, keys).await;
+                )*
+
+                // Every column is locked before any is written,
+                // so rows never see a key with only some of its cells removed
+                #[allow(unused_mut)]
+                let mut columns: Vec<(std::any::TypeId, deebs::LockDespawnFn<Self>)> = vec![
+                    #(
+                        (
+                            std::any::TypeId::of::<#column_inner_tys>(),
+                            deebs::lock_despawn::<Self, #column_inner_tys>,
+                        ),
+                    )*
+                ];
+                #(
+                    columns.extend(<#flatten_tys as deebs::SubTable<Self>>::despawn_columns());
+                )*
+                let (dropped, type_ids) = deebs::despawn_columns(self, columns, &keys).await;
+
+                if let Some(key_allocator) = deebs::Table::key_allocator(self) {
+                    for key in keys.iter() {
+                        key_allocator.free(key);
+                    }
+                }
+
+                deebs::DespawnedCells {
+                    dropped,
+                    keys,
+                    type_ids,
+                }
+            }
+
+            async fn notify_all_observers(&self) {
+                #(
+                    deebs::Table::notify_observers::<#column_inner_tys>(self).await;
+                )*
+                #(
+                    deebs::SubTable::<Self>::notify_all_observers(&self.#flatten_idents, self).await;
+                )*
+            }
+
+            fn column_storage(&self, type_id: std::any::TypeId) -> deebs::ColumnStorage {
+                #(
+                    if type_id == std::any::TypeId::of::<#dense_column_inner_tys>() {
+                        return deebs::ColumnStorage::Dense;
+                    }
+                )*
+                #(
+                    if let Some(storage) = deebs::SubTable::<Self>::column_storage(&self.#flatten_idents, type_id) {
+                        return storage;
+                    }
+                )*
+
+                deebs::ColumnStorage::Map
+            }
+
+            fn column_index<T>(&self) -> Option<Box<dyn deebs::CellIndex<T>>>
+            where
+                T: 'static,
+            {
+                #(
+                    if std::any::TypeId::of::<T>() == std::any::TypeId::of::<#index_column_inner_tys>() {
+                        let index: Box<dyn deebs::CellIndex<#index_column_inner_tys>> =
+                            Box::new(#index_tys::default());
+                        return deebs::downcast_index(index);
+                    }
+                )*
+                #(
+                    if let Some(index) = deebs::SubTable::<Self>::column_index::<T>(&self.#flatten_idents) {
+                        return Some(index);
+                    }
+                )*
+
+                None
+            }
+
+            async fn update_views(&self, type_ids: &[std::any::TypeId], keys: &[deebs::Key]) {
                 #(
                     if <#view_inner_tys as deebs::Row<Self>>::inner_types()
                         .iter()
                         .any(|ty| type_ids.contains(ty))
                     {
-                        self.#view_idents.update(self).await;
+                        self.#view_idents.update_keys(self, keys).await;
                     }
                 )*
+                #(
+                    deebs::SubTable::<Self>::update_views(&self.#flatten_idents, self, type_ids, keys).await;
+                )*
+            }
+
+            async fn rebuild_views(&self) {
+                #(
+                    self.#view_idents.update(self).await;
+                )*
+                #(
+                    deebs::SubTable::<Self>::rebuild_views(&self.#flatten_idents, self).await;
+                )*
+            }
+
+            fn column_registry() -> deebs::reflect::ColumnRegistry<Self> {
+                #[allow(unused_mut)]
+                let mut columns = #column_infos;
+                #(
+                    columns.extend(
+                        <#flatten_tys as deebs::SubTable<Self>>::column_infos()
+                            .into_iter()
+                            .map(|mut column| {
+                                column.flatten = Some(stringify!(#flatten_idents));
+                                column
+                            }),
+                    );
+                )*
+
+                deebs::reflect::ColumnRegistry::new(columns)
             }
         }
+
+        #serde
+
+        #egui
     };
 
-    tokens.into()
+    Ok(tokens)
+}
+
+/// Implement `Serialize` and `Deserialize` for `ident` through its `serde_idents` fields,
+/// defaulting the rest and running `finish` on the deserialized `table`.
+///
+/// The impls are only emitted if deebs' `serde` feature is enabled.
+pub(crate) fn serde_impls(
+    ident: &Ident,
+    generics: &Generics,
+    field_idents: &[Ident],
+    serde_idents: &[Ident],
+    serde_tys: &[Type],
+    finish: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let params = &generics.params;
+    let default_idents = field_idents
+        .iter()
+        .filter(|field_ident| !serde_idents.contains(field_ident))
+        .collect::<Vec<_>>();
+
+    quote! {
+        deebs::cfg_serde! {
+            const _: () = {
+                #[derive(deebs::serde::Serialize)]
+                #[serde(crate = "deebs::serde")]
+                struct SerializeTable<'__table, #params> {
+                    #(
+                        #serde_idents: &'__table #serde_tys,
+                    )*
+                    #[serde(skip)]
+                    __phantom: std::marker::PhantomData<&'__table #ident #generics>,
+                }
+
+                #[derive(deebs::serde::Deserialize)]
+                #[serde(crate = "deebs::serde")]
+                struct DeserializeTable #generics {
+                    #(
+                        #serde_idents: #serde_tys,
+                    )*
+                    #[serde(skip)]
+                    __phantom: std::marker::PhantomData<#ident #generics>,
+                }
+
+                impl #generics deebs::serde::Serialize for #ident #generics {
+                    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                    where
+                        S: deebs::serde::Serializer,
+                    {
+                        deebs::serde::Serialize::serialize(
+                            &SerializeTable {
+                                #(
+                                    #serde_idents: &self.#serde_idents,
+                                )*
+                                __phantom: std::marker::PhantomData,
+                            },
+                            serializer,
+                        )
+                    }
+                }
+
+                impl<'de, #params> deebs::serde::Deserialize<'de> for #ident #generics {
+                    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                    where
+                        D: deebs::serde::Deserializer<'de>,
+                    {
+                        let DeserializeTable { #(#serde_idents,)* .. } =
+                            <DeserializeTable as deebs::serde::Deserialize>::deserialize(deserializer)?;
+
+                        #[allow(unused_mut)]
+                        let mut table = #ident {
+                            #(
+                                #serde_idents,
+                            )*
+                            #(
+                                #default_idents: Default::default(),
+                            )*
+                        };
+
+                        #finish
+
+                        Ok(table)
+                    }
+                }
+            };
+        }
+    }
+}
+
+/// Build a `Vec<deebs::reflect::ColumnInfo<#table>>` describing each column.
+pub(crate) fn column_infos(
+    table: &proc_macro2::TokenStream,
+    column_idents: &[Ident],
+    column_inner_tys: &[Type],
+) -> proc_macro2::TokenStream {
+    // Optional vtables are selected per column by calling probe methods on `&Probe`,
+    // which resolve to `None` for cell types that lack the probed trait.
+    // Those behind a deebs feature are only probed if it is enabled.
+    let serde_probes = quote! {
+        deebs::cfg_serde! {
+            use deebs::reflect::{
+                ProbeDeserialize as _, ProbeNoDeserialize as _, ProbeNoSerialize as _,
+                ProbeSerialize as _,
+            };
+
+            column.serialize = (&probe).serialize_fn();
+            column.deserialize = (&probe).deserialize_fn();
+        }
+    };
+
+    let journal_probes = quote! {
+        deebs::cfg_journal! {
+            use deebs::reflect::{ProbeJournal as _, ProbeNoJournal as _};
+
+            column.journal = (&probe).journal_fns();
+        }
+    };
+
+    quote! {
+        vec![
+            #(
+                {
+                    use deebs::reflect::{
+                        ProbeClone as _, ProbeDebug as _, ProbeDisplay as _,
+                        ProbeNoClone as _, ProbeNoDebug as _, ProbeNoDisplay as _,
+                    };
+
+                    let probe = deebs::reflect::Probe::<#table, #column_inner_tys>::new();
+                    let mut column = deebs::reflect::ColumnInfo::new::<#column_inner_tys>(
+                        stringify!(#column_idents),
+                    );
+
+                    column.debug = (&probe).debug_fn();
+                    column.display = (&probe).display_fn();
+                    column.snapshot = (&probe).snapshot_fns();
+
+                    #serde_probes
+                    #journal_probes
+
+                    column
+                },
+            )*
+        ]
+    }
 }
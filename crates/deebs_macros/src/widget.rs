@@ -1,7 +1,7 @@
 use quote::quote;
 use syn::ItemStruct;
 
-pub fn impl_widget(input: ItemStruct) -> proc_macro::TokenStream {
+pub fn impl_widget(input: ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
     let ident = input.ident;
     
     let tokens = quote! {
@@ -12,5 +12,5 @@ pub fn impl_widget(input: ItemStruct) -> proc_macro::TokenStream {
         }
     };
 
-    tokens.into()
+    Ok(tokens)
 }
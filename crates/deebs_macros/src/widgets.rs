@@ -4,72 +4,79 @@ use syn::{ItemStruct};
 
 use crate::RowInput;
 
-pub fn impl_widgets(input: ItemStruct) -> proc_macro::TokenStream {
+pub fn impl_widgets(input: ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
     let RowInput {
         ident,
         generics,
         where_predicates,
-        all_view_names: _,
         concrete_view_names,
         concrete_view_names_plural: _,
         concrete_view_tys,
         concrete_view_inner_tys,
+        concrete_view_boxed,
         option_view_names,
         option_view_names_plural: _,
         option_view_tys,
         option_view_inner_tys,
-    } = RowInput::new(input);
-
-    assert!(
-        !concrete_view_tys.is_empty() || !option_view_tys.is_empty(),
-        "Row struct must have at least one view member."
-    );
+        option_view_boxed,
+        filter_view_names: _,
+        filter_view_names_plural: _,
+        filter_view_tys: _,
+    } = RowInput::new(input)?;
 
     let mut concrete_view_names_immut = vec![];
-    let mut concrete_view_tys_immut = vec![];
+    let mut concrete_view_derefs_immut = vec![];
     let mut concrete_view_inner_tys_immut = vec![];
 
     let mut concrete_view_names_mut = vec![];
-    let mut concrete_view_tys_mut = vec![];
+    let mut concrete_view_derefs_mut = vec![];
     let mut concrete_view_inner_tys_mut = vec![];
 
     let mut option_view_names_immut = vec![];
-    let mut option_view_tys_immut = vec![];
+    let mut option_view_derefs_immut = vec![];
     let mut option_view_inner_tys_immut = vec![];
 
     let mut option_view_names_mut = vec![];
-    let mut option_view_tys_mut = vec![];
+    let mut option_view_derefs_mut = vec![];
     let mut option_view_inner_tys_mut = vec![];
 
+    // Boxed views deref through the box before the cell
+    let deref = |boxed: bool| {
+        if boxed {
+            quote!(.deref().deref())
+        } else {
+            quote!(.deref())
+        }
+    };
+    let deref_mut = |boxed: bool| {
+        if boxed {
+            quote!(.deref_mut().deref_mut())
+        } else {
+            quote!(.deref_mut())
+        }
+    };
+
     for (i, ty) in concrete_view_tys.iter().enumerate() {
-        match ty.to_string().as_str() {
-            "ReadCell" => {
-                concrete_view_names_immut.push(&concrete_view_names[i]);
-                concrete_view_tys_immut.push(&concrete_view_tys[i]);
-                concrete_view_inner_tys_immut.push(&concrete_view_inner_tys[i]);
-            }
-            "WriteCell" => {
-                concrete_view_names_mut.push(&concrete_view_names[i]);
-                concrete_view_tys_mut.push(&concrete_view_tys[i]);
-                concrete_view_inner_tys_mut.push(&concrete_view_inner_tys[i]);
-            }
-            _ => panic!("Unrecognized type"),
+        if *ty == "ReadCell" {
+            concrete_view_names_immut.push(&concrete_view_names[i]);
+            concrete_view_derefs_immut.push(deref(concrete_view_boxed[i]));
+            concrete_view_inner_tys_immut.push(&concrete_view_inner_tys[i]);
+        } else {
+            concrete_view_names_mut.push(&concrete_view_names[i]);
+            concrete_view_derefs_mut.push(deref_mut(concrete_view_boxed[i]));
+            concrete_view_inner_tys_mut.push(&concrete_view_inner_tys[i]);
         }
     }
 
     for (i, ty) in option_view_tys.iter().enumerate() {
-        match ty.to_string().as_str() {
-            "ReadCell" => {
-                option_view_names_immut.push(&option_view_names[i]);
-                option_view_tys_immut.push(&option_view_tys[i]);
-                option_view_inner_tys_immut.push(&option_view_inner_tys[i]);
-            }
-            "WriteCell" => {
-                option_view_names_mut.push(&option_view_names[i]);
-                option_view_tys_mut.push(&option_view_tys[i]);
-                option_view_inner_tys_mut.push(&option_view_inner_tys[i]);
-            }
-            _ => panic!("Unrecognized type"),
+        if *ty == "ReadCell" {
+            option_view_names_immut.push(&option_view_names[i]);
+            option_view_derefs_immut.push(deref(option_view_boxed[i]));
+            option_view_inner_tys_immut.push(&option_view_inner_tys[i]);
+        } else {
+            option_view_names_mut.push(&option_view_names[i]);
+            option_view_derefs_mut.push(deref_mut(option_view_boxed[i]));
+            option_view_inner_tys_mut.push(&option_view_inner_tys[i]);
         }
     }
 
@@ -99,16 +106,16 @@ pub fn impl_widgets(input: ItemStruct) -> proc_macro::TokenStream {
                 let response = ui.interact(egui::Rect::NOTHING, egui::Id::new(stringify!(#ident)), egui::Sense::hover());
 
                 #(
-                    let response = response.union(ui.add(self.#concrete_view_names_immut.deref()));
+                    let response = response.union(ui.add(self.#concrete_view_names_immut #concrete_view_derefs_immut));
                 )*
 
                 #(
-                    let response = response.union(ui.add(self.#concrete_view_names_mut.deref_mut()));
+                    let response = response.union(ui.add(self.#concrete_view_names_mut #concrete_view_derefs_mut));
                 )*
 
                 #(
                     let response = response.union(if let Some(#option_view_names_immut) = &self.#option_view_names_immut {
-                        ui.add(#option_view_names_immut.deref())
+                        ui.add(#option_view_names_immut #option_view_derefs_immut)
                     } else {
                         ui.label("")
                     });
@@ -116,7 +123,7 @@ pub fn impl_widgets(input: ItemStruct) -> proc_macro::TokenStream {
 
                 #(
                     let response = response.union(if let Some(#option_view_names_mut) = &mut self.#option_view_names_mut {
-                        ui.add(#option_view_names_mut.deref_mut())
+                        ui.add(#option_view_names_mut #option_view_derefs_mut)
                     } else {
                         ui.label("")
                     });
@@ -127,5 +134,5 @@ pub fn impl_widgets(input: ItemStruct) -> proc_macro::TokenStream {
         }
     };
 
-    tokens.into()
+    Ok(tokens)
 }
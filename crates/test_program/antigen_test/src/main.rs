@@ -164,8 +164,9 @@ async fn game_tick(table: Arc<MyTable<'static>>) {
 #[cfg_attr(feature = "tracing", tracing::instrument(skip(table)))]
 async fn main_events_cleared(table: Arc<MyTable<'static>>) {
     serial!(
-        antigen_winit::run_close_window_system(table.clone()),
+        // Swap chains are dropped before their windows' entities are despawned
         antigen_winit_wgpu::drop_swap_chains(table.clone()),
+        antigen_winit::run_close_window_system(table.clone()),
         antigen_winit_wgpu::create_swap_chains(table.clone()),
         antigen_winit::run_window_event_system(table.clone()),
         antigen_egui::run_window_event_system(table.clone()),
@@ -1,26 +1,23 @@
 pub mod stdout_debugger;
 
-use antigen_crossterm::{CrosstermEvents, CrosstermKeyEvents};
+use antigen_crossterm::{crossterm::event::KeyEvent, CrosstermColumns};
 use antigen_egui::EguiUserInterface;
 use antigen_rendering::{AlwaysRedraw, OnCpu, OnGpu, RedrawFlag};
 use antigen_tracing::TraceRoot;
 use antigen_winit_wgpu::{WgpuSwapChains, WinitSwapChain};
 use borrow_derive::Borrow;
-use std::{fmt::Debug, sync::atomic::AtomicUsize};
+use std::fmt::Debug;
 
 use deebs::{
     macros::{CommonKeys, Map, Row, Table, Widgets},
-    Column, ReadCell, Singleton, View, WriteCell,
+    Children, Column, EventReader, KeyAllocator, Parent, ReadCell, Singleton, View, WriteCell,
 };
 
 use antigen_components::Label;
 use antigen_wgpu::{
-    WgpuCommandBuffers, WgpuDevice, WgpuInstance, WgpuQueue, WgpuRenderer, WgpuSwapChainFrame,
-    WgpuTextureView,
-};
-use antigen_winit::{
-    WinitMainEvents, WinitRedrawEvents, WinitWindow, WinitWindowEvents, WinitWindows,
+    WgpuColumns, WgpuCommandBuffers, WgpuRenderer, WgpuSwapChainFrame, WgpuTextureView,
 };
+use antigen_winit::{WinitColumns, WinitWindow};
 
 use hello_quads::{QuadPosition, QuadSize};
 use stdout_debugger::StdoutDebugger;
@@ -31,16 +28,17 @@ use antigen_log::LogRecords;
 #[derive(Debug, Default, Borrow, Table)]
 pub struct MyTable<'a> {
     // Primary Key
-    key_head: AtomicUsize,
-
-    // Singletons
-    crossterm_events: Singleton<CrosstermEvents>,
+    key_allocator: KeyAllocator,
 
-    winit_window_pool: Singleton<WinitWindows>,
-    winit_main_events: Singleton<WinitMainEvents>,
-    winit_redraw_events: Singleton<WinitRedrawEvents>,
+    // Integrations
+    #[flatten]
+    crossterm: CrosstermColumns,
+    #[flatten]
+    winit: WinitColumns,
+    #[flatten]
+    wgpu: WgpuColumns,
 
-    wgpu_instance: Singleton<WgpuInstance>,
+    // Singletons
     wgpu_swap_chain_pool: Singleton<WgpuSwapChains>,
     log_records: Singleton<LogRecords>,
     stdout_debug: Singleton<StdoutDebugger>,
@@ -52,34 +50,31 @@ pub struct MyTable<'a> {
 
     // Test
     bools: Column<bool>,
+    #[dense]
     ints: Column<i32>,
+    #[dense]
     floats: Column<f32>,
+    #[dense]
     chars: Column<char>,
     strs: Column<&'static str>,
     strings: Column<String>,
     quad_positions: Column<QuadPosition>,
     quad_sizes: Column<QuadSize>,
 
+    // hierarchy
+    parents: Column<Parent>,
+    children: Column<Children>,
+
     // crossterm
-    crossterm_key_events: Column<CrosstermKeyEvents>,
+    crossterm_key_event_readers: Column<EventReader<KeyEvent>>,
 
     // rendering
     redraw_flags: Column<RedrawFlag>,
     always_redraw_on_cpus: Column<AlwaysRedraw<OnCpu>>,
     always_redraw_on_gpus: Column<AlwaysRedraw<OnGpu>>,
 
-    // winit
-    winit_window_events: Column<WinitWindowEvents>,
-    windows: Column<WinitWindow>,
-
     // wgpu
-    wgpu_devices: Column<WgpuDevice>,
-    wgpu_queues: Column<WgpuQueue>,
     wgpu_swap_chains: Column<WinitSwapChain>,
-    wgpu_swap_chain_frames: Column<WgpuSwapChainFrame>,
-    wgpu_texture_views: Column<WgpuTextureView>,
-    wgpu_renderers: Column<WgpuRenderer>,
-    wgpu_command_buffers: Column<WgpuCommandBuffers>,
 
     // egui
     egui_user_interface: Column<EguiUserInterface<MyTable<'static>>>,
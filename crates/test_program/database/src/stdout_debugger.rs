@@ -16,7 +16,7 @@ use antigen_debug_stdout::{queue_lines, run_table_system, StdoutDebug};
 use antigen_log::LogRecords;
 use async_std::{self, io::prelude::WriteExt, sync::Arc};
 use async_trait;
-use deebs::{ReadSingleton, Table};
+use deebs::{EventReader, ReadSingleton, Table};
 use antigen_components::Label;
 
 #[derive(Debug, Copy, Clone)]
@@ -166,6 +166,7 @@ pub async fn assemble(table: Arc<MyTable<'static>>) {
     let key = table.next_key();
     table.insert(key, Label::from("stdout Debugger")).await;
     table.insert(key, CrosstermKeyEvents::default()).await;
+    table.insert(key, EventReader::<KeyEvent>::default()).await;
 }
 
 #[cfg_attr(feature = "tracing", tracing::instrument(skip(table)))]
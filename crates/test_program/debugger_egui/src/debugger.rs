@@ -1,4 +1,4 @@
-use std::ops::Deref;
+use std::{collections::BTreeSet, ops::Deref};
 
 use deebs::{
     BorrowView,  ReadView, Row, Table, 
@@ -7,7 +7,7 @@ use deebs::{
 use async_std::sync::Arc;
 use futures::StreamExt;
 use egui::{CtxRef};
-use antigen_egui::Widgets;
+use antigen_egui::{CellWidget, ReflectWidgets, Widgets};
 
 pub fn debugger<'a, R, T>(table: Arc<T>) -> impl Fn(&CtxRef) + Send + Sync
 where
@@ -44,3 +44,45 @@ where
         });
     }
 }
+
+/// Lists every cell of every key using the table's [`ColumnRegistry`](deebs::reflect::ColumnRegistry),
+/// without needing a [`Row`] struct naming each column.
+pub fn registry_debugger<T>(table: Arc<T>) -> impl Fn(&CtxRef) + Send + Sync
+where
+    T: Table + ReflectWidgets + Send + Sync,
+{
+    let mut registry = T::column_registry();
+    T::insert_cell_widgets(&mut registry);
+
+    move |context: &CtxRef| {
+        egui::CentralPanel::default().show(context, |ui| {
+            egui::ScrollArea::auto_sized().show(ui, |ui| {
+                let keys = registry
+                    .iter()
+                    .filter_map(|column| (column.keys)(&*table))
+                    .flatten()
+                    .collect::<BTreeSet<_>>();
+
+                for key in keys {
+                    egui::CollapsingHeader::new(key.to_string()).show(ui, |ui| {
+                        egui::Grid::new(key).striped(true).show(ui, |ui| {
+                            for column in registry.columns_of(&*table, &key) {
+                                ui.label(column.name().as_ref());
+                                match CellWidget::show(&*table, column, &key, ui) {
+                                    Ok(Some(_)) => (),
+                                    Ok(None) => {
+                                        ui.label(column.type_name);
+                                    }
+                                    Err(e) => {
+                                        ui.label(e.to_string());
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+                }
+            });
+        });
+    }
+}
@@ -9,7 +9,7 @@ use antigen_wgpu::{
 };
 use async_std::sync::Arc;
 use deebs::{BorrowView, Insert, Row};
-use std::{borrow::Borrow, ops::Deref, sync::atomic::AtomicUsize};
+use std::ops::Deref;
 
 use antigen_components::Label;
 use antigen_winit::{
@@ -23,7 +23,6 @@ use deebs::{BorrowColumn, Table};
 pub async fn assemble<'a, R, T>(table: Arc<T>)
 where
     T: Table
-        + Borrow<AtomicUsize>
         + BorrowColumn<Label>
         + BorrowColumn<WgpuDevice>
         + BorrowColumn<WinitWindow>
@@ -66,6 +66,7 @@ where
                             );
                         });
                     }
+                    QuadRow::notify_observers(table.deref()).await;
                 })
             });
         });
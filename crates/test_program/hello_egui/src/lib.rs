@@ -9,7 +9,7 @@ use antigen_wgpu::{
 };
 use async_std::sync::Arc;
 use deebs::Insert;
-use std::{borrow::Borrow, ops::Deref, sync::atomic::AtomicUsize};
+use std::ops::Deref;
 
 use antigen_winit::{
     winit::dpi::{PhysicalSize, Size},
@@ -26,7 +26,6 @@ use hello_quads::{QuadPosition, QuadSize};
 pub async fn assemble<T>(table: Arc<T>)
 where
     T: Table
-        + Borrow<AtomicUsize>
         + BorrowColumn<Label>
         + BorrowColumn<WgpuDevice>
         + BorrowColumn<WinitWindow>
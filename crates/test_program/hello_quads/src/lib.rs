@@ -1,9 +1,4 @@
-use std::{
-    borrow::{Borrow, Cow},
-    fmt::Display,
-    ops::Deref,
-    sync::atomic::AtomicUsize,
-};
+use std::{borrow::Cow, fmt::Display, ops::Deref};
 
 use antigen_components::Label;
 use antigen_rendering::{AlwaysRedraw, OnCpu, RedrawFlag};
@@ -143,7 +138,6 @@ pub struct DebugRow<'a> {
 pub async fn assemble<T>(table: Arc<T>)
 where
     T: Table
-        + Borrow<AtomicUsize>
         + BorrowColumn<Label>
         + BorrowColumn<WgpuDevice>
         + BorrowColumn<WgpuQueue>
@@ -1,10 +1,6 @@
 use antigen_winit_wgpu::WinitSwapChain;
 use futures::StreamExt;
-use std::{
-    borrow::{Borrow, Cow},
-    ops::Deref,
-    sync::atomic::AtomicUsize,
-};
+use std::{borrow::Cow, ops::Deref};
 
 use antigen_rendering::RedrawFlag;
 use antigen_wgpu::{
@@ -40,7 +36,6 @@ pub struct DebugRow<'a> {
 pub async fn assemble<T>(table: Arc<T>)
 where
     T: Table
-        + Borrow<AtomicUsize>
         + BorrowColumn<Label>
         + BorrowColumn<WgpuDevice>
         + BorrowColumn<WinitWindow>
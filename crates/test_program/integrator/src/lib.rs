@@ -1,6 +1,6 @@
 //! Simple numerical integrator
 
-use std::{borrow::Borrow, ops::Deref, sync::atomic::AtomicUsize};
+use std::ops::Deref;
 
 use antigen_components::Label;
 use async_std::sync::Arc;
@@ -52,12 +52,15 @@ impl IntegratorSystem {
             + Sync
             + 'a,
     {
-        let view = ReadView::new(table.deref()).await;
-        let query = view.keys().then(|key| DebugRow::new(table.deref(), key));
-        futures::pin_mut!(query);
-        while let Some(debug_row) = query.next().await {
-            debug_row.integrate();
+        {
+            let view = ReadView::new(table.deref()).await;
+            let query = view.keys().then(|key| DebugRow::new(table.deref(), key));
+            futures::pin_mut!(query);
+            while let Some(debug_row) = query.next().await {
+                debug_row.integrate();
+            }
         }
+        DebugRow::notify_observers(table.deref()).await;
     }
 }
 
@@ -65,7 +68,6 @@ impl IntegratorSystem {
 pub async fn assemble<T>(table: Arc<T>)
 where
     T: Table
-        + Borrow<AtomicUsize>
         + BorrowColumn<Label>
         + BorrowColumn<bool>
         + BorrowColumn<i32>
@@ -17,9 +17,8 @@ where
                     ui.label("args");
                     ui.end_row();
 
-                    async_std::task::block_on(async {
-                        let log_records = ReadSingleton::<LogRecords>::new(table.deref()).await;
-
+                    // The UI runs inside a render task, so skip the frame instead of waiting
+                    if let Some(log_records) = ReadSingleton::<LogRecords>::try_new(table.deref()) {
                         let mut last = None;
                         for log in log_records.iter() {
                             let response = ui.label(format!("{:?}", log.level));
@@ -30,7 +29,7 @@ where
                         if let Some(last) = last {
                             last.scroll_to_me(egui::Align::Max);
                         }
-                    });
+                    }
                 });
             });
         });
@@ -10,7 +10,7 @@ use antigen_wgpu::{
 };
 use async_std::sync::Arc;
 use deebs::{BorrowSingleton, Insert};
-use std::{borrow::Borrow, ops::Deref, sync::atomic::AtomicUsize};
+use std::ops::Deref;
 
 use antigen_components::Label;
 use antigen_winit::{
@@ -24,7 +24,6 @@ use deebs::{BorrowColumn, Table};
 pub async fn assemble<'a, T>(table: Arc<T>)
 where
     T: Table
-        + Borrow<AtomicUsize>
         + BorrowSingleton<TraceRoot>
         + BorrowColumn<Label>
         + BorrowColumn<WgpuDevice>
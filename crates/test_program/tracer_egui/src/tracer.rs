@@ -12,12 +12,12 @@ where
     move |context: &CtxRef| {
         egui::CentralPanel::default().show(context, |ui| {
             egui::ScrollArea::auto_sized().show(ui, |ui| {
-                async_std::task::block_on(async {
-                    let mut trace_root = WriteSingleton::<TraceRoot>::new(table.deref()).await;
+                // The UI runs inside a render task, so skip the frame instead of waiting
+                if let Some(mut trace_root) = WriteSingleton::<TraceRoot>::try_new(table.deref()) {
                     for tree in trace_root.children().values() {
                         tree.ui(ui);
                     }
-                });
+                }
             });
         });
     }